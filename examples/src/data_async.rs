@@ -0,0 +1,58 @@
+use futures_util::StreamExt;
+use simconnect_sdk::{AsyncSimConnect, Notification, SimConnectObject};
+
+/// A data structure that will be used to receive data from SimConnect.
+/// See the documentation of `SimConnectObject` for more information on the arguments of the `simconnect` attribute.
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+#[allow(dead_code)]
+struct AirplaneData {
+    #[simconnect(name = "TITLE")]
+    title: String,
+    #[simconnect(name = "CATEGORY")]
+    category: String,
+    #[simconnect(name = "PLANE LATITUDE", unit = "degrees")]
+    lat: f64,
+    #[simconnect(name = "PLANE LONGITUDE", unit = "degrees")]
+    lon: f64,
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+    alt: f64,
+    #[simconnect(name = "SIM ON GROUND")]
+    sim_on_ground: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = AsyncSimConnect::new("Receiving data asynchronously example")?;
+    let mut notifications_received = 0;
+
+    // No need to sleep between notifications, the stream waits until the next one is received.
+    while let Some(notification) = client.next().await {
+        match notification? {
+            Notification::Open => {
+                println!("Connection opened.");
+
+                // After the connection is successfully open, we register the struct
+                // Detaching the subscription keeps the struct registered until we unregister it
+                client.register_object::<AirplaneData>()?.detach();
+            }
+            Notification::Object(data) => {
+                if let Ok(airplane_data) = AirplaneData::try_from(&data) {
+                    println!("{airplane_data:?}");
+
+                    notifications_received += 1;
+
+                    // After we have received 10 notifications, we unregister the struct
+                    if notifications_received > 10 {
+                        client.unregister_object::<AirplaneData>()?;
+                        println!("Subscription stopped.");
+                        break;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
@@ -48,9 +48,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("Connection opened.");
 
                     // After the connection is successfully open, we register the structs
-                    client.register_object::<GpsData>()?;
-                    client.register_object::<AirplaneData>()?;
-                    client.register_object::<OnGround>()?;
+                    // Detaching the subscriptions keeps the structs registered for as long as the client is alive
+                    client.register_object::<GpsData>()?.detach();
+                    client.register_object::<AirplaneData>()?.detach();
+                    client.register_object::<OnGround>()?.detach();
                 }
                 Some(Notification::Object(data)) => {
                     if let Ok(gps_data) = GpsData::try_from(&data) {
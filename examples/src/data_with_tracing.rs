@@ -50,7 +50,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         info!("Open");
 
                         // After the connection is successfully open, we register the struct
-                        client.register_object::<AirplaneData>()?;
+                        // Detaching the subscription keeps the struct registered until we unregister it
+                        client.register_object::<AirplaneData>()?.detach();
                     }
                     Some(Notification::Object(data)) => {
                         if let Ok(airplane_data) = AirplaneData::try_from(&data) {
@@ -29,7 +29,10 @@ struct AirplaneDataCPacked {
 }
 
 impl SimConnectObjectExt for AirplaneData {
-    fn register(client: &mut SimConnect, id: u32) -> Result<(), SimConnectError> {
+    const PERIOD: Period = Period::Second;
+    const CONDITION: Condition = Condition::None;
+
+    fn define(client: &mut SimConnect, id: u32) -> Result<(), SimConnectError> {
         client.add_to_data_definition(id, "TITLE", "", DataType::String)?;
         client.add_to_data_definition(id, "CATEGORY", "", DataType::String)?;
         client.add_to_data_definition(id, "PLANE LATITUDE", "degrees", DataType::Float64)?;
@@ -37,8 +40,6 @@ impl SimConnectObjectExt for AirplaneData {
         client.add_to_data_definition(id, "PLANE ALTITUDE", "feet", DataType::Float64)?;
         client.add_to_data_definition(id, "SIM ON GROUND", "", DataType::Float64)?;
 
-        client.request_data_on_sim_object(id, Period::Second, Condition::None, 0)?;
-
         Ok(())
     }
 }
@@ -75,7 +76,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         println!("Connection opened.");
 
                         // After the connection is successfully open, we register the struct
-                        client.register_object::<AirplaneData>()?;
+                        // Detaching the subscription keeps the struct registered until we unregister it
+                        client.register_object::<AirplaneData>()?.detach();
                     }
                     Some(Notification::Object(data)) => {
                         if let Ok(gps_data) = AirplaneData::try_from(&data) {
@@ -0,0 +1,67 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use simconnect_sdk::{Dispatcher, SimConnect, SimConnectObject, SystemEvent, SystemEventRequest};
+
+/// A data structure that will be used to receive data from SimConnect.
+/// See the documentation of `SimConnectObject` for more information on the arguments of the `simconnect` attribute.
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+#[allow(dead_code)]
+struct AirplaneData {
+    #[simconnect(name = "TITLE")]
+    title: String,
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+    alt: f64,
+    #[simconnect(name = "SIM ON GROUND")]
+    sim_on_ground: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = SimConnect::new("Dispatcher example")?;
+
+    let notifications_received = Rc::new(Cell::new(0));
+
+    Dispatcher::new(client)
+        .on_open(|client| {
+            println!("Connection opened.");
+
+            // After the connection is successfully open, we register the struct and the system events we're interested in
+            // Detaching the subscription keeps the struct registered for as long as the client is alive
+            client.register_object::<AirplaneData>()?.detach();
+            client.subscribe_to_system_event(SystemEventRequest::Pause)?;
+
+            Ok(())
+        })
+        .on_object({
+            let notifications_received = notifications_received.clone();
+            move |_, airplane_data: AirplaneData| {
+                println!("{airplane_data:?}");
+
+                notifications_received.set(notifications_received.get() + 1);
+
+                Ok(())
+            }
+        })
+        .on_system_event(SystemEventRequest::Pause, |_, event| {
+            if let SystemEvent::Pause { state } = event {
+                println!("Pause: {state}.");
+            }
+
+            Ok(())
+        })
+        .on_exception(|_, exception| {
+            println!("Exception: {exception}.");
+
+            Ok(())
+        })
+        .on_quit(|_| {
+            println!("Simulator quit.");
+
+            Ok(())
+        })
+        // After we have received 10 notifications, we stop
+        .run_until(|| notifications_received.get() > 10)?;
+
+    Ok(())
+}
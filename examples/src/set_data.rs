@@ -0,0 +1,62 @@
+use simconnect_sdk::{Notification, ObjectId, SimConnect, SimConnectObject};
+
+/// A data structure that will be used to both receive data from SimConnect and set it.
+/// The `settable` flag allows the struct to be passed to `set_data_on_sim_object`.
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct FuelData {
+    #[simconnect(name = "FUEL TANK LEFT MAIN QUANTITY", unit = "gallons")]
+    left_main: f64,
+    #[simconnect(name = "FUEL TANK RIGHT MAIN QUANTITY", unit = "gallons")]
+    right_main: f64,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = SimConnect::new("Setting data example");
+
+    match client {
+        Ok(mut client) => {
+            let mut fuel_set = false;
+
+            loop {
+                let notification = client.get_next_dispatch()?;
+
+                match notification {
+                    Some(Notification::Open) => {
+                        println!("Connection opened.");
+
+                        // After the connection is successfully open, we register the struct
+                        // Detaching the subscription keeps the struct registered until we unregister it
+                        client.register_object::<FuelData>()?.detach();
+                    }
+                    Some(Notification::Object(data)) => {
+                        if let Ok(fuel_data) = FuelData::try_from(&data) {
+                            println!("{fuel_data:?}");
+
+                            // The first time we receive the fuel quantities, we set both main tanks to 20 gallons
+                            if !fuel_set {
+                                let fuel_data = FuelData {
+                                    left_main: 20.0,
+                                    right_main: 20.0,
+                                };
+                                client.set_data_on_sim_object(ObjectId::USER, &fuel_data)?;
+                                println!("Fuel set.");
+
+                                fuel_set = true;
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+
+                // sleep for about a frame to reduce CPU usage
+                std::thread::sleep(std::time::Duration::from_millis(16));
+            }
+        }
+        Err(e) => {
+            println!("Error: {e:?}")
+        }
+    }
+
+    Ok(())
+}
@@ -8,6 +8,8 @@ use crate::helpers::{get_attribute, mk_err};
 pub enum FieldType {
     Str,
     Int,
+    Float,
+    Flag,
 }
 
 pub struct FieldInfo {
@@ -48,6 +50,22 @@ pub static ALLOWED_CLASS_ATTRIBUTES: Lazy<HashMap<String, FieldInfo>> = Lazy::ne
             accepted_values: vec![],
         },
     );
+    map.insert(
+        "object_id".to_string(),
+        FieldInfo {
+            field_type: FieldType::Int,
+            required: false,
+            accepted_values: vec![],
+        },
+    );
+    map.insert(
+        "settable".to_string(),
+        FieldInfo {
+            field_type: FieldType::Flag,
+            required: false,
+            accepted_values: vec![],
+        },
+    );
 
     map
 });
@@ -71,10 +89,76 @@ pub static ALLOWED_FIELD_ATTRIBUTES: Lazy<HashMap<String, FieldInfo>> = Lazy::ne
             accepted_values: vec![],
         },
     );
+    map.insert(
+        "string_len".to_string(),
+        FieldInfo {
+            field_type: FieldType::Int,
+            required: false,
+            accepted_values: vec![
+                "8".to_string(),
+                "32".to_string(),
+                "64".to_string(),
+                "128".to_string(),
+                "256".to_string(),
+                "260".to_string(),
+            ],
+        },
+    );
+    map.insert(
+        "epsilon".to_string(),
+        FieldInfo {
+            field_type: FieldType::Float,
+            required: false,
+            accepted_values: vec![],
+        },
+    );
+    map.insert(
+        "count".to_string(),
+        FieldInfo {
+            field_type: FieldType::Int,
+            required: false,
+            accepted_values: vec![],
+        },
+    );
+    map.insert(
+        "variable".to_string(),
+        FieldInfo {
+            field_type: FieldType::Flag,
+            required: false,
+            accepted_values: vec![],
+        },
+    );
 
     map
 });
-pub const SUPPORTED_FIELD_TYPES: [&str; 3] = ["f64", "bool", "String"];
+// The error reported for an invalid field attribute.
+pub const FIELD_ERROR_MESSAGE: &str = "expected attribute `#[simconnect(name = \"...\", unit = \"...\", epsilon = ..., string_len = ..., count = ..., variable)]`. `unit`, `epsilon`, `string_len`, `count` and `variable` are optional.";
+pub const SUPPORTED_FIELD_TYPES: [&str; 12] = [
+    "f64",
+    "f32",
+    "i64",
+    "i32",
+    "u32",
+    "bool",
+    "String",
+    "LatLonAlt",
+    "XYZ",
+    "InitPosition",
+    "MarkerState",
+    "AiWaypoint",
+];
+// The field types that support `epsilon`.
+pub const NUMERIC_FIELD_TYPES: [&str; 5] = ["f64", "f32", "i64", "i32", "u32"];
+// The field types that can be used as the elements of an array field.
+pub const ARRAY_FIELD_TYPES: [&str; 6] = ["f64", "f32", "i64", "i32", "u32", "bool"];
+// The field types that implement `simconnect_sdk::DataStruct`.
+pub const DATA_STRUCT_FIELD_TYPES: [&str; 5] = [
+    "LatLonAlt",
+    "XYZ",
+    "InitPosition",
+    "MarkerState",
+    "AiWaypoint",
+];
 
 pub fn extract_attribute_properties(
     attr: &syn::Attribute,
@@ -127,7 +211,11 @@ pub fn extract_attribute_properties(
                                             syn::Lit::Int(lit)
                                                 if property.field_type == FieldType::Int =>
                                             {
-                                                let value = lit.to_string();
+                                                // all the integer properties end up as `u32`s or smaller
+                                                let value = match lit.base10_parse::<u32>() {
+                                                    Ok(value) => value.to_string(),
+                                                    Err(e) => return Err(e.to_compile_error()),
+                                                };
 
                                                 if !property.accepted_values.is_empty()
                                                     && !property.accepted_values.contains(&value)
@@ -146,6 +234,22 @@ pub fn extract_attribute_properties(
 
                                                 results.insert(ident_string, value);
                                             }
+                                            syn::Lit::Float(lit)
+                                                if property.field_type == FieldType::Float =>
+                                            {
+                                                results.insert(
+                                                    ident_string,
+                                                    lit.base10_digits().to_string(),
+                                                );
+                                            }
+                                            syn::Lit::Int(lit)
+                                                if property.field_type == FieldType::Float =>
+                                            {
+                                                results.insert(
+                                                    ident_string,
+                                                    lit.base10_digits().to_string(),
+                                                );
+                                            }
                                             lit => {
                                                 return Err(syn::Error::new_spanned(
                                                     lit,
@@ -167,6 +271,28 @@ pub fn extract_attribute_properties(
                             }
                         }
                     }
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                        let ident_string = match path.get_ident() {
+                            Some(ident) => ident.to_string(),
+                            None => {
+                                // no ident found
+                                return Err(mk_err(nvs.clone(), error_message));
+                            }
+                        };
+
+                        match allowed_properties.get(&ident_string) {
+                            Some(property)
+                                if property.field_type == FieldType::Flag
+                                    && !results.contains_key(&ident_string) =>
+                            {
+                                results.insert(ident_string, "true".to_string());
+                            }
+                            _ => {
+                                // found an unexpected or a duplicate flag
+                                return Err(mk_err(nvs.clone(), error_message));
+                            }
+                        }
+                    }
                     meta => {
                         // nvc.nested[] was not k = v
                         return Err(mk_err(meta, error_message));
@@ -200,16 +326,17 @@ pub fn parse_field_attributes(
 ) -> Result<(&proc_macro2::Ident, &syn::Path, HashMap<String, String>), proc_macro2::TokenStream> {
     let attr = get_attribute(&field.attrs);
 
-    let error_message =
-        "expected attribute `#[simconnect(name = \"...\", unit = \"...\")]`. `unit` is optional.";
-
     let name = field.ident.as_ref().expect("this should not happen");
-    let ty = &field.ty;
+    // array fields are validated against their element type
+    let (ty, array_len) = match &field.ty {
+        syn::Type::Array(syn::TypeArray { elem, len, .. }) => (elem.as_ref(), Some(len)),
+        ty => (ty, None),
+    };
 
     match attr {
         Some(attr) => {
             let properties =
-                extract_attribute_properties(attr, &ALLOWED_FIELD_ATTRIBUTES, error_message);
+                extract_attribute_properties(attr, &ALLOWED_FIELD_ATTRIBUTES, FIELD_ERROR_MESSAGE);
 
             match properties {
                 Ok(properties) => {
@@ -228,7 +355,34 @@ pub fn parse_field_attributes(
                                     if SUPPORTED_FIELD_TYPES
                                         .contains(&value.to_string().as_str()) =>
                                 {
-                                    Ok((name, path, properties))
+                                    let string_len = properties.contains_key("string_len");
+                                    let variable = properties.contains_key("variable");
+
+                                    if (string_len || variable) && value != "String" {
+                                        Err(mk_err(
+                                            attr,
+                                            "`string_len` and `variable` are only supported by `String` fields.",
+                                        ))
+                                    } else if string_len && variable {
+                                        Err(mk_err(
+                                            attr,
+                                            "`string_len` and `variable` are mutually exclusive.",
+                                        ))
+                                    } else if properties.contains_key("epsilon")
+                                        && !NUMERIC_FIELD_TYPES
+                                            .contains(&value.to_string().as_str())
+                                    {
+                                        Err(mk_err(
+                                            attr,
+                                            &format!(
+                                                r#"`epsilon` is only supported by fields of type ["{}"]."#,
+                                                NUMERIC_FIELD_TYPES.join(r#"", ""#)
+                                            ),
+                                        ))
+                                    } else {
+                                        validate_count(attr, value, array_len, &properties)
+                                            .map(|_| (name, path, properties))
+                                    }
                                 }
 
                                 _ => Err(mk_err(ty, error_message_supported_types)),
@@ -240,6 +394,55 @@ pub fn parse_field_attributes(
                 Err(e) => Err(e),
             }
         }
-        None => Err(mk_err(field, error_message)),
+        None => Err(mk_err(field, FIELD_ERROR_MESSAGE)),
+    }
+}
+
+/// Check that `count` is specified exactly for the array fields and that it matches the length of the array.
+fn validate_count(
+    attr: &syn::Attribute,
+    value: &proc_macro2::Ident,
+    array_len: Option<&syn::Expr>,
+    properties: &HashMap<String, String>,
+) -> Result<(), proc_macro2::TokenStream> {
+    let count = properties.get("count");
+
+    match (count, array_len) {
+        (None, None) => Ok(()),
+        (Some(_), None) => Err(mk_err(attr, "`count` is only supported by array fields.")),
+        (count, Some(len)) => {
+            let len = match len {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(lit),
+                    ..
+                }) => lit.base10_parse::<usize>().ok(),
+                _ => None,
+            };
+
+            if count.and_then(|count| count.parse::<usize>().ok()) != len || len.is_none() {
+                Err(mk_err(
+                    attr,
+                    "Array fields require a `count` matching the length of the array.",
+                ))
+            } else if !ARRAY_FIELD_TYPES.contains(&value.to_string().as_str()) {
+                Err(mk_err(
+                    attr,
+                    &format!(
+                        r#"Array field elements must be one of ["{}"]."#,
+                        ARRAY_FIELD_TYPES.join(r#"", ""#)
+                    ),
+                ))
+            } else if !properties
+                .get("name")
+                .is_some_and(|name| name.contains("{}"))
+            {
+                Err(mk_err(
+                    attr,
+                    "`count` requires a `{}` placeholder in `name`, which is replaced by the index.",
+                ))
+            } else {
+                Ok(())
+            }
+        }
     }
 }
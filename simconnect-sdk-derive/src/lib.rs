@@ -4,7 +4,10 @@ extern crate proc_macro;
 
 use std::collections::HashMap;
 
-use fields::{extract_attribute_properties, parse_field_attributes, ALLOWED_CLASS_ATTRIBUTES};
+use fields::{
+    extract_attribute_properties, parse_field_attributes, ALLOWED_CLASS_ATTRIBUTES,
+    DATA_STRUCT_FIELD_TYPES, FIELD_ERROR_MESSAGE,
+};
 use helpers::{get_attribute, mk_err};
 use proc_macro::TokenStream;
 use quote::quote;
@@ -19,10 +22,23 @@ mod helpers;
 /// * `period` - Required. One of `once`, `visual-frame`, `sim-frame`, `second`.
 /// * `condition` - Optional. Defaults to `none`. The condition of the data. Must be either `none` or `changed`. `changed` = Data will only be sent to the client when one or more values have changed. All the variables in a data definition will be returned if just one of the values changes.
 /// * `interval` - Optional. Defaults to `0`. The number of period events that should elapse between transmissions of the data. `0` means the data is transmitted every Period, `1` means that the data is transmitted every other Period, etc.
+/// * `object_id` - Optional. Defaults to the user aircraft. The ID of the simulation object the data is requested for.
+/// * `settable` - Optional. A flag without a value. Implements `SimConnectSettableObjectExt`, so that the struct can be written back through `SimConnect::set_data_on_sim_object`.
 ///
 /// # Field Arguments
 /// * `name` - Required. The name of the field. One from <https://www.prepar3d.com/SDKv5/sdk/references/variables/simulation_variables.html>.
 /// * `unit` - Optional. The unit of the field. For `string`s and `bool`s it should be left out or be empty string. For numeric fields it should be one from <https://www.prepar3d.com/SDKv5/sdk/references/variables/simulation_variables.html>.
+/// * `epsilon` - Optional. Defaults to `0.0`. Numeric fields only. With `condition = "changed"`, the amount the value has to change by before the data is sent again.
+/// * `string_len` - Optional. Defaults to `256`. `String` fields only. The size of the string buffer, including the NUL terminator. One of `8`, `32`, `64`, `128`, `256`, `260`.
+/// * `count` - Optional. Array fields only, where it is required and must match the length of the array. Expands an indexed simvar into one value per index, e.g. `name = "GENERAL ENG RPM:{}", count = 4` for a `[f64; 4]` field requests `GENERAL ENG RPM:1` to `GENERAL ENG RPM:4`. The `{}` placeholder in `name` is replaced by the index, starting from `1`.
+/// * `variable` - Optional. A flag without a value. `String` fields only. Requests the field as a variable-length string. The structs with `variable` fields are read one field at a time instead of being transmuted from a packed struct.
+///
+/// # Field Types
+/// `f64`, `f32`, `i64`, `i32`, `u32`, `bool` and `String`. Integer fields should use an integer unit, e.g. `number`, `Bco16` or `enum`.
+///
+/// Fixed-size arrays of `f64`, `f32`, `i64`, `i32`, `u32` and `bool`, together with `count`.
+///
+/// The structured data types `LatLonAlt`, `XYZ`, `InitPosition`, `MarkerState` and `AiWaypoint`, e.g. for `STRUCT LATLONALT` or `Initial Position`. The structs with structured fields are read one field at a time instead of being transmuted from a packed struct.
 ///
 /// # Example
 ///
@@ -30,20 +46,24 @@ mod helpers;
 /// # use simconnect_sdk_derive::SimConnectObject;
 ///
 /// #[derive(Debug, Clone, SimConnectObject)]
-/// #[simconnect(period = "second")]
+/// #[simconnect(period = "second", condition = "changed")]
 /// struct AirplaneData {
 ///     #[simconnect(name = "TITLE")]
 ///     title: String,
-///     #[simconnect(name = "CATEGORY")]
+///     #[simconnect(name = "CATEGORY", string_len = 32)]
 ///     category: String,
-///     #[simconnect(name = "PLANE LATITUDE", unit = "degrees")]
+///     #[simconnect(name = "PLANE LATITUDE", unit = "degrees", epsilon = 0.0001)]
 ///     lat: f64,
-///     #[simconnect(name = "PLANE LONGITUDE", unit = "degrees")]
+///     #[simconnect(name = "PLANE LONGITUDE", unit = "degrees", epsilon = 0.0001)]
 ///     lon: f64,
 ///     #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
 ///     alt: f64,
 ///     #[simconnect(name = "SIM ON GROUND", unit = "bool")]
 ///     sim_on_ground: bool,
+///     #[simconnect(name = "NUMBER OF ENGINES", unit = "number")]
+///     engines: i32,
+///     #[simconnect(name = "GENERAL ENG RPM:{}", unit = "rpm", count = 4)]
+///     rpm: [f64; 4],
 /// }
 /// ```
 #[proc_macro_derive(SimConnectObject, attributes(simconnect))]
@@ -80,60 +100,129 @@ pub fn derive(input: TokenStream) -> TokenStream {
         }
     }
 
+    // variable-length strings and data structs have no packed representation, so the data is read one field at a time
+    let sequential = parsed_fields
+        .iter()
+        .any(|(_, path, properties)| properties.contains_key("variable") || is_data_struct(path));
+
     // packed struct fields
     let packed_fields = parsed_fields
         .iter()
-        .map(|(ident, path, _)| build_packed_field(ident, path));
+        .map(|(ident, path, properties)| build_packed_field(ident, path, properties));
     let packed_fields_assignments = parsed_fields
         .iter()
-        .map(|(ident, path, _)| build_packed_field_assignment(ident, path));
+        .map(|(ident, path, properties)| build_packed_field_assignment(ident, path, properties));
+    let read_fields_assignments = parsed_fields
+        .iter()
+        .map(|(ident, path, properties)| build_read_field_assignment(ident, path, properties));
 
     // SC fields
     let sc_definition = parsed_fields
         .iter()
         .map(|(_, path, properties)| build_sc_definition(path, properties));
     let sc_request = build_sc_request(&ast);
+    let sc_settable =
+        build_sc_settable(&ast, name_ident, &packed_ident, &parsed_fields, sequential);
 
     // put everything together
+    let (packed_struct, try_from) = if sequential {
+        (
+            quote! {},
+            quote! {
+                let mut reader = value.try_reader::<#name_ident>()?;
+                Ok(#name_ident {
+                    #(#read_fields_assignments,)*
+                })
+            },
+        )
+    } else {
+        (
+            quote! {
+                #[repr(C, packed)]
+                struct #packed_ident {
+                    #(#packed_fields,)*
+                }
+            },
+            quote! {
+                let raw = value.try_transmute::<#name_ident, #packed_ident>()?;
+                Ok(#name_ident {
+                    #(#packed_fields_assignments,)*
+                })
+            },
+        )
+    };
+
     let expanded = quote! {
-        #[repr(C, packed)]
-        struct #packed_ident {
-            #(#packed_fields,)*
-        }
+        #packed_struct
         impl simconnect_sdk::SimConnectObjectExt for #name_ident {
-            fn register(client: &mut simconnect_sdk::SimConnect, id: u32) -> Result<(), simconnect_sdk::SimConnectError> {
+            #sc_request
+            fn define(client: &mut simconnect_sdk::SimConnect, id: u32) -> Result<(), simconnect_sdk::SimConnectError> {
                 #(#sc_definition)*
-                #sc_request
                 Ok(())
             }
         }
         impl TryFrom<&simconnect_sdk::Object> for #name_ident {
             type Error = simconnect_sdk::SimConnectError;
             fn try_from(value: &simconnect_sdk::Object) -> Result<Self, Self::Error> {
-                let raw = value.try_transmute::<#name_ident, #packed_ident>()?;
-                Ok(#name_ident {
-                    #(#packed_fields_assignments,)*
-                })
+                #try_from
             }
         }
+        #sc_settable
     };
 
     expanded.into()
 }
 
-fn build_packed_field(ident: &proc_macro2::Ident, path: &syn::Path) -> proc_macro2::TokenStream {
+/// Whether the field is one of the `simconnect_sdk::DataStruct` types.
+fn is_data_struct(path: &syn::Path) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|s| DATA_STRUCT_FIELD_TYPES.contains(&s.ident.to_string().as_str()))
+}
+
+/// The length of an array field.
+fn count(properties: &HashMap<String, String>) -> Option<usize> {
+    properties
+        .get("count")
+        .and_then(|count| count.parse::<usize>().ok())
+}
+
+/// The size of the buffer of a fixed size `String` field.
+fn string_len(properties: &HashMap<String, String>) -> usize {
+    properties
+        .get("string_len")
+        .and_then(|len| len.parse::<usize>().ok())
+        .unwrap_or(256)
+}
+
+fn build_packed_field(
+    ident: &proc_macro2::Ident,
+    path: &syn::Path,
+    properties: &HashMap<String, String>,
+) -> proc_macro2::TokenStream {
     let path_segments = &path.segments;
     let path_idents = path_segments.iter().map(|s| &s.ident);
 
-    match path_idents.last() {
+    let ty = match path_idents.last() {
         Some(value) if value == "String" => {
+            let len = string_len(properties);
+
+            quote! { [std::primitive::i8; #len] }
+        }
+        // SimConnect sends booleans as INT32
+        Some(value) if value == "bool" => quote! { std::primitive::i32 },
+        _ => quote! { #path },
+    };
+
+    match count(properties) {
+        Some(count) => {
             quote! {
-                #ident: [std::primitive::i8; 256]
+                #ident: [#ty; #count]
             }
         }
-        _ => {
+        None => {
             quote! {
-                #ident: #path
+                #ident: #ty
             }
         }
     }
@@ -142,6 +231,7 @@ fn build_packed_field(ident: &proc_macro2::Ident, path: &syn::Path) -> proc_macr
 fn build_packed_field_assignment(
     ident: &proc_macro2::Ident,
     path: &syn::Path,
+    properties: &HashMap<String, String>,
 ) -> proc_macro2::TokenStream {
     let path_segments = &path.segments;
     let path_idents = path_segments.iter().map(|s| &s.ident);
@@ -152,6 +242,16 @@ fn build_packed_field_assignment(
                 #ident: simconnect_sdk::fixed_c_str_to_string(&raw.#ident)
             }
         }
+        Some(value) if value == "bool" && count(properties).is_some() => {
+            quote! {
+                #ident: raw.#ident.map(|value| value != 0)
+            }
+        }
+        Some(value) if value == "bool" => {
+            quote! {
+                #ident: raw.#ident != 0
+            }
+        }
         _ => {
             quote! {
                 #ident: raw.#ident
@@ -160,48 +260,209 @@ fn build_packed_field_assignment(
     }
 }
 
-fn build_sc_definition(
+fn build_read_field_assignment(
+    ident: &proc_macro2::Ident,
     path: &syn::Path,
     properties: &HashMap<String, String>,
 ) -> proc_macro2::TokenStream {
-    let error_message =
-        "expected attribute `#[simconnect(name = \"...\", unit = \"...\")]`. `unit` is optional.";
-
     let path_segments = &path.segments;
     let path_idents = path_segments.iter().map(|s| &s.ident);
 
-    let name = properties.get("name").expect("this should never happen");
-    let unit = match properties.get("unit") {
-        Some(unit) => unit,
-        None => "",
+    let read = match path_idents.last() {
+        Some(_) if is_data_struct(path) => {
+            quote! { <#path as simconnect_sdk::DataStruct>::read(&mut reader)? }
+        }
+        Some(value) if value == "String" && properties.contains_key("variable") => {
+            quote! { reader.string_v()? }
+        }
+        Some(value) if value == "String" => {
+            let len = string_len(properties);
+
+            quote! { reader.fixed_string(#len)? }
+        }
+        Some(value) if value == "bool" => quote! { reader.i32()? != 0 },
+        Some(value) if value == "u32" => quote! { reader.i32()? as u32 },
+        Some(value) => quote! { reader.#value()? },
+        None => {
+            // this error is already caught in `parse_field_attributes`
+            return mk_err(path, "expected a field type");
+        }
     };
 
+    match count(properties) {
+        Some(count) => {
+            quote! {
+                #ident: {
+                    let mut values = [Default::default(); #count];
+                    for value in values.iter_mut() {
+                        *value = #read;
+                    }
+                    values
+                }
+            }
+        }
+        None => {
+            quote! {
+                #ident: #read
+            }
+        }
+    }
+}
+
+fn build_unpacked_field_assignment(
+    ident: &proc_macro2::Ident,
+    path: &syn::Path,
+    properties: &HashMap<String, String>,
+) -> proc_macro2::TokenStream {
+    let path_segments = &path.segments;
+    let path_idents = path_segments.iter().map(|s| &s.ident);
+
     match path_idents.last() {
-        Some(value) if value == "f64" => {
+        Some(value) if value == "String" => {
             quote! {
-                client.add_to_data_definition(id, #name, #unit, simconnect_sdk::DataType::Float64)?;
+                #ident: simconnect_sdk::string_to_fixed_c_str(&self.#ident)
+            }
+        }
+        Some(value) if value == "bool" && count(properties).is_some() => {
+            quote! {
+                #ident: self.#ident.map(std::primitive::i32::from)
             }
         }
         Some(value) if value == "bool" => {
             quote! {
-                client.add_to_data_definition(id, #name, #unit, simconnect_sdk::DataType::Bool)?;
+                #ident: std::primitive::i32::from(self.#ident)
+            }
+        }
+        _ => {
+            quote! {
+                #ident: self.#ident
+            }
+        }
+    }
+}
+
+fn build_written_field(
+    ident: &proc_macro2::Ident,
+    path: &syn::Path,
+    properties: &HashMap<String, String>,
+) -> proc_macro2::TokenStream {
+    let path_segments = &path.segments;
+    let path_idents = path_segments.iter().map(|s| &s.ident);
+
+    match path_idents.last() {
+        Some(_) if is_data_struct(path) => {
+            quote! {
+                simconnect_sdk::DataStruct::write(&self.#ident, &mut data);
+            }
+        }
+        Some(value) if value == "String" && properties.contains_key("variable") => {
+            quote! {
+                data.extend_from_slice(&simconnect_sdk::string_to_string_v(&self.#ident));
             }
         }
         Some(value) if value == "String" => {
+            let len = string_len(properties);
+
             quote! {
-                client.add_to_data_definition(id, #name, #unit, simconnect_sdk::DataType::String)?;
+                data.extend_from_slice(&simconnect_sdk::packed_to_bytes(
+                    &simconnect_sdk::string_to_fixed_c_str::<#len>(&self.#ident),
+                ));
+            }
+        }
+        Some(value) if value == "bool" && count(properties).is_some() => {
+            quote! {
+                for value in self.#ident {
+                    data.extend_from_slice(&std::primitive::i32::from(value).to_le_bytes());
+                }
+            }
+        }
+        Some(value) if value == "bool" => {
+            quote! {
+                data.extend_from_slice(&std::primitive::i32::from(self.#ident).to_le_bytes());
+            }
+        }
+        _ if count(properties).is_some() => {
+            quote! {
+                for value in self.#ident {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
             }
         }
+        _ => {
+            quote! {
+                data.extend_from_slice(&self.#ident.to_le_bytes());
+            }
+        }
+    }
+}
+
+fn build_sc_definition(
+    path: &syn::Path,
+    properties: &HashMap<String, String>,
+) -> proc_macro2::TokenStream {
+    let path_segments = &path.segments;
+    let path_idents = path_segments.iter().map(|s| &s.ident);
+
+    let name = properties.get("name").expect("this should never happen");
+    let unit = match properties.get("unit") {
+        Some(unit) => unit,
+        None => "",
+    };
+
+    let epsilon = properties
+        .get("epsilon")
+        .and_then(|epsilon| epsilon.parse::<f32>().ok())
+        .unwrap_or_default();
+
+    let data_type = match path_idents.last() {
+        Some(_) if is_data_struct(path) => {
+            quote! { <#path as simconnect_sdk::DataStruct>::DATA_TYPE }
+        }
+        Some(value) if value == "f64" => quote! { simconnect_sdk::DataType::Float64 },
+        Some(value) if value == "f32" => quote! { simconnect_sdk::DataType::Float32 },
+        Some(value) if value == "i64" => quote! { simconnect_sdk::DataType::Int64 },
+        // SimConnect has no unsigned types, `u32` fields are received as INT32
+        Some(value) if value == "i32" || value == "u32" => {
+            quote! { simconnect_sdk::DataType::Int32 }
+        }
+        Some(value) if value == "bool" => quote! { simconnect_sdk::DataType::Bool },
+        Some(value) if value == "String" && properties.contains_key("variable") => {
+            quote! { simconnect_sdk::DataType::StringV }
+        }
+        Some(value) if value == "String" => match string_len(properties) {
+            8 => quote! { simconnect_sdk::DataType::String8 },
+            32 => quote! { simconnect_sdk::DataType::String32 },
+            64 => quote! { simconnect_sdk::DataType::String64 },
+            128 => quote! { simconnect_sdk::DataType::String128 },
+            260 => quote! { simconnect_sdk::DataType::String260 },
+            _ => quote! { simconnect_sdk::DataType::String },
+        },
         _ => {
             // this error is already caught in `parse_field_attributes`
-            mk_err(path, error_message)
+            return mk_err(path, FIELD_ERROR_MESSAGE);
+        }
+    };
+
+    match count(properties) {
+        // indexed simvars are numbered from 1
+        Some(count) => {
+            let names = (1..=count).map(|index| name.replace("{}", &index.to_string()));
+
+            quote! {
+                #(client.add_to_data_definition_with_epsilon(id, #names, #unit, #data_type, #epsilon)?;)*
+            }
+        }
+        None => {
+            quote! {
+                client.add_to_data_definition_with_epsilon(id, #name, #unit, #data_type, #epsilon)?;
+            }
         }
     }
 }
 
 fn build_sc_request(ast: &DeriveInput) -> proc_macro2::TokenStream {
     let attr = get_attribute(&ast.attrs);
-    let error_message = "expected attribute `#[simconnect(period = \"...\", condition = \"...\", interval = ...)]`. `condition` and `interval` are optional.";
+    let error_message = "expected attribute `#[simconnect(period = \"...\", condition = \"...\", interval = ..., object_id = ..., settable)]`. `condition`, `interval`, `object_id` and `settable` are optional.";
 
     match attr {
         Some(attr) => {
@@ -251,8 +512,26 @@ fn build_sc_request(ast: &DeriveInput) -> proc_macro2::TokenStream {
                         None => 0,
                     };
 
+                    let object_id = match properties.get("object_id") {
+                        Some(object_id) => {
+                            let object_id = object_id.parse::<u32>().unwrap_or_default();
+
+                            quote! {
+                                simconnect_sdk::ObjectId(#object_id)
+                            }
+                        }
+                        None => {
+                            quote! {
+                                simconnect_sdk::ObjectId::USER
+                            }
+                        }
+                    };
+
                     quote! {
-                        client.request_data_on_sim_object(id, #period, #condition, #interval)?;
+                        const OBJECT_ID: simconnect_sdk::ObjectId = #object_id;
+                        const PERIOD: simconnect_sdk::Period = #period;
+                        const CONDITION: simconnect_sdk::Condition = #condition;
+                        const INTERVAL: u32 = #interval;
                     }
                 }
                 Err(e) => e,
@@ -261,3 +540,53 @@ fn build_sc_request(ast: &DeriveInput) -> proc_macro2::TokenStream {
         None => mk_err(ast, error_message),
     }
 }
+
+fn build_sc_settable(
+    ast: &DeriveInput,
+    name_ident: &proc_macro2::Ident,
+    packed_ident: &proc_macro2::Ident,
+    parsed_fields: &[(&proc_macro2::Ident, &syn::Path, HashMap<String, String>)],
+    sequential: bool,
+) -> proc_macro2::TokenStream {
+    let settable = get_attribute(&ast.attrs)
+        .and_then(|attr| {
+            // the errors are already reported by `build_sc_request`
+            extract_attribute_properties(attr, &ALLOWED_CLASS_ATTRIBUTES, "").ok()
+        })
+        .is_some_and(|properties| properties.contains_key("settable"));
+
+    if !settable {
+        return quote! {};
+    }
+
+    if sequential {
+        let written_fields = parsed_fields
+            .iter()
+            .map(|(ident, path, properties)| build_written_field(ident, path, properties));
+
+        return quote! {
+            impl simconnect_sdk::SimConnectSettableObjectExt for #name_ident {
+                fn to_data(&self) -> Vec<u8> {
+                    let mut data = Vec::new();
+                    #(#written_fields)*
+                    data
+                }
+            }
+        };
+    }
+
+    let unpacked_fields_assignments = parsed_fields
+        .iter()
+        .map(|(ident, path, properties)| build_unpacked_field_assignment(ident, path, properties));
+
+    quote! {
+        impl simconnect_sdk::SimConnectSettableObjectExt for #name_ident {
+            fn to_data(&self) -> Vec<u8> {
+                let raw = #packed_ident {
+                    #(#unpacked_fields_assignments,)*
+                };
+                simconnect_sdk::packed_to_bytes(&raw)
+            }
+        }
+    }
+}
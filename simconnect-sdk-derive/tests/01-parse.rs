@@ -37,4 +37,96 @@ struct Data4 {
     pub lon: f64,
 }
 
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", object_id = 1)]
+struct Data5 {
+    #[simconnect(name = "PLANE LATITUDE", unit = "degrees")]
+    pub lat: f64,
+    #[simconnect(name = "PLANE LONGITUDE", unit = "degrees")]
+    pub lon: f64,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct Data6 {
+    #[simconnect(name = "ATC ID")]
+    pub atc_id: String,
+    #[simconnect(name = "FUEL TANK CENTER QUANTITY", unit = "gallons")]
+    pub fuel: f64,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct Data7 {
+    #[simconnect(name = "NUMBER OF ENGINES", unit = "number")]
+    pub engines: i32,
+    #[simconnect(name = "TRANSPONDER CODE:1", unit = "Bco16")]
+    pub transponder: u32,
+    #[simconnect(name = "ZULU TIME", unit = "seconds")]
+    pub zulu_time: i64,
+    #[simconnect(name = "ENG RPM ANIMATION PERCENT:1", unit = "percent")]
+    pub rpm: f32,
+    #[simconnect(name = "SIM ON GROUND", unit = "bool")]
+    pub sim_on_ground: bool,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct Data8 {
+    #[simconnect(name = "ATC ID", string_len = 32)]
+    pub atc_id: String,
+    #[simconnect(name = "TITLE", string_len = 260)]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct Data9 {
+    #[simconnect(name = "TITLE", variable)]
+    pub title: String,
+    #[simconnect(name = "ATC ID", string_len = 8)]
+    pub atc_id: String,
+    #[simconnect(name = "NUMBER OF ENGINES", unit = "number")]
+    pub engines: u32,
+    #[simconnect(name = "SIM ON GROUND", unit = "bool")]
+    pub sim_on_ground: bool,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct Data10 {
+    #[simconnect(name = "STRUCT LATLONALT")]
+    pub position: simconnect_sdk::LatLonAlt,
+    #[simconnect(name = "STRUCT WORLDVELOCITY")]
+    pub velocity: simconnect_sdk::XYZ,
+    #[simconnect(name = "Initial Position")]
+    pub init_position: simconnect_sdk::InitPosition,
+    #[simconnect(name = "Tailhook")]
+    pub tailhook: simconnect_sdk::MarkerState,
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+    pub alt: f64,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", condition = "changed")]
+struct Data11 {
+    #[simconnect(name = "PLANE LATITUDE", unit = "degrees", epsilon = 0.0001)]
+    pub lat: f64,
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet", epsilon = 10)]
+    pub alt: f64,
+    #[simconnect(name = "TRANSPONDER CODE:1", unit = "Bco16", epsilon = 1.0)]
+    pub transponder: u32,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct Data12 {
+    #[simconnect(name = "GENERAL ENG RPM:{}", unit = "rpm", count = 4, epsilon = 10)]
+    pub rpm: [f64; 4],
+    #[simconnect(name = "ENG COMBUSTION:{}", unit = "bool", count = 2)]
+    pub combustion: [bool; 2],
+    #[simconnect(name = "FUELSYSTEM PUMP SWITCH:{}", unit = "enum", count = 3)]
+    pub pumps: [i32; 3],
+}
+
 fn main() {}
@@ -44,4 +44,12 @@ struct Data10 {}
 #[simconnect(period = "second", intervalX = 0)]
 struct Data11 {}
 
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable, settable)]
+struct Data12 {}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", periodX)]
+struct Data13 {}
+
 fn main() {}
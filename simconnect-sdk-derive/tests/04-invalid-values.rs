@@ -32,4 +32,86 @@ struct Data7 {
     pub lat: u64,
 }
 
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", object_id = "X")]
+struct Data8 {}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable = true)]
+struct Data9 {}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data10 {
+    #[simconnect(name = "TITLE", string_len = 100)]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data11 {
+    #[simconnect(name = "PLANE LATITUDE", unit = "degrees", string_len = 8)]
+    pub lat: f64,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data12 {
+    #[simconnect(name = "TITLE", string_len = 8, variable)]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data13 {
+    #[simconnect(name = "TITLE", epsilon = 0.5)]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data14 {
+    #[simconnect(name = "PLANE LATITUDE", unit = "degrees", epsilon = "X")]
+    pub lat: f64,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data15 {
+    #[simconnect(name = "GENERAL ENG RPM:{}", unit = "rpm")]
+    pub rpm: [f64; 4],
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data16 {
+    #[simconnect(name = "GENERAL ENG RPM:{}", unit = "rpm", count = 2)]
+    pub rpm: [f64; 4],
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data17 {
+    #[simconnect(name = "GENERAL ENG RPM:{}", unit = "rpm", count = 4)]
+    pub rpm: f64,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data18 {
+    #[simconnect(name = "GENERAL ENG RPM:1", unit = "rpm", count = 4)]
+    pub rpm: [f64; 4],
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct Data19 {
+    #[simconnect(name = "ATC ID:{}", count = 2)]
+    pub atc_id: [String; 2],
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", object_id = 5000000000)]
+struct Data20 {}
+
 fn main() {}
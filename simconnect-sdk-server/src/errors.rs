@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+/// SimConnect SDK Server error.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum ServerError {
+    /// I/O error.
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    /// The client sent something that doesn't follow the SimConnect protocol.
+    #[error("Protocol error: {0}")]
+    ProtocolError(String),
+    /// The script could not be parsed.
+    #[error("Script error on line {line}: {message}")]
+    ScriptError { line: usize, message: String },
+    /// The client didn't send the expected packet in time.
+    #[error("Timed out waiting for `{0}`")]
+    Timeout(String),
+    /// The client asked for something the server doesn't support.
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+}
@@ -0,0 +1,43 @@
+//! # SimConnect SDK Server
+//! A stand-in for the simulator that speaks the SimConnect network protocol over TCP.
+//!
+//! It accepts the connection of a SimConnect client, keeps track of the data definitions, requests and subscriptions the client makes
+//! and plays a [`Script`] of simulation variable updates, events and facilities, or a recording of a real simulator session.
+//! It is meant to test SimConnect clients, e.g. the ones using [`simconnect_sdk::TcpBackend`], without a running simulator.
+//!
+//! ```rust,no_run
+//! use simconnect_sdk_server::{Script, Server};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let server = Server::bind("127.0.0.1:5000")?;
+//!     let script = Script::parse(
+//!         r#"
+//!         expect RequestDataOnSimObject
+//!         set "PLANE ALTITUDE" 1500
+//!         tick
+//!         "#,
+//!     )?;
+//!
+//!     let mut session = server.accept()?;
+//!     session.run(&script)?;
+//!     session.serve()?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`simconnect_sdk::TcpBackend`]: https://docs.rs/simconnect-sdk/latest/simconnect_sdk/struct.TcpBackend.html
+
+mod errors;
+mod message;
+mod packet;
+mod script;
+mod server;
+mod session;
+
+pub use errors::ServerError;
+pub use message::{Facility, FacilityType, Message};
+pub use packet::Packet;
+pub use script::{Script, Step, Value};
+pub use server::Server;
+pub use session::{DataDatum, Session};
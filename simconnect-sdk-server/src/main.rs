@@ -0,0 +1,112 @@
+use std::fs::File;
+use std::io::BufWriter;
+
+use simconnect_sdk_server::{Script, Server};
+use tracing::{error, info};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+const USAGE: &str = "\
+Usage:
+    simconnect-sdk-server [--address <ADDRESS>] [SCRIPT]
+    simconnect-sdk-server [--address <ADDRESS>] --record <FILE> --simulator <ADDRESS>
+
+Options:
+    --address <ADDRESS>    The address to listen on [default: 127.0.0.1:5000]
+    --record <FILE>        Forward the connection to a simulator and record everything it sends to FILE
+    --simulator <ADDRESS>  The address of the simulator, used together with --record";
+
+struct Args {
+    address: String,
+    script: Option<String>,
+    record: Option<String>,
+    simulator: Option<String>,
+}
+
+/// Serve the clients one at a time, playing the same script for each one of them.
+///
+/// To see every packet and message, run:
+///
+/// ```bash
+/// RUST_LOG=trace cargo run --bin simconnect-sdk-server -- script.txt
+/// ```
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    setup_logging()?;
+
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    let server = Server::bind(&args.address)?;
+    info!("Listening on {}", server.local_addr()?);
+
+    if let Some(record) = args.record {
+        let simulator = args.simulator.ok_or("--record requires --simulator")?;
+
+        server.record(simulator, BufWriter::new(File::create(&record)?))?;
+        info!("Recording saved to {record}");
+
+        return Ok(());
+    }
+
+    let script = match args.script {
+        Some(path) => Script::load(path)?,
+        None => Script::default(),
+    };
+
+    loop {
+        let result = server.accept().and_then(|mut session| {
+            info!("Client `{}` connected", session.app_name());
+
+            session.run(&script)?;
+            session.serve()?;
+
+            info!("Client `{}` disconnected", session.app_name());
+
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            error!("{e}");
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut result = Args {
+        address: "127.0.0.1:5000".to_string(),
+        script: None,
+        record: None,
+        simulator: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{name} requires a value"));
+
+        match arg.as_str() {
+            "--address" => result.address = value("--address")?,
+            "--record" => result.record = Some(value("--record")?),
+            "--simulator" => result.simulator = Some(value("--simulator")?),
+            arg if arg.starts_with("--") => return Err(format!("unknown option `{arg}`")),
+            _ if result.script.is_none() => result.script = Some(arg),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+
+    Ok(result)
+}
+
+fn setup_logging() -> Result<(), Box<dyn std::error::Error>> {
+    let filter_layer = EnvFilter::try_from_default_env().or_else(|_| EnvFilter::try_new("info"))?;
+    let fmt_layer = fmt::layer().with_target(false);
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(fmt_layer)
+        .init();
+
+    Ok(())
+}
@@ -0,0 +1,406 @@
+// The version of the SimConnect network protocol.
+const PROTOCOL_VERSION: u32 = 4;
+// The size of the `SIMCONNECT_RECV` header.
+const RECV_HEADER_SIZE: usize = 12;
+
+// `SIMCONNECT_RECV_ID` values.
+const RECV_ID_EXCEPTION: u32 = 1;
+pub(crate) const RECV_ID_OPEN: u32 = 2;
+const RECV_ID_QUIT: u32 = 3;
+const RECV_ID_EVENT: u32 = 4;
+const RECV_ID_EVENT_FILENAME: u32 = 6;
+const RECV_ID_EVENT_FRAME: u32 = 7;
+const RECV_ID_SIMOBJECT_DATA: u32 = 8;
+const RECV_ID_SIMOBJECT_DATA_BYTYPE: u32 = 9;
+const RECV_ID_RESERVED_KEY: u32 = 13;
+const RECV_ID_SYSTEM_STATE: u32 = 15;
+const RECV_ID_AIRPORT_LIST: u32 = 18;
+const RECV_ID_VOR_LIST: u32 = 19;
+const RECV_ID_NDB_LIST: u32 = 20;
+const RECV_ID_WAYPOINT_LIST: u32 = 21;
+
+/// `SIMCONNECT_FACILITY_LIST_TYPE`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FacilityType {
+    Airport,
+    Waypoint,
+    NDB,
+    VOR,
+}
+
+impl FacilityType {
+    fn recv_id(self) -> u32 {
+        match self {
+            FacilityType::Airport => RECV_ID_AIRPORT_LIST,
+            FacilityType::Waypoint => RECV_ID_WAYPOINT_LIST,
+            FacilityType::NDB => RECV_ID_NDB_LIST,
+            FacilityType::VOR => RECV_ID_VOR_LIST,
+        }
+    }
+}
+
+impl TryFrom<i32> for FacilityType {
+    type Error = i32;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FacilityType::Airport),
+            1 => Ok(FacilityType::Waypoint),
+            2 => Ok(FacilityType::NDB),
+            3 => Ok(FacilityType::VOR),
+            value => Err(value),
+        }
+    }
+}
+
+/// A facility in the facilities cache of the server.
+///
+/// Only the fields that are relevant for the `facility_type` are sent to the client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Facility {
+    pub facility_type: FacilityType,
+    pub icao: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: f64,
+    /// Waypoints, NDB and VOR stations only.
+    pub mag_var: f32,
+    /// NDB and VOR stations only.
+    pub frequency: u32,
+    /// VOR stations only. `SIMCONNECT_RECV_ID_VOR_LIST_HAS_*` flags.
+    pub flags: u32,
+    /// VOR stations only.
+    pub localizer: f32,
+    /// VOR stations only.
+    pub glide_lat: f64,
+    /// VOR stations only.
+    pub glide_lon: f64,
+    /// VOR stations only.
+    pub glide_alt: f64,
+    /// VOR stations only.
+    pub glide_slope_angle: f32,
+}
+
+impl Facility {
+    /// A facility of the given type, with every field other than the ICAO zeroed.
+    pub fn new(facility_type: FacilityType, icao: &str) -> Self {
+        Self {
+            facility_type,
+            icao: icao.to_string(),
+            lat: 0.0,
+            lon: 0.0,
+            alt: 0.0,
+            mag_var: 0.0,
+            frequency: 0,
+            flags: 0,
+            localizer: 0.0,
+            glide_lat: 0.0,
+            glide_lon: 0.0,
+            glide_alt: 0.0,
+            glide_slope_angle: 0.0,
+        }
+    }
+
+    fn encode(&self, writer: &mut Writer) {
+        writer.string(&self.icao, 9);
+        writer.f64(self.lat);
+        writer.f64(self.lon);
+        writer.f64(self.alt);
+
+        if self.facility_type == FacilityType::Airport {
+            return;
+        }
+        writer.f32(self.mag_var);
+
+        if self.facility_type == FacilityType::Waypoint {
+            return;
+        }
+        writer.u32(self.frequency);
+
+        if self.facility_type == FacilityType::NDB {
+            return;
+        }
+        writer.u32(self.flags);
+        writer.f32(self.localizer);
+        writer.f64(self.glide_lat);
+        writer.f64(self.glide_lon);
+        writer.f64(self.glide_alt);
+        writer.f32(self.glide_slope_angle);
+    }
+}
+
+/// A message sent by the server, which the client receives as a `SIMCONNECT_RECV_*` structure.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Message {
+    /// `SIMCONNECT_RECV_OPEN`.
+    Open { app_name: String },
+    /// `SIMCONNECT_RECV_QUIT`.
+    Quit,
+    /// `SIMCONNECT_RECV_EXCEPTION`.
+    Exception {
+        exception: u32,
+        send_id: u32,
+        index: u32,
+    },
+    /// `SIMCONNECT_RECV_EVENT`.
+    Event {
+        group_id: u32,
+        event_id: u32,
+        data: u32,
+    },
+    /// `SIMCONNECT_RECV_EVENT_FILENAME`.
+    EventFilename {
+        group_id: u32,
+        event_id: u32,
+        file_name: String,
+    },
+    /// `SIMCONNECT_RECV_EVENT_FRAME`.
+    EventFrame {
+        group_id: u32,
+        event_id: u32,
+        frame_rate: f32,
+        sim_speed: f32,
+    },
+    /// `SIMCONNECT_RECV_RESERVED_KEY`.
+    ReservedKey { choice: String, key: String },
+    /// `SIMCONNECT_RECV_SYSTEM_STATE`.
+    SystemState {
+        request_id: u32,
+        integer: u32,
+        float: f32,
+        string: String,
+    },
+    /// `SIMCONNECT_RECV_SIMOBJECT_DATA`. `data` is the packed content of the data definition.
+    SimObjectData {
+        request_id: u32,
+        object_id: u32,
+        define_id: u32,
+        define_count: u32,
+        data: Vec<u8>,
+    },
+    /// `SIMCONNECT_RECV_SIMOBJECT_DATA_BYTYPE`. One message is sent for each of the `out_of` objects in range.
+    SimObjectDataByType {
+        request_id: u32,
+        object_id: u32,
+        define_id: u32,
+        define_count: u32,
+        entry_number: u32,
+        out_of: u32,
+        data: Vec<u8>,
+    },
+    /// `SIMCONNECT_RECV_AIRPORT_LIST`, `SIMCONNECT_RECV_WAYPOINT_LIST`, `SIMCONNECT_RECV_NDB_LIST` or `SIMCONNECT_RECV_VOR_LIST`.
+    FacilityList {
+        facility_type: FacilityType,
+        request_id: u32,
+        entry_number: u32,
+        out_of: u32,
+        facilities: Vec<Facility>,
+    },
+    /// A message that is sent as is. It must start with a `SIMCONNECT_RECV` header.
+    Raw(Vec<u8>),
+}
+
+impl Message {
+    /// Encode the message as it is sent over the wire.
+    pub fn encode(&self) -> Vec<u8> {
+        let (recv_id, mut writer) = match self {
+            Message::Open { app_name } => {
+                let mut writer = Writer::default();
+                writer.string(app_name, 256);
+                // application version, application build, SimConnect version and SimConnect build
+                for value in [11, 0, 0, 0, 11, 0, 0, 0] {
+                    writer.u32(value);
+                }
+                // reserved
+                writer.u32(0);
+                writer.u32(0);
+
+                (RECV_ID_OPEN, writer)
+            }
+            Message::Quit => (RECV_ID_QUIT, Writer::default()),
+            Message::Exception {
+                exception,
+                send_id,
+                index,
+            } => {
+                let mut writer = Writer::default();
+                writer.u32(*exception);
+                writer.u32(*send_id);
+                writer.u32(*index);
+
+                (RECV_ID_EXCEPTION, writer)
+            }
+            Message::Event {
+                group_id,
+                event_id,
+                data,
+            } => (RECV_ID_EVENT, event(*group_id, *event_id, *data)),
+            Message::EventFilename {
+                group_id,
+                event_id,
+                file_name,
+            } => {
+                let mut writer = event(*group_id, *event_id, 0);
+                writer.string(file_name, 260);
+                // flags
+                writer.u32(0);
+
+                (RECV_ID_EVENT_FILENAME, writer)
+            }
+            Message::EventFrame {
+                group_id,
+                event_id,
+                frame_rate,
+                sim_speed,
+            } => {
+                let mut writer = event(*group_id, *event_id, 0);
+                writer.f32(*frame_rate);
+                writer.f32(*sim_speed);
+
+                (RECV_ID_EVENT_FRAME, writer)
+            }
+            Message::ReservedKey { choice, key } => {
+                let mut writer = Writer::default();
+                writer.string(choice, 30);
+                writer.string(key, 50);
+
+                (RECV_ID_RESERVED_KEY, writer)
+            }
+            Message::SystemState {
+                request_id,
+                integer,
+                float,
+                string,
+            } => {
+                let mut writer = Writer::default();
+                writer.u32(*request_id);
+                writer.u32(*integer);
+                writer.f32(*float);
+                writer.string(string, 260);
+
+                (RECV_ID_SYSTEM_STATE, writer)
+            }
+            Message::SimObjectData {
+                request_id,
+                object_id,
+                define_id,
+                define_count,
+                data,
+            } => {
+                let mut writer = Writer::default();
+                writer.u32(*request_id);
+                writer.u32(*object_id);
+                writer.u32(*define_id);
+                // flags, entry number and out of
+                writer.u32(0);
+                writer.u32(1);
+                writer.u32(1);
+                writer.u32(*define_count);
+                writer.bytes(data);
+
+                (RECV_ID_SIMOBJECT_DATA, writer)
+            }
+            Message::SimObjectDataByType {
+                request_id,
+                object_id,
+                define_id,
+                define_count,
+                entry_number,
+                out_of,
+                data,
+            } => {
+                let mut writer = Writer::default();
+                writer.u32(*request_id);
+                writer.u32(*object_id);
+                writer.u32(*define_id);
+                // flags
+                writer.u32(0);
+                writer.u32(*entry_number);
+                writer.u32(*out_of);
+                writer.u32(*define_count);
+                writer.bytes(data);
+
+                (RECV_ID_SIMOBJECT_DATA_BYTYPE, writer)
+            }
+            Message::FacilityList {
+                facility_type,
+                request_id,
+                entry_number,
+                out_of,
+                facilities,
+            } => {
+                let mut writer = Writer::default();
+                writer.u32(*request_id);
+                writer.u32(facilities.len() as u32);
+                writer.u32(*entry_number);
+                writer.u32(*out_of);
+                for facility in facilities {
+                    facility.encode(&mut writer);
+                }
+
+                (facility_type.recv_id(), writer)
+            }
+            Message::Raw(data) => return data.clone(),
+        };
+
+        let size = (RECV_HEADER_SIZE + writer.data.len()) as u32;
+
+        let mut data = Vec::with_capacity(size as usize);
+        data.extend_from_slice(&size.to_le_bytes());
+        data.extend_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+        data.extend_from_slice(&recv_id.to_le_bytes());
+        data.append(&mut writer.data);
+
+        data
+    }
+}
+
+fn event(group_id: u32, event_id: u32, data: u32) -> Writer {
+    let mut writer = Writer::default();
+    writer.u32(group_id);
+    writer.u32(event_id);
+    writer.u32(data);
+
+    writer
+}
+
+/// Little-endian writer for the body of a message.
+#[derive(Debug, Default)]
+pub(crate) struct Writer {
+    pub(crate) data: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn i32(&mut self, value: i32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn i64(&mut self, value: i64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn f32(&mut self, value: f32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn f64(&mut self, value: f64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn bytes(&mut self, value: &[u8]) {
+        self.data.extend_from_slice(value);
+    }
+
+    /// Write a fixed size, NUL padded, string. Values that don't fit are truncated so that they are always NUL terminated.
+    pub(crate) fn string(&mut self, value: &str, size: usize) {
+        let bytes = &value.as_bytes()[..value.len().min(size - 1)];
+
+        self.data.extend_from_slice(bytes);
+        self.data.resize(self.data.len() + size - bytes.len(), 0);
+    }
+}
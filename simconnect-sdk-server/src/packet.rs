@@ -0,0 +1,478 @@
+use std::io::{ErrorKind, Read};
+
+use crate::ServerError;
+
+// Every packet sent by the client starts with a header made of the packet size, the protocol version, the packet type and the send ID.
+const HEADER_SIZE: usize = 16;
+// The packet type is the packet ID with the highest nibble set.
+const PACKET_TYPE_MASK: u32 = 0xF000_0000;
+// The size of the string arguments, which are sent as fixed size, NUL padded, strings.
+const STRING_SIZE: usize = 256;
+// The key choices of `SimConnect_RequestReservedKey` are sent as shorter strings.
+const KEY_CHOICE_SIZE: usize = 30;
+// Upper bound for the size of a single packet, to guard against a corrupt stream.
+const MAX_PACKET_SIZE: usize = 1024 * 1024;
+
+/// A packet sent by a SimConnect client.
+///
+/// Each variant maps 1:1 to a function of the SimConnect C API and carries the same arguments, minus the connection handle.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Packet {
+    Open {
+        app_name: String,
+    },
+    MapClientEventToSimEvent {
+        event_id: u32,
+        event_name: String,
+    },
+    TransmitClientEvent {
+        object_id: u32,
+        event_id: u32,
+        data: u32,
+        group_id: u32,
+        flags: u32,
+    },
+    AddClientEventToNotificationGroup {
+        group_id: u32,
+        event_id: u32,
+        maskable: bool,
+    },
+    RemoveClientEvent {
+        group_id: u32,
+        event_id: u32,
+    },
+    SetNotificationGroupPriority {
+        group_id: u32,
+        priority: u32,
+    },
+    ClearNotificationGroup {
+        group_id: u32,
+    },
+    RequestNotificationGroup {
+        group_id: u32,
+    },
+    MapInputEventToClientEvent {
+        group_id: u32,
+        input_definition: String,
+        down_event_id: u32,
+        down_value: u32,
+        up_event_id: u32,
+        up_value: u32,
+        maskable: bool,
+    },
+    SetInputGroupPriority {
+        group_id: u32,
+        priority: u32,
+    },
+    RemoveInputEvent {
+        group_id: u32,
+        input_definition: String,
+    },
+    ClearInputGroup {
+        group_id: u32,
+    },
+    SetInputGroupState {
+        group_id: u32,
+        state: u32,
+    },
+    RequestReservedKey {
+        event_id: u32,
+        key_choices: [String; 3],
+    },
+    AddToDataDefinition {
+        define_id: u32,
+        datum_name: String,
+        units_name: String,
+        datum_type: i32,
+        epsilon: f32,
+        datum_id: u32,
+    },
+    ClearDataDefinition {
+        define_id: u32,
+    },
+    RequestDataOnSimObject {
+        request_id: u32,
+        define_id: u32,
+        object_id: u32,
+        period: i32,
+        flags: u32,
+        origin: u32,
+        interval: u32,
+        limit: u32,
+    },
+    RequestDataOnSimObjectType {
+        request_id: u32,
+        define_id: u32,
+        radius_meters: u32,
+        object_type: i32,
+    },
+    SetDataOnSimObject {
+        define_id: u32,
+        object_id: u32,
+        flags: u32,
+        array_count: u32,
+        unit_size: u32,
+        data: Vec<u8>,
+    },
+    SubscribeToSystemEvent {
+        event_id: u32,
+        event_name: String,
+    },
+    UnsubscribeFromSystemEvent {
+        event_id: u32,
+    },
+    SetSystemEventState {
+        event_id: u32,
+        state: u32,
+    },
+    RequestSystemState {
+        request_id: u32,
+        state: String,
+    },
+    SetSystemState {
+        state: String,
+        integer: u32,
+        float: f32,
+        string: String,
+    },
+    SubscribeToFacilities {
+        facility_type: i32,
+        request_id: u32,
+    },
+    UnsubscribeToFacilities {
+        facility_type: i32,
+    },
+    RequestFacilitiesList {
+        facility_type: i32,
+        request_id: u32,
+    },
+    TransmitClientEventEx1 {
+        object_id: u32,
+        event_id: u32,
+        group_id: u32,
+        flags: u32,
+        data: [u32; 5],
+    },
+    /// A packet that is not understood by this server.
+    Unknown {
+        id: u32,
+        data: Vec<u8>,
+    },
+}
+
+impl Packet {
+    /// The name of the packet, which is also the name of the variant, e.g. `AddToDataDefinition`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Packet::Open { .. } => "Open",
+            Packet::MapClientEventToSimEvent { .. } => "MapClientEventToSimEvent",
+            Packet::TransmitClientEvent { .. } => "TransmitClientEvent",
+            Packet::AddClientEventToNotificationGroup { .. } => "AddClientEventToNotificationGroup",
+            Packet::RemoveClientEvent { .. } => "RemoveClientEvent",
+            Packet::SetNotificationGroupPriority { .. } => "SetNotificationGroupPriority",
+            Packet::ClearNotificationGroup { .. } => "ClearNotificationGroup",
+            Packet::RequestNotificationGroup { .. } => "RequestNotificationGroup",
+            Packet::AddToDataDefinition { .. } => "AddToDataDefinition",
+            Packet::ClearDataDefinition { .. } => "ClearDataDefinition",
+            Packet::RequestDataOnSimObject { .. } => "RequestDataOnSimObject",
+            Packet::RequestDataOnSimObjectType { .. } => "RequestDataOnSimObjectType",
+            Packet::SetDataOnSimObject { .. } => "SetDataOnSimObject",
+            Packet::MapInputEventToClientEvent { .. } => "MapInputEventToClientEvent",
+            Packet::SetInputGroupPriority { .. } => "SetInputGroupPriority",
+            Packet::RemoveInputEvent { .. } => "RemoveInputEvent",
+            Packet::ClearInputGroup { .. } => "ClearInputGroup",
+            Packet::SetInputGroupState { .. } => "SetInputGroupState",
+            Packet::RequestReservedKey { .. } => "RequestReservedKey",
+            Packet::SubscribeToSystemEvent { .. } => "SubscribeToSystemEvent",
+            Packet::UnsubscribeFromSystemEvent { .. } => "UnsubscribeFromSystemEvent",
+            Packet::SetSystemEventState { .. } => "SetSystemEventState",
+            Packet::RequestSystemState { .. } => "RequestSystemState",
+            Packet::SetSystemState { .. } => "SetSystemState",
+            Packet::SubscribeToFacilities { .. } => "SubscribeToFacilities",
+            Packet::UnsubscribeToFacilities { .. } => "UnsubscribeToFacilities",
+            Packet::RequestFacilitiesList { .. } => "RequestFacilitiesList",
+            Packet::TransmitClientEventEx1 { .. } => "TransmitClientEventEx1",
+            Packet::Unknown { .. } => "Unknown",
+        }
+    }
+
+    /// Read the next packet from `reader`. Returns `None` if the client has closed the connection.
+    pub fn read(reader: &mut impl Read) -> Result<Option<Self>, ServerError> {
+        let mut header = [0; HEADER_SIZE];
+
+        match reader.read_exact(&mut header) {
+            Ok(()) => (),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    ErrorKind::UnexpectedEof
+                        | ErrorKind::ConnectionReset
+                        | ErrorKind::ConnectionAborted
+                ) =>
+            {
+                return Ok(None)
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let mut header = Reader::new(&header);
+        let size = header.u32()? as usize;
+        let _version = header.u32()?;
+        let packet_type = header.u32()?;
+
+        if !(HEADER_SIZE..=MAX_PACKET_SIZE).contains(&size) {
+            return Err(ServerError::ProtocolError(format!(
+                "received a packet with an invalid size of {size} bytes"
+            )));
+        }
+        if packet_type & PACKET_TYPE_MASK != PACKET_TYPE_MASK {
+            return Err(ServerError::ProtocolError(format!(
+                "received a packet with an invalid type of {packet_type:#x}"
+            )));
+        }
+
+        let mut data = vec![0; size - HEADER_SIZE];
+        reader.read_exact(&mut data)?;
+
+        Self::decode(packet_type & !PACKET_TYPE_MASK, data).map(Some)
+    }
+
+    fn decode(id: u32, data: Vec<u8>) -> Result<Self, ServerError> {
+        let mut args = Reader::new(&data);
+
+        let packet = match id {
+            0x01 => Packet::Open {
+                app_name: args.string()?,
+            },
+            0x04 => Packet::MapClientEventToSimEvent {
+                event_id: args.u32()?,
+                event_name: args.string()?,
+            },
+            0x05 => Packet::TransmitClientEvent {
+                object_id: args.u32()?,
+                event_id: args.u32()?,
+                data: args.u32()?,
+                group_id: args.u32()?,
+                flags: args.u32()?,
+            },
+            0x06 => Packet::SetSystemEventState {
+                event_id: args.u32()?,
+                state: args.u32()?,
+            },
+            0x07 => Packet::AddClientEventToNotificationGroup {
+                group_id: args.u32()?,
+                event_id: args.u32()?,
+                maskable: args.u32()? != 0,
+            },
+            0x08 => Packet::RemoveClientEvent {
+                group_id: args.u32()?,
+                event_id: args.u32()?,
+            },
+            0x09 => Packet::SetNotificationGroupPriority {
+                group_id: args.u32()?,
+                priority: args.u32()?,
+            },
+            0x0A => Packet::ClearNotificationGroup {
+                group_id: args.u32()?,
+            },
+            0x0B => Packet::RequestNotificationGroup {
+                group_id: args.u32()?,
+            },
+            0x0C => Packet::AddToDataDefinition {
+                define_id: args.u32()?,
+                datum_name: args.string()?,
+                units_name: args.string()?,
+                datum_type: args.i32()?,
+                epsilon: args.f32()?,
+                datum_id: args.u32()?,
+            },
+            0x0D => Packet::ClearDataDefinition {
+                define_id: args.u32()?,
+            },
+            0x0E => Packet::RequestDataOnSimObject {
+                request_id: args.u32()?,
+                define_id: args.u32()?,
+                object_id: args.u32()?,
+                period: args.i32()?,
+                flags: args.u32()?,
+                origin: args.u32()?,
+                interval: args.u32()?,
+                limit: args.u32()?,
+            },
+            0x0F => Packet::RequestDataOnSimObjectType {
+                request_id: args.u32()?,
+                define_id: args.u32()?,
+                radius_meters: args.u32()?,
+                object_type: args.i32()?,
+            },
+            0x10 => Packet::SetDataOnSimObject {
+                define_id: args.u32()?,
+                object_id: args.u32()?,
+                flags: args.u32()?,
+                array_count: args.u32()?,
+                unit_size: args.u32()?,
+                data: args.rest().to_vec(),
+            },
+            0x11 => Packet::MapInputEventToClientEvent {
+                group_id: args.u32()?,
+                input_definition: args.string()?,
+                down_event_id: args.u32()?,
+                down_value: args.u32()?,
+                up_event_id: args.u32()?,
+                up_value: args.u32()?,
+                maskable: args.u32()? != 0,
+            },
+            0x12 => Packet::SetInputGroupPriority {
+                group_id: args.u32()?,
+                priority: args.u32()?,
+            },
+            0x13 => Packet::RemoveInputEvent {
+                group_id: args.u32()?,
+                input_definition: args.string()?,
+            },
+            0x14 => Packet::ClearInputGroup {
+                group_id: args.u32()?,
+            },
+            0x15 => Packet::SetInputGroupState {
+                group_id: args.u32()?,
+                state: args.u32()?,
+            },
+            0x16 => Packet::RequestReservedKey {
+                event_id: args.u32()?,
+                key_choices: [
+                    args.fixed_string(KEY_CHOICE_SIZE)?,
+                    args.fixed_string(KEY_CHOICE_SIZE)?,
+                    args.fixed_string(KEY_CHOICE_SIZE)?,
+                ],
+            },
+            0x17 => Packet::SubscribeToSystemEvent {
+                event_id: args.u32()?,
+                event_name: args.string()?,
+            },
+            0x18 => Packet::UnsubscribeFromSystemEvent {
+                event_id: args.u32()?,
+            },
+            0x35 => Packet::RequestSystemState {
+                request_id: args.u32()?,
+                state: args.string()?,
+            },
+            0x36 => Packet::SetSystemState {
+                state: args.string()?,
+                integer: args.u32()?,
+                float: args.f32()?,
+                string: args.string()?,
+            },
+            0x41 => Packet::SubscribeToFacilities {
+                facility_type: args.i32()?,
+                request_id: args.u32()?,
+            },
+            0x42 => Packet::UnsubscribeToFacilities {
+                facility_type: args.i32()?,
+            },
+            0x43 => Packet::RequestFacilitiesList {
+                facility_type: args.i32()?,
+                request_id: args.u32()?,
+            },
+            0x44 => Packet::TransmitClientEventEx1 {
+                object_id: args.u32()?,
+                event_id: args.u32()?,
+                group_id: args.u32()?,
+                flags: args.u32()?,
+                data: [
+                    args.u32()?,
+                    args.u32()?,
+                    args.u32()?,
+                    args.u32()?,
+                    args.u32()?,
+                ],
+            },
+            id => Packet::Unknown { id, data },
+        };
+
+        Ok(packet)
+    }
+}
+
+/// Little-endian reader over the arguments of a packet.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], ServerError> {
+        if self.data.len() < len {
+            return Err(ServerError::ProtocolError(
+                "received a packet that is too short for its arguments".to_string(),
+            ));
+        }
+
+        let (value, rest) = self.data.split_at(len);
+        self.data = rest;
+
+        Ok(value)
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, ServerError> {
+        Ok(u32::from_le_bytes(
+            self.take(4)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    pub(crate) fn i32(&mut self) -> Result<i32, ServerError> {
+        Ok(i32::from_le_bytes(
+            self.take(4)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    pub(crate) fn i64(&mut self) -> Result<i64, ServerError> {
+        Ok(i64::from_le_bytes(
+            self.take(8)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    pub(crate) fn f32(&mut self) -> Result<f32, ServerError> {
+        Ok(f32::from_le_bytes(
+            self.take(4)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    pub(crate) fn f64(&mut self) -> Result<f64, ServerError> {
+        Ok(f64::from_le_bytes(
+            self.take(8)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    fn string(&mut self) -> Result<String, ServerError> {
+        self.fixed_string(STRING_SIZE)
+    }
+
+    /// A NUL padded string of `len` bytes.
+    pub(crate) fn fixed_string(&mut self, len: usize) -> Result<String, ServerError> {
+        let value = self.take(len)?;
+        let end = value.iter().position(|c| *c == 0).unwrap_or(value.len());
+
+        Ok(String::from_utf8_lossy(&value[..end]).into_owned())
+    }
+
+    /// A NUL terminated string of variable length.
+    pub(crate) fn string_v(&mut self) -> Result<String, ServerError> {
+        let end = self.data.iter().position(|c| *c == 0).ok_or_else(|| {
+            ServerError::ProtocolError(
+                "received a variable-length string that is not NUL terminated".to_string(),
+            )
+        })?;
+        let value = self.take(end + 1)?;
+
+        Ok(String::from_utf8_lossy(&value[..end]).into_owned())
+    }
+
+    /// The remaining bytes.
+    pub(crate) fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.data)
+    }
+}
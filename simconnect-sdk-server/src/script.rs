@@ -0,0 +1,328 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::{Facility, FacilityType, Message, ServerError};
+
+/// The value of a simulation variable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    String(String),
+    /// The members of a struct data type, e.g. `STRUCT LATLONALT`, in the order they are packed.
+    List(Vec<Value>),
+}
+
+/// A single step of a [`Script`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Step {
+    /// Keep handling the packets sent by the client for the given duration.
+    Wait(Duration),
+    /// Keep handling the packets sent by the client until one with the given name is received. See [`crate::Packet::name`].
+    /// Packets that have been received since the previous `Expect`, e.g. while waiting, are matched too.
+    Expect(String),
+    /// Set the value of a simulation variable. Variables that have not been set are sent as zero.
+    Set { name: String, value: Value },
+    /// Send the data of every active periodic request.
+    Tick,
+    /// Send a system or client event, by name, to the client that has subscribed to it.
+    Event { name: String, data: u32 },
+    /// Send a system event that carries a file name to the client that has subscribed to it.
+    EventFilename { name: String, file_name: String },
+    /// Send a system event that carries the frame rate to the client that has subscribed to it.
+    EventFrame {
+        name: String,
+        frame_rate: f32,
+        sim_speed: f32,
+    },
+    /// Add a facility to the facilities cache and notify the subscribers.
+    AddFacility(Facility),
+    /// Send an exception.
+    Exception(u32),
+    /// Send a message as is.
+    Send(Message),
+    /// Send the messages of a recording as they are. A recording is a file made of `SIMCONNECT_RECV` messages, back to back.
+    Replay(PathBuf),
+    /// Send the quit message.
+    Quit,
+}
+
+/// A list of [`Step`]s that are played by a [`crate::Session`].
+///
+/// # Syntax
+/// One step per line. Empty lines and lines starting with `#` are ignored. Arguments that contain spaces must be double-quoted.
+///
+/// ```text
+/// wait <milliseconds>
+/// expect <packet name>
+/// set <simulation variable> <number or "string">...
+/// tick
+/// event <event name> [data]
+/// event-filename <event name> <file name>
+/// event-frame <event name> <frame rate> <sim speed>
+/// airport <icao> <lat> <lon> <alt>
+/// waypoint <icao> <lat> <lon> <alt> <mag var>
+/// ndb <icao> <lat> <lon> <alt> <mag var> <frequency>
+/// vor <icao> <lat> <lon> <alt> <mag var> <frequency> <flags> <localizer> <glide lat> <glide lon> <glide alt> <glide slope angle>
+/// exception <exception>
+/// replay <path>
+/// quit
+/// ```
+///
+/// # Example
+///
+/// ```text
+/// # wait for the client to register its data definition
+/// expect RequestDataOnSimObject
+/// set "PLANE ALTITUDE" 1500
+/// set TITLE "Cessna 172"
+/// set "STRUCT LATLONALT" 47.4 -122.3 433
+/// tick
+/// event Pause 1
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Script {
+    steps: Vec<Step>,
+}
+
+impl Script {
+    /// Create a script from a list of steps.
+    pub fn new(steps: Vec<Step>) -> Self {
+        Self { steps }
+    }
+
+    /// The steps of the script.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Load a script from a file. Relative `replay` paths are resolved against the directory of the script.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ServerError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        let mut script = Self::parse(&content)?;
+
+        if let Some(dir) = path.parent() {
+            for step in &mut script.steps {
+                if let Step::Replay(recording) = step {
+                    if recording.is_relative() {
+                        *recording = dir.join(&*recording);
+                    }
+                }
+            }
+        }
+
+        Ok(script)
+    }
+
+    /// Parse a script.
+    pub fn parse(content: &str) -> Result<Self, ServerError> {
+        let mut steps = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |message: String| ServerError::ScriptError {
+                line: line_number,
+                message,
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let args = tokenize(line).map_err(error)?;
+            steps.push(parse_step(&args).map_err(error)?);
+        }
+
+        Ok(Self { steps })
+    }
+}
+
+fn parse_step(args: &[Token]) -> Result<Step, String> {
+    let command = args[0].text.as_str();
+    let args = &args[1..];
+
+    let step = match command {
+        "wait" => {
+            expect_args(command, args, 1)?;
+            Step::Wait(Duration::from_millis(number(&args[0])?))
+        }
+        "expect" => {
+            expect_args(command, args, 1)?;
+            Step::Expect(args[0].text.clone())
+        }
+        "set" => {
+            if args.len() < 2 {
+                return Err("`set` expects at least 2 arguments".to_string());
+            }
+
+            let mut values = args[1..]
+                .iter()
+                .map(|arg| {
+                    if arg.quoted {
+                        Ok(Value::String(arg.text.clone()))
+                    } else {
+                        Ok(Value::Number(number(arg)?))
+                    }
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+            // more than one value sets the members of a struct data type
+            let value = if values.len() == 1 {
+                values.remove(0)
+            } else {
+                Value::List(values)
+            };
+
+            Step::Set {
+                name: args[0].text.clone(),
+                value,
+            }
+        }
+        "tick" => {
+            expect_args(command, args, 0)?;
+            Step::Tick
+        }
+        "event" => {
+            if args.len() != 1 && args.len() != 2 {
+                return Err("`event` expects 1 or 2 arguments".to_string());
+            }
+
+            Step::Event {
+                name: args[0].text.clone(),
+                data: args.get(1).map(number).transpose()?.unwrap_or_default(),
+            }
+        }
+        "event-filename" => {
+            expect_args(command, args, 2)?;
+            Step::EventFilename {
+                name: args[0].text.clone(),
+                file_name: args[1].text.clone(),
+            }
+        }
+        "event-frame" => {
+            expect_args(command, args, 3)?;
+            Step::EventFrame {
+                name: args[0].text.clone(),
+                frame_rate: number(&args[1])?,
+                sim_speed: number(&args[2])?,
+            }
+        }
+        "airport" => {
+            expect_args(command, args, 4)?;
+            Step::AddFacility(facility(FacilityType::Airport, args)?)
+        }
+        "waypoint" => {
+            expect_args(command, args, 5)?;
+            Step::AddFacility(facility(FacilityType::Waypoint, args)?)
+        }
+        "ndb" => {
+            expect_args(command, args, 6)?;
+            Step::AddFacility(facility(FacilityType::NDB, args)?)
+        }
+        "vor" => {
+            expect_args(command, args, 12)?;
+            Step::AddFacility(facility(FacilityType::VOR, args)?)
+        }
+        "exception" => {
+            expect_args(command, args, 1)?;
+            Step::Exception(number(&args[0])?)
+        }
+        "replay" => {
+            expect_args(command, args, 1)?;
+            Step::Replay(PathBuf::from(&args[0].text))
+        }
+        "quit" => {
+            expect_args(command, args, 0)?;
+            Step::Quit
+        }
+        command => return Err(format!("unknown command `{command}`")),
+    };
+
+    Ok(step)
+}
+
+fn facility(facility_type: FacilityType, args: &[Token]) -> Result<Facility, String> {
+    let mut facility = Facility::new(facility_type, &args[0].text);
+    facility.lat = number(&args[1])?;
+    facility.lon = number(&args[2])?;
+    facility.alt = number(&args[3])?;
+
+    if facility_type != FacilityType::Airport {
+        facility.mag_var = number(&args[4])?;
+    }
+    if facility_type == FacilityType::NDB || facility_type == FacilityType::VOR {
+        facility.frequency = number(&args[5])?;
+    }
+    if facility_type == FacilityType::VOR {
+        facility.flags = number(&args[6])?;
+        facility.localizer = number(&args[7])?;
+        facility.glide_lat = number(&args[8])?;
+        facility.glide_lon = number(&args[9])?;
+        facility.glide_alt = number(&args[10])?;
+        facility.glide_slope_angle = number(&args[11])?;
+    }
+
+    Ok(facility)
+}
+
+fn expect_args(command: &str, args: &[Token], count: usize) -> Result<(), String> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{command}` expects {count} argument(s), got {}",
+            args.len()
+        ))
+    }
+}
+
+fn number<T: std::str::FromStr>(arg: &Token) -> Result<T, String> {
+    arg.text
+        .parse()
+        .map_err(|_| format!("`{}` is not a valid number", arg.text))
+}
+
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+/// Split a line into whitespace separated tokens. Double-quoted tokens can contain whitespace.
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        if c == '"' {
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => text.push(c),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+
+            tokens.push(Token { text, quoted: true });
+        } else {
+            let mut text = c.to_string();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                text.push(c);
+            }
+
+            tokens.push(Token {
+                text,
+                quoted: false,
+            });
+        }
+    }
+
+    Ok(tokens)
+}
@@ -0,0 +1,90 @@
+use std::io::{Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+use crate::{ServerError, Session};
+
+/// A stand-in for the simulator, listening for SimConnect clients over TCP.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use simconnect_sdk_server::{Script, Server};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let server = Server::bind("127.0.0.1:5000")?;
+///     let script = Script::load("script.txt")?;
+///
+///     let mut session = server.accept()?;
+///     session.run(&script)?;
+///     session.serve()?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Server {
+    listener: TcpListener,
+}
+
+impl Server {
+    /// Start listening on `addr`. Use port `0` to let the OS pick a free port.
+    pub fn bind(addr: impl ToSocketAddrs) -> Result<Self, ServerError> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+        })
+    }
+
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr, ServerError> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Wait for the next client to connect and open the connection.
+    pub fn accept(&self) -> Result<Session, ServerError> {
+        let (stream, addr) = self.listener.accept()?;
+        tracing::debug!("Accepted connection from {addr}");
+
+        stream.set_nodelay(true)?;
+
+        Session::open(stream)
+    }
+
+    /// Wait for the next client to connect and forward the connection to the simulator listening on `simulator`.
+    /// Every message sent by the simulator is also written to `output`, so that it can be replayed later on.
+    pub fn record(
+        &self,
+        simulator: impl ToSocketAddrs,
+        mut output: impl Write,
+    ) -> Result<(), ServerError> {
+        let (client, addr) = self.listener.accept()?;
+        tracing::debug!("Accepted connection from {addr}");
+
+        let simulator = TcpStream::connect(simulator)?;
+
+        let mut from_client = client.try_clone()?;
+        let mut to_simulator = simulator.try_clone()?;
+        let forward = std::thread::spawn(move || {
+            let _ = std::io::copy(&mut from_client, &mut to_simulator);
+            let _ = to_simulator.shutdown(Shutdown::Write);
+        });
+
+        let mut from_simulator = simulator;
+        let mut to_client = client;
+        let mut buffer = [0; 8192];
+        loop {
+            let len = from_simulator.read(&mut buffer)?;
+            if len == 0 {
+                break;
+            }
+
+            to_client.write_all(&buffer[..len])?;
+            output.write_all(&buffer[..len])?;
+        }
+
+        output.flush()?;
+        let _ = to_client.shutdown(Shutdown::Both);
+        let _ = forward.join();
+
+        Ok(())
+    }
+}
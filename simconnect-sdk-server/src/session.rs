@@ -0,0 +1,855 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, trace, warn};
+
+use crate::message::{Writer, RECV_ID_OPEN};
+use crate::packet::Reader;
+use crate::{Facility, FacilityType, Message, Packet, Script, ServerError, Step, Value};
+
+// How long the server waits for a packet named by a `Step::Expect`, or for the `Open` packet of a new client.
+const EXPECT_TIMEOUT: Duration = Duration::from_secs(10);
+// The group ID SimConnect uses for system events, `SIMCONNECT_UNUSED`.
+const UNUSED_GROUP_ID: u32 = u32::MAX;
+
+// `SIMCONNECT_PERIOD` values.
+const PERIOD_NEVER: i32 = 0;
+const PERIOD_ONCE: i32 = 1;
+// `SIMCONNECT_DATA_REQUEST_FLAG_CHANGED`.
+const DATA_REQUEST_FLAG_CHANGED: u32 = 0x01;
+// `SIMCONNECT_OBJECT_ID_USER`.
+const OBJECT_ID_USER: u32 = 0;
+// `SIMCONNECT_STATE_OFF`.
+const SYSTEM_EVENT_STATE_OFF: u32 = 0;
+
+/// A single entry of a data definition registered by the client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataDatum {
+    /// The name of the simulation variable.
+    pub name: String,
+    /// The unit of the simulation variable.
+    pub unit: String,
+    /// The raw `SIMCONNECT_DATATYPE` of the simulation variable.
+    pub datum_type: i32,
+    /// The epsilon of the simulation variable.
+    pub epsilon: f32,
+}
+
+#[derive(Debug)]
+struct DataRequest {
+    define_id: u32,
+    object_id: u32,
+    flags: u32,
+    interval: u32,
+    ticks: u32,
+    last_data: Option<Vec<u8>>,
+}
+
+/// The server side of the connection with a single client.
+///
+/// It keeps track of the data definitions, data requests, event subscriptions and facility subscriptions made by the client,
+/// and uses them to turn the [`Step`]s of a [`Script`] into the messages the client expects.
+#[derive(Debug)]
+pub struct Session {
+    stream: TcpStream,
+    packets: Receiver<Result<Packet, ServerError>>,
+    connected: bool,
+    app_name: String,
+    received: Vec<Packet>,
+    // The index of the first received packet that hasn't been matched by a `Step::Expect` yet.
+    expected: usize,
+    data_definitions: HashMap<u32, Vec<DataDatum>>,
+    data_requests: BTreeMap<u32, DataRequest>,
+    system_events: HashMap<String, u32>,
+    // The system events whose notifications have been turned off by the client.
+    disabled_system_events: HashSet<u32>,
+    // The system states set by the client, by lowercase name. The states that haven't been set are answered with zeros.
+    system_states: HashMap<String, (u32, f32, String)>,
+    client_events: HashMap<String, u32>,
+    notification_groups: HashMap<u32, u32>,
+    facilities: Vec<Facility>,
+    facility_subscriptions: HashMap<FacilityType, u32>,
+    variables: HashMap<String, Value>,
+}
+
+impl Session {
+    /// Wait for the `Open` packet of the client and answer it.
+    pub(crate) fn open(stream: TcpStream) -> Result<Self, ServerError> {
+        let (sender, packets) = mpsc::channel();
+
+        let reader = stream.try_clone()?;
+        std::thread::Builder::new()
+            .name("simconnect-server-reader".to_string())
+            .spawn(move || read_packets(reader, sender))?;
+
+        let mut session = Self {
+            stream,
+            packets,
+            connected: true,
+            app_name: String::new(),
+            received: Vec::new(),
+            expected: 0,
+            data_definitions: HashMap::new(),
+            data_requests: BTreeMap::new(),
+            system_events: HashMap::new(),
+            disabled_system_events: HashSet::new(),
+            system_states: HashMap::new(),
+            client_events: HashMap::new(),
+            notification_groups: HashMap::new(),
+            facilities: Vec::new(),
+            facility_subscriptions: HashMap::new(),
+            variables: HashMap::new(),
+        };
+
+        match session.next_packet(Some(Instant::now() + EXPECT_TIMEOUT))? {
+            Some(Packet::Open { app_name }) => {
+                debug!("Client `{app_name}` connected");
+
+                session.received.push(Packet::Open {
+                    app_name: app_name.clone(),
+                });
+                session.app_name = app_name;
+                session.send(&Message::Open {
+                    app_name: "SimConnect SDK Server".to_string(),
+                })?;
+
+                Ok(session)
+            }
+            Some(packet) => Err(ServerError::ProtocolError(format!(
+                "expected an `Open` packet, received `{}`",
+                packet.name()
+            ))),
+            None => Err(ServerError::Timeout("Open".to_string())),
+        }
+    }
+
+    /// The name the client has opened the connection with.
+    pub fn app_name(&self) -> &str {
+        &self.app_name
+    }
+
+    /// All the packets handled so far, in order.
+    pub fn packets(&self) -> &[Packet] {
+        &self.received
+    }
+
+    /// The entries of a data definition, in the order they were added, or `None` if the definition doesn't exist or has been cleared.
+    pub fn data_definition(&self, define_id: u32) -> Option<&[DataDatum]> {
+        self.data_definitions.get(&define_id).map(Vec::as_slice)
+    }
+
+    /// The current value of a simulation variable, as set by the script or by the client, if any.
+    pub fn variable(&self, name: &str) -> Option<&Value> {
+        self.variables.get(&name.to_uppercase())
+    }
+
+    /// Whether the client is still connected.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Play all the steps of a script, in order.
+    pub fn run(&mut self, script: &Script) -> Result<(), ServerError> {
+        for step in script.steps() {
+            self.step(step)?;
+        }
+
+        Ok(())
+    }
+
+    /// Keep handling the packets sent by the client until it disconnects.
+    pub fn serve(&mut self) -> Result<(), ServerError> {
+        while let Some(packet) = self.next_packet(None)? {
+            self.handle(packet)?;
+        }
+
+        Ok(())
+    }
+
+    /// Play a single step.
+    pub fn step(&mut self, step: &Step) -> Result<(), ServerError> {
+        trace!("Playing {step:?}");
+
+        self.handle_pending()?;
+
+        match step {
+            Step::Wait(duration) => {
+                let deadline = Instant::now() + *duration;
+
+                while let Some(packet) = self.next_packet(Some(deadline))? {
+                    self.handle(packet)?;
+                }
+            }
+            Step::Expect(name) => {
+                let deadline = Instant::now() + EXPECT_TIMEOUT;
+
+                // The packet might have been handled by an earlier step already.
+                loop {
+                    let position = self.received[self.expected..]
+                        .iter()
+                        .position(|packet| packet.name() == name);
+
+                    if let Some(position) = position {
+                        self.expected += position + 1;
+                        break;
+                    }
+
+                    match self.next_packet(Some(deadline))? {
+                        Some(packet) => self.handle(packet)?,
+                        None => return Err(ServerError::Timeout(name.clone())),
+                    }
+                }
+            }
+            Step::Set { name, value } => {
+                self.variables.insert(name.to_uppercase(), value.clone());
+            }
+            Step::Tick => {
+                let request_ids = self.data_requests.keys().copied().collect::<Vec<_>>();
+
+                for request_id in request_ids {
+                    self.send_data(request_id)?;
+                }
+            }
+            Step::Event { name, data } => {
+                if let Some((group_id, event_id)) = self.event(name) {
+                    self.send(&Message::Event {
+                        group_id,
+                        event_id,
+                        data: *data,
+                    })?;
+                }
+            }
+            Step::EventFilename { name, file_name } => {
+                if let Some((group_id, event_id)) = self.event(name) {
+                    self.send(&Message::EventFilename {
+                        group_id,
+                        event_id,
+                        file_name: file_name.clone(),
+                    })?;
+                }
+            }
+            Step::EventFrame {
+                name,
+                frame_rate,
+                sim_speed,
+            } => {
+                if let Some((group_id, event_id)) = self.event(name) {
+                    self.send(&Message::EventFrame {
+                        group_id,
+                        event_id,
+                        frame_rate: *frame_rate,
+                        sim_speed: *sim_speed,
+                    })?;
+                }
+            }
+            Step::AddFacility(facility) => {
+                self.facilities.push(facility.clone());
+
+                if let Some(request_id) = self.facility_subscriptions.get(&facility.facility_type) {
+                    self.send(&Message::FacilityList {
+                        facility_type: facility.facility_type,
+                        request_id: *request_id,
+                        entry_number: 0,
+                        out_of: 1,
+                        facilities: vec![facility.clone()],
+                    })?;
+                }
+            }
+            Step::Exception(exception) => {
+                self.send(&Message::Exception {
+                    exception: *exception,
+                    send_id: 0,
+                    index: 0,
+                })?;
+            }
+            Step::Send(message) => {
+                self.send(message)?;
+            }
+            Step::Replay(path) => {
+                let recording = std::fs::read(path)?;
+
+                for message in split_messages(&recording)? {
+                    // the connection is already open
+                    if message_id(message) != RECV_ID_OPEN {
+                        self.send(&Message::Raw(message.to_vec()))?;
+                    }
+                }
+            }
+            Step::Quit => {
+                self.send(&Message::Quit)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send a message to the client.
+    pub fn send(&mut self, message: &Message) -> Result<(), ServerError> {
+        trace!("Sending {message:?}");
+
+        self.stream.write_all(&message.encode())?;
+
+        Ok(())
+    }
+
+    /// Close the connection.
+    pub fn close(&mut self) -> Result<(), ServerError> {
+        self.stream.shutdown(Shutdown::Both)?;
+        self.connected = false;
+
+        Ok(())
+    }
+
+    fn next_packet(&mut self, deadline: Option<Instant>) -> Result<Option<Packet>, ServerError> {
+        if !self.connected {
+            return Ok(None);
+        }
+
+        let packet = match deadline {
+            Some(deadline) => {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+
+                match self.packets.recv_timeout(timeout) {
+                    Ok(packet) => Some(packet),
+                    Err(RecvTimeoutError::Timeout) => return Ok(None),
+                    Err(RecvTimeoutError::Disconnected) => None,
+                }
+            }
+            None => self.packets.recv().ok(),
+        };
+
+        match packet {
+            Some(packet) => packet.map(Some),
+            None => {
+                debug!("Client `{}` disconnected", self.app_name);
+                self.connected = false;
+
+                Ok(None)
+            }
+        }
+    }
+
+    fn handle_pending(&mut self) -> Result<(), ServerError> {
+        loop {
+            match self.packets.try_recv() {
+                Ok(packet) => self.handle(packet?)?,
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => {
+                    self.connected = false;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn handle(&mut self, packet: Packet) -> Result<(), ServerError> {
+        trace!("Received {packet:?}");
+
+        match &packet {
+            Packet::AddToDataDefinition {
+                define_id,
+                datum_name,
+                units_name,
+                datum_type,
+                epsilon,
+                ..
+            } => {
+                self.data_definitions
+                    .entry(*define_id)
+                    .or_default()
+                    .push(DataDatum {
+                        name: datum_name.clone(),
+                        unit: units_name.clone(),
+                        datum_type: *datum_type,
+                        epsilon: *epsilon,
+                    });
+            }
+            Packet::ClearDataDefinition { define_id } => {
+                self.data_definitions.remove(define_id);
+            }
+            Packet::RequestDataOnSimObject {
+                request_id,
+                define_id,
+                object_id,
+                period,
+                flags,
+                interval,
+                ..
+            } => {
+                self.data_requests.insert(
+                    *request_id,
+                    DataRequest {
+                        define_id: *define_id,
+                        object_id: *object_id,
+                        flags: *flags,
+                        interval: *interval,
+                        ticks: 0,
+                        last_data: None,
+                    },
+                );
+
+                match *period {
+                    PERIOD_NEVER => {
+                        self.data_requests.remove(request_id);
+                    }
+                    PERIOD_ONCE => {
+                        self.send_data(*request_id)?;
+                        self.data_requests.remove(request_id);
+                    }
+                    _ => (),
+                }
+            }
+            Packet::RequestDataOnSimObjectType {
+                request_id,
+                define_id,
+                ..
+            } => {
+                self.send_data_by_type(*request_id, *define_id)?;
+            }
+            Packet::SetDataOnSimObject {
+                define_id, data, ..
+            } => {
+                self.set_data(*define_id, data)?;
+            }
+            Packet::SubscribeToSystemEvent {
+                event_id,
+                event_name,
+            } => {
+                self.system_events
+                    .insert(event_name.to_lowercase(), *event_id);
+            }
+            Packet::UnsubscribeFromSystemEvent { event_id } => {
+                self.system_events.retain(|_, id| id != event_id);
+                self.disabled_system_events.remove(event_id);
+            }
+            Packet::SetSystemEventState { event_id, state } => {
+                if *state == SYSTEM_EVENT_STATE_OFF {
+                    self.disabled_system_events.insert(*event_id);
+                } else {
+                    self.disabled_system_events.remove(event_id);
+                }
+            }
+            Packet::RequestSystemState { request_id, state } => {
+                let (integer, float, string) = self
+                    .system_states
+                    .get(&state.to_lowercase())
+                    .cloned()
+                    .unwrap_or_default();
+
+                self.send(&Message::SystemState {
+                    request_id: *request_id,
+                    integer,
+                    float,
+                    string,
+                })?;
+            }
+            Packet::SetSystemState {
+                state,
+                integer,
+                float,
+                string,
+            } => {
+                self.system_states
+                    .insert(state.to_lowercase(), (*integer, *float, string.clone()));
+            }
+            Packet::MapClientEventToSimEvent {
+                event_id,
+                event_name,
+            } => {
+                self.client_events
+                    .insert(event_name.to_lowercase(), *event_id);
+            }
+            Packet::AddClientEventToNotificationGroup {
+                group_id, event_id, ..
+            } => {
+                self.notification_groups.insert(*event_id, *group_id);
+            }
+            Packet::RemoveClientEvent { event_id, .. } => {
+                self.notification_groups.remove(event_id);
+            }
+            Packet::ClearNotificationGroup { group_id } => {
+                self.notification_groups.retain(|_, id| id != group_id);
+            }
+            Packet::RequestFacilitiesList {
+                facility_type,
+                request_id,
+            } => {
+                if let Ok(facility_type) = FacilityType::try_from(*facility_type) {
+                    self.send_facilities(facility_type, *request_id)?;
+                }
+            }
+            Packet::SubscribeToFacilities {
+                facility_type,
+                request_id,
+            } => {
+                if let Ok(facility_type) = FacilityType::try_from(*facility_type) {
+                    self.facility_subscriptions
+                        .insert(facility_type, *request_id);
+                    self.send_facilities(facility_type, *request_id)?;
+                }
+            }
+            Packet::UnsubscribeToFacilities { facility_type } => {
+                if let Ok(facility_type) = FacilityType::try_from(*facility_type) {
+                    self.facility_subscriptions.remove(&facility_type);
+                }
+            }
+            Packet::RequestReservedKey { key_choices, .. } => {
+                // The first choice is always available.
+                self.send(&Message::ReservedKey {
+                    choice: key_choices[0].clone(),
+                    key: key_choices[0].clone(),
+                })?;
+            }
+            Packet::SetNotificationGroupPriority { .. }
+            | Packet::RequestNotificationGroup { .. }
+            | Packet::MapInputEventToClientEvent { .. }
+            | Packet::SetInputGroupPriority { .. }
+            | Packet::RemoveInputEvent { .. }
+            | Packet::ClearInputGroup { .. }
+            | Packet::SetInputGroupState { .. }
+            | Packet::TransmitClientEvent { .. }
+            | Packet::TransmitClientEventEx1 { .. }
+            | Packet::Open { .. } => (),
+            Packet::Unknown { id, .. } => {
+                warn!("Received unknown packet with ID {id:#x}");
+            }
+        }
+
+        self.received.push(packet);
+
+        Ok(())
+    }
+
+    /// Find the group ID and the event ID the client uses for an event, by name.
+    fn event(&self, name: &str) -> Option<(u32, u32)> {
+        let name = name.to_lowercase();
+
+        if let Some(event_id) = self.system_events.get(&name) {
+            if self.disabled_system_events.contains(event_id) {
+                debug!("The client has turned `{name}` off");
+                return None;
+            }
+
+            return Some((UNUSED_GROUP_ID, *event_id));
+        }
+
+        if let Some(event_id) = self.client_events.get(&name) {
+            let group_id = self
+                .notification_groups
+                .get(event_id)
+                .copied()
+                .unwrap_or(UNUSED_GROUP_ID);
+
+            return Some((group_id, *event_id));
+        }
+
+        warn!("The client has not subscribed to `{name}`");
+
+        None
+    }
+
+    fn send_data(&mut self, request_id: u32) -> Result<(), ServerError> {
+        let Some(request) = self.data_requests.get(&request_id) else {
+            return Ok(());
+        };
+        let Some((writer, define_count)) = self.encode_definition(request.define_id)? else {
+            return Ok(());
+        };
+
+        let Some(request) = self.data_requests.get_mut(&request_id) else {
+            return Ok(());
+        };
+
+        let tick = request.ticks;
+        request.ticks += 1;
+        if tick % (request.interval + 1) != 0 {
+            return Ok(());
+        }
+
+        if request.flags & DATA_REQUEST_FLAG_CHANGED == DATA_REQUEST_FLAG_CHANGED
+            && request.last_data.as_ref() == Some(&writer.data)
+        {
+            return Ok(());
+        }
+        request.last_data = Some(writer.data.clone());
+
+        let message = Message::SimObjectData {
+            request_id,
+            object_id: request.object_id,
+            define_id: request.define_id,
+            define_count,
+            data: writer.data,
+        };
+
+        self.send(&message)
+    }
+
+    /// Send the data of the user aircraft, the only simulation object there is, in answer to a `RequestDataOnSimObjectType`.
+    fn send_data_by_type(&mut self, request_id: u32, define_id: u32) -> Result<(), ServerError> {
+        let Some((writer, define_count)) = self.encode_definition(define_id)? else {
+            return Ok(());
+        };
+
+        let message = Message::SimObjectDataByType {
+            request_id,
+            object_id: OBJECT_ID_USER,
+            define_id,
+            define_count,
+            entry_number: 1,
+            out_of: 1,
+            data: writer.data,
+        };
+
+        self.send(&message)
+    }
+
+    /// Update the simulation variables of a data definition with the values set by the client.
+    fn set_data(&mut self, define_id: u32, data: &[u8]) -> Result<(), ServerError> {
+        let Some(definition) = self.data_definitions.get(&define_id) else {
+            return Ok(());
+        };
+
+        let mut reader = Reader::new(data);
+        for datum in definition {
+            let value = decode_datum(&mut reader, datum)?;
+            self.variables.insert(datum.name.to_uppercase(), value);
+        }
+
+        Ok(())
+    }
+
+    /// Encode the current values of a data definition. Returns the data and the number of datums, if the definition exists.
+    fn encode_definition(&self, define_id: u32) -> Result<Option<(Writer, u32)>, ServerError> {
+        let Some(definition) = self.data_definitions.get(&define_id) else {
+            return Ok(None);
+        };
+
+        let mut writer = Writer::default();
+        for datum in definition {
+            encode_datum(
+                &mut writer,
+                datum,
+                self.variables.get(&datum.name.to_uppercase()),
+            )?;
+        }
+
+        Ok(Some((writer, definition.len() as u32)))
+    }
+
+    fn send_facilities(
+        &mut self,
+        facility_type: FacilityType,
+        request_id: u32,
+    ) -> Result<(), ServerError> {
+        let facilities = self
+            .facilities
+            .iter()
+            .filter(|facility| facility.facility_type == facility_type)
+            .cloned()
+            .collect();
+
+        self.send(&Message::FacilityList {
+            facility_type,
+            request_id,
+            entry_number: 0,
+            out_of: 1,
+            facilities,
+        })
+    }
+}
+
+/// Encode the value of a simulation variable according to its `SIMCONNECT_DATATYPE`.
+fn encode_datum(
+    writer: &mut Writer,
+    datum: &DataDatum,
+    value: Option<&Value>,
+) -> Result<(), ServerError> {
+    if let Some(members) = struct_members(datum.datum_type) {
+        let values = match value {
+            Some(Value::List(values)) => values.as_slice(),
+            Some(value) => std::slice::from_ref(value),
+            None => &[],
+        };
+
+        // members that have not been set are sent as zero
+        for (index, member) in members.iter().enumerate() {
+            let value = values.get(index);
+
+            match member {
+                Member::Int32 => writer.i32(as_number(value) as i32),
+                Member::Float64 => writer.f64(as_number(value)),
+                Member::String(size) => writer.string(as_text(value), *size),
+            }
+        }
+
+        return Ok(());
+    }
+
+    let number = as_number(value);
+    let text = as_text(value);
+
+    match datum.datum_type {
+        // INT32
+        1 => writer.i32(number as i32),
+        // INT64
+        2 => writer.i64(number as i64),
+        // FLOAT32
+        3 => writer.f32(number as f32),
+        // FLOAT64
+        4 => writer.f64(number),
+        // STRING8 to STRING260
+        5 => writer.string(text, 8),
+        6 => writer.string(text, 32),
+        7 => writer.string(text, 64),
+        8 => writer.string(text, 128),
+        9 => writer.string(text, 256),
+        10 => writer.string(text, 260),
+        // STRINGV
+        11 => {
+            writer.bytes(text.as_bytes());
+            writer.bytes(&[0]);
+        }
+        datum_type => {
+            return Err(ServerError::Unsupported(format!(
+                "data type {datum_type} of `{}`",
+                datum.name
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_datum(reader: &mut Reader, datum: &DataDatum) -> Result<Value, ServerError> {
+    if let Some(members) = struct_members(datum.datum_type) {
+        let values = members
+            .iter()
+            .map(|member| match member {
+                Member::Int32 => Ok(Value::Number(reader.i32()? as f64)),
+                Member::Float64 => Ok(Value::Number(reader.f64()?)),
+                Member::String(size) => Ok(Value::String(reader.fixed_string(*size)?)),
+            })
+            .collect::<Result<Vec<_>, ServerError>>()?;
+
+        return Ok(Value::List(values));
+    }
+
+    let value = match datum.datum_type {
+        // INT32
+        1 => Value::Number(reader.i32()? as f64),
+        // INT64
+        2 => Value::Number(reader.i64()? as f64),
+        // FLOAT32
+        3 => Value::Number(reader.f32()? as f64),
+        // FLOAT64
+        4 => Value::Number(reader.f64()?),
+        // STRING8 to STRING260
+        5 => Value::String(reader.fixed_string(8)?),
+        6 => Value::String(reader.fixed_string(32)?),
+        7 => Value::String(reader.fixed_string(64)?),
+        8 => Value::String(reader.fixed_string(128)?),
+        9 => Value::String(reader.fixed_string(256)?),
+        10 => Value::String(reader.fixed_string(260)?),
+        // STRINGV
+        11 => Value::String(reader.string_v()?),
+        datum_type => {
+            return Err(ServerError::Unsupported(format!(
+                "data type {datum_type} of `{}`",
+                datum.name
+            )))
+        }
+    };
+
+    Ok(value)
+}
+
+fn as_number(value: Option<&Value>) -> f64 {
+    match value {
+        Some(Value::Number(number)) => *number,
+        _ => 0.0,
+    }
+}
+
+fn as_text(value: Option<&Value>) -> &str {
+    match value {
+        Some(Value::String(text)) => text.as_str(),
+        _ => "",
+    }
+}
+
+/// A member of a struct data type.
+enum Member {
+    Int32,
+    Float64,
+    /// A fixed size string of the given size.
+    String(usize),
+}
+
+/// The members of a struct data type, in the order they are packed, or `None` if the data type is not a struct.
+fn struct_members(datum_type: i32) -> Option<&'static [Member]> {
+    use Member::{Float64, Int32};
+
+    let members: &'static [Member] = match datum_type {
+        // INITPOSITION
+        12 => &[
+            Float64, Float64, Float64, Float64, Float64, Float64, Int32, Int32,
+        ],
+        // MARKERSTATE
+        13 => &[Member::String(64), Int32],
+        // WAYPOINT
+        14 => &[Float64, Float64, Float64, Int32, Float64, Float64],
+        // LATLONALT and XYZ
+        15 | 16 => &[Float64, Float64, Float64],
+        _ => return None,
+    };
+
+    Some(members)
+}
+
+/// Split a recording into messages. Each message starts with a `SIMCONNECT_RECV` header, whose first field is the size of the message.
+fn split_messages(mut recording: &[u8]) -> Result<Vec<&[u8]>, ServerError> {
+    let mut messages = Vec::new();
+
+    while !recording.is_empty() {
+        let size = recording
+            .get(0..4)
+            .map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize)
+            .unwrap_or_default();
+
+        if size < 12 || size > recording.len() {
+            return Err(ServerError::ProtocolError(
+                "the recording is corrupt".to_string(),
+            ));
+        }
+
+        let (message, rest) = recording.split_at(size);
+        messages.push(message);
+        recording = rest;
+    }
+
+    Ok(messages)
+}
+
+fn message_id(message: &[u8]) -> u32 {
+    u32::from_le_bytes([message[8], message[9], message[10], message[11]])
+}
+
+fn read_packets(mut stream: TcpStream, sender: Sender<Result<Packet, ServerError>>) {
+    loop {
+        let packet = match Packet::read(&mut stream) {
+            Ok(Some(packet)) => Ok(packet),
+            // the connection has been closed
+            Ok(None) => break,
+            Err(e) => Err(e),
+        };
+
+        let failed = packet.is_err();
+
+        if sender.send(packet).is_err() || failed {
+            break;
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use std::net::SocketAddr;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use simconnect_sdk::{
+    ClientEvent, ClientEventRequest, FacilityType, InitPosition, InitPositionAirspeed, LatLonAlt,
+    MarkerState, Notification, ObjectId, SimConnect, SimConnectObject, SimObjectType, SystemEvent,
+    SystemEventRequest, SystemState, SystemStateRequest, TcpBackend,
+};
+use simconnect_sdk_server::{Message, Packet, Script, Server, Session, Value};
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct AirplaneData {
+    #[simconnect(name = "TITLE")]
+    title: String,
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+    alt: f64,
+}
+
+/// Start a server that plays `script` to the first client and returns the session once the client disconnects.
+fn start(script: &str) -> (SocketAddr, JoinHandle<Session>) {
+    let script = Script::parse(script).unwrap();
+    let server = Server::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+
+    let handle = std::thread::spawn(move || {
+        let mut session = server.accept().unwrap();
+        session.run(&script).unwrap();
+        session.serve().unwrap();
+        session
+    });
+
+    (addr, handle)
+}
+
+fn connect(addr: SocketAddr) -> SimConnect {
+    let backend = TcpBackend::connect("Server test", addr).unwrap();
+    let mut client = SimConnect::with_backend(backend);
+
+    assert!(matches!(next_notification(&mut client), Notification::Open));
+
+    client
+}
+
+fn next_notification(client: &mut SimConnect) -> Notification {
+    let start = Instant::now();
+
+    loop {
+        if let Some(notification) = client.get_next_dispatch().unwrap() {
+            return notification;
+        }
+
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn object_data() {
+    let (addr, server) = start(
+        r#"
+        expect RequestDataOnSimObject
+        set TITLE "Cessna 172"
+        set "PLANE ALTITUDE" 1500
+        tick
+        set "PLANE ALTITUDE" 1600.5
+        tick
+        "#,
+    );
+
+    let mut client = connect(addr);
+    let _subscription = client.register_object::<AirplaneData>().unwrap();
+
+    for alt in [1500.0, 1600.5] {
+        match next_notification(&mut client) {
+            Notification::Object(data) => {
+                let data = AirplaneData::try_from(&data).unwrap();
+                assert_eq!(data.title, "Cessna 172");
+                assert_eq!(data.alt, alt);
+            }
+            notification => panic!("unexpected notification {notification:?}"),
+        }
+    }
+
+    drop(client);
+    let session = server.join().unwrap();
+
+    assert_eq!(session.app_name(), "Server test");
+    let definition = session.data_definition(0).unwrap();
+    assert_eq!(definition.len(), 2);
+    assert_eq!(definition[0].name, "TITLE");
+    assert_eq!(definition[1].name, "PLANE ALTITUDE");
+    assert_eq!(definition[1].unit, "feet");
+}
+
+#[test]
+fn object_data_by_type() {
+    let (addr, server) = start(
+        r#"
+        set TITLE "Cessna 172"
+        set "PLANE ALTITUDE" 1500
+        expect RequestDataOnSimObjectType
+        "#,
+    );
+
+    let mut client = connect(addr);
+    client
+        .request_data_by_type::<AirplaneData>(1000, SimObjectType::User)
+        .unwrap();
+
+    match next_notification(&mut client) {
+        Notification::ObjectsByType { objects, .. } => {
+            assert_eq!(objects.len(), 1);
+            assert_eq!(objects[0].0, ObjectId::USER);
+
+            let data = AirplaneData::try_from(&objects[0].1).unwrap();
+            assert_eq!(data.title, "Cessna 172");
+            assert_eq!(data.alt, 1500.0);
+        }
+        notification => panic!("unexpected notification {notification:?}"),
+    }
+
+    drop(client);
+    server.join().unwrap();
+}
+
+#[test]
+fn set_data() {
+    #[derive(Debug, Clone, SimConnectObject)]
+    #[simconnect(period = "second", settable)]
+    struct Fuel {
+        #[simconnect(name = "ATC ID")]
+        atc_id: String,
+        #[simconnect(name = "FUEL TANK CENTER QUANTITY", unit = "gallons")]
+        center: f64,
+    }
+
+    let (addr, server) = start(
+        r#"
+        expect SetDataOnSimObject
+        "#,
+    );
+
+    let mut client = connect(addr);
+    client
+        .set_data_on_sim_object(
+            ObjectId::USER,
+            &Fuel {
+                atc_id: "N172SP".to_string(),
+                center: 20.5,
+            },
+        )
+        .unwrap();
+
+    drop(client);
+    let session = server.join().unwrap();
+
+    assert_eq!(
+        session.variable("ATC ID"),
+        Some(&Value::String("N172SP".to_string()))
+    );
+    assert_eq!(
+        session.variable("FUEL TANK CENTER QUANTITY"),
+        Some(&Value::Number(20.5))
+    );
+}
+
+#[test]
+fn struct_data() {
+    #[derive(Debug, Clone, SimConnectObject)]
+    #[simconnect(period = "second")]
+    struct PositionData {
+        #[simconnect(name = "STRUCT LATLONALT")]
+        position: LatLonAlt,
+        #[simconnect(name = "Tailhook")]
+        tailhook: MarkerState,
+    }
+
+    #[derive(Debug, Clone, SimConnectObject)]
+    #[simconnect(period = "once", settable)]
+    struct RepositionData {
+        #[simconnect(name = "Initial Position")]
+        position: InitPosition,
+    }
+
+    let (addr, server) = start(
+        r#"
+        expect RequestDataOnSimObject
+        set "STRUCT LATLONALT" 46.785 23.686 1200
+        set Tailhook "Tailhook" 1
+        tick
+        expect SetDataOnSimObject
+        "#,
+    );
+
+    let mut client = connect(addr);
+    let _subscription = client.register_object::<PositionData>().unwrap();
+
+    match next_notification(&mut client) {
+        Notification::Object(data) => {
+            let data = PositionData::try_from(&data).unwrap();
+            assert_eq!(
+                data.position,
+                LatLonAlt {
+                    lat: 46.785,
+                    lon: 23.686,
+                    alt: 1200.0,
+                }
+            );
+            assert_eq!(
+                data.tailhook,
+                MarkerState {
+                    name: "Tailhook".to_string(),
+                    on: true,
+                }
+            );
+        }
+        notification => panic!("unexpected notification {notification:?}"),
+    }
+
+    client
+        .set_data_on_sim_object(
+            ObjectId::USER,
+            &RepositionData {
+                position: InitPosition {
+                    lat: 44.571,
+                    lon: 26.085,
+                    heading: 80.0,
+                    on_ground: true,
+                    airspeed: InitPositionAirspeed::Knots(120),
+                    ..Default::default()
+                },
+            },
+        )
+        .unwrap();
+
+    drop(client);
+    let session = server.join().unwrap();
+
+    assert_eq!(
+        session.variable("Initial Position"),
+        Some(&Value::List(
+            [44.571, 26.085, 0.0, 0.0, 0.0, 80.0, 1.0, 120.0]
+                .into_iter()
+                .map(Value::Number)
+                .collect()
+        ))
+    );
+}
+
+#[test]
+fn system_events() {
+    let (addr, server) = start(
+        r#"
+        expect SubscribeToSystemEvent
+        expect SubscribeToSystemEvent
+        event Pause 1
+        expect SetSystemEventState
+        event Pause 0
+        event-filename FlightLoaded "flights/other/MainMenu.FLT"
+        "#,
+    );
+
+    let mut client = connect(addr);
+    client
+        .subscribe_to_system_event(SystemEventRequest::Pause)
+        .unwrap();
+    client
+        .subscribe_to_system_event(SystemEventRequest::FlightLoaded)
+        .unwrap();
+
+    assert!(matches!(
+        next_notification(&mut client),
+        Notification::SystemEvent(SystemEvent::Pause { state: true })
+    ));
+
+    // the next pause event is not sent while the subscription is off
+    client
+        .set_system_event_state(SystemEventRequest::Pause, false)
+        .unwrap();
+    match next_notification(&mut client) {
+        Notification::SystemEvent(SystemEvent::FlightLoaded { file_name }) => {
+            assert_eq!(file_name, "flights/other/MainMenu.FLT");
+        }
+        notification => panic!("unexpected notification {notification:?}"),
+    }
+
+    drop(client);
+    server.join().unwrap();
+}
+
+#[test]
+fn system_state() {
+    let (addr, server) = start(
+        r#"
+        expect RequestSystemState
+        "#,
+    );
+
+    let mut client = connect(addr);
+    client
+        .set_system_state(&SystemState::FlightPlan {
+            file_name: "flights/LRCL-LROP.PLN".to_string(),
+        })
+        .unwrap();
+    client
+        .request_system_state(SystemStateRequest::FlightPlan)
+        .unwrap();
+    client
+        .request_system_state(SystemStateRequest::DialogMode)
+        .unwrap();
+
+    match next_notification(&mut client) {
+        Notification::SystemState(SystemState::FlightPlan { file_name }) => {
+            assert_eq!(file_name, "flights/LRCL-LROP.PLN");
+        }
+        notification => panic!("unexpected notification {notification:?}"),
+    }
+    // the states that haven't been set are answered with zeros
+    assert!(matches!(
+        next_notification(&mut client),
+        Notification::SystemState(SystemState::DialogMode { state: false })
+    ));
+
+    drop(client);
+    server.join().unwrap();
+}
+
+#[test]
+fn facilities() {
+    let (addr, server) = start(
+        r#"
+        airport LRCL 46.785 23.686 382
+        airport LROP 44.571 26.085 96
+        expect SubscribeToFacilities
+        airport EGLL 51.477 -0.461 25
+        "#,
+    );
+
+    let mut client = connect(addr);
+    client
+        .subscribe_to_facilities(FacilityType::Airport)
+        .unwrap();
+
+    match next_notification(&mut client) {
+        Notification::AirportList(airports) => {
+            let icaos = airports.iter().map(|a| a.icao.as_str()).collect::<Vec<_>>();
+            assert_eq!(icaos, ["LRCL", "LROP"]);
+            assert_eq!(airports[0].lat, 46.785);
+        }
+        notification => panic!("unexpected notification {notification:?}"),
+    }
+
+    match next_notification(&mut client) {
+        Notification::AirportList(airports) => {
+            assert_eq!(airports.len(), 1);
+            assert_eq!(airports[0].icao, "EGLL");
+            assert_eq!(airports[0].lon, -0.461);
+        }
+        notification => panic!("unexpected notification {notification:?}"),
+    }
+
+    drop(client);
+    server.join().unwrap();
+}
+
+#[test]
+fn transmit_client_event() {
+    let (addr, server) = start(
+        r#"
+        expect TransmitClientEventEx1
+        "#,
+    );
+
+    let mut client = connect(addr);
+    client
+        .transmit_client_event(ObjectId::USER, ClientEvent::Throttle1Set { value: 8000 })
+        .unwrap();
+    client
+        .transmit_client_event_ex1(ObjectId::USER, ClientEventRequest::Throttle1Set, &[1, 2, 3])
+        .unwrap();
+
+    drop(client);
+    let session = server.join().unwrap();
+
+    let transmitted = session
+        .packets()
+        .iter()
+        .filter_map(|packet| match packet {
+            Packet::TransmitClientEvent { data, .. } => Some(vec![*data]),
+            Packet::TransmitClientEventEx1 { data, .. } => Some(data.to_vec()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(transmitted, vec![vec![8000], vec![1, 2, 3, 0, 0]]);
+}
+
+#[test]
+fn replay() {
+    let path = std::env::temp_dir().join(format!(
+        "simconnect-sdk-server-replay-{}.bin",
+        std::process::id()
+    ));
+    let recording = [
+        Message::Open {
+            app_name: "Recorded".to_string(),
+        },
+        Message::Quit,
+    ]
+    .iter()
+    .flat_map(Message::encode)
+    .collect::<Vec<_>>();
+    std::fs::write(&path, recording).unwrap();
+
+    let (addr, server) = start(&format!("replay \"{}\"", path.display()));
+
+    let mut client = connect(addr);
+    assert!(matches!(next_notification(&mut client), Notification::Quit));
+
+    drop(client);
+    let session = server.join().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(session.packets(), [Packet::Open { .. }]));
+}
@@ -1,5 +1,6 @@
 use std::env;
-use std::path::PathBuf;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
 
 fn main() {
     println!("cargo:rerun-if-changed=ffi/include/Wrapper.h");
@@ -7,12 +8,20 @@ fn main() {
     println!("cargo:rerun-if-changed=ffi/include/SimConnect.h");
     println!("cargo:rerun-if-changed=ffi/lib/SimConnect.lib");
     println!("cargo:rerun-if-changed=ffi/lib/SimConnect.dll");
+    println!("cargo:rerun-if-changed=data/client_events.txt");
 
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-    println!("cargo:rustc-link-search={}", out_path.to_string_lossy());
-    println!("cargo:rustc-link-lib=static=SimConnect");
 
-    let builder = if env::var("DOCS_RS").is_ok() {
+    // The SimConnect C API is only available on Windows.
+    // Other targets only get the type definitions, which are used by the non-FFI backends.
+    let is_windows = env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows");
+
+    if is_windows {
+        println!("cargo:rustc-link-search={}", out_path.to_string_lossy());
+        println!("cargo:rustc-link-lib=static=SimConnect");
+    }
+
+    let builder = if env::var("DOCS_RS").is_ok() || !is_windows {
         bindgen::Builder::default().header("ffi/include/WrapperFake.h")
     } else {
         bindgen::Builder::default().header("ffi/include/Wrapper.h")
@@ -21,39 +30,77 @@ fn main() {
     let bindings = builder
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
         .clang_args(&["-x", "c++"])
+        .allowlist_function("CloseHandle")
+        .allowlist_function("CreateEventW")
+        .allowlist_function("SetEvent")
+        .allowlist_function("WaitForSingleObject")
         .allowlist_function("SimConnect_AddClientEventToNotificationGroup")
         .allowlist_function("SimConnect_AddToDataDefinition")
         .allowlist_function("SimConnect_CallDispatch")
         .allowlist_function("SimConnect_ClearDataDefinition")
+        .allowlist_function("SimConnect_ClearInputGroup")
         .allowlist_function("SimConnect_ClearNotificationGroup")
         .allowlist_function("SimConnect_Close")
         .allowlist_function("SimConnect_GetNextDispatch")
         .allowlist_function("SimConnect_MapClientEventToSimEvent")
+        .allowlist_function("SimConnect_MapInputEventToClientEvent")
         .allowlist_function("SimConnect_Open")
         .allowlist_function("SimConnect_RemoveClientEvent")
+        .allowlist_function("SimConnect_RemoveInputEvent")
         .allowlist_function("SimConnect_RequestDataOnSimObject")
+        .allowlist_function("SimConnect_RequestDataOnSimObjectType")
         .allowlist_function("SimConnect_RequestFacilitiesList")
+        .allowlist_function("SimConnect_RequestNotificationGroup")
+        .allowlist_function("SimConnect_RequestReservedKey")
+        .allowlist_function("SimConnect_RequestSystemState")
+        .allowlist_function("SimConnect_SetDataOnSimObject")
+        .allowlist_function("SimConnect_SetInputGroupPriority")
+        .allowlist_function("SimConnect_SetInputGroupState")
         .allowlist_function("SimConnect_SetNotificationGroupPriority")
+        .allowlist_function("SimConnect_SetSystemState")
+        .allowlist_function("SimConnect_SetSystemEventState")
         .allowlist_function("SimConnect_SubscribeToFacilities")
         .allowlist_function("SimConnect_SubscribeToSystemEvent")
+        .allowlist_function("SimConnect_TransmitClientEvent_EX1")
+        .allowlist_function("SimConnect_TransmitClientEvent")
         .allowlist_function("SimConnect_UnsubscribeFromSystemEvent")
         .allowlist_function("SimConnect_UnsubscribeToFacilities")
         .allowlist_type("SIMCONNECT_CLIENT_DATA_PERIOD")
+        .allowlist_type("SIMCONNECT_DATA_RACE_RESULT")
         .allowlist_type("SIMCONNECT_EXCEPTION")
         .allowlist_type("SIMCONNECT_RECV_AIRPORT_LIST")
+        .allowlist_type("SIMCONNECT_RECV_CUSTOM_ACTION")
+        .allowlist_type("SIMCONNECT_RECV_EVENT_EX1")
         .allowlist_type("SIMCONNECT_RECV_EVENT_FILENAME")
         .allowlist_type("SIMCONNECT_RECV_EVENT_FRAME")
+        .allowlist_type("SIMCONNECT_RECV_EVENT_MULTIPLAYER_CLIENT_STARTED")
+        .allowlist_type("SIMCONNECT_RECV_EVENT_MULTIPLAYER_SERVER_STARTED")
+        .allowlist_type("SIMCONNECT_RECV_EVENT_MULTIPLAYER_SESSION_ENDED")
+        .allowlist_type("SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE")
+        .allowlist_type("SIMCONNECT_RECV_EVENT_RACE_END")
+        .allowlist_type("SIMCONNECT_RECV_EVENT_RACE_LAP")
+        .allowlist_type("SIMCONNECT_RECV_EVENT_WEATHER_MODE")
         .allowlist_type("SIMCONNECT_RECV_EVENT")
         .allowlist_type("SIMCONNECT_RECV_EXCEPTION")
         .allowlist_type("SIMCONNECT_RECV_FACILITIES_LIST")
         .allowlist_type("SIMCONNECT_RECV_ID")
         .allowlist_type("SIMCONNECT_RECV_NDB_LIST")
         .allowlist_type("SIMCONNECT_RECV_OPEN")
+        .allowlist_type("SIMCONNECT_RECV_RESERVED_KEY")
+        .allowlist_type("SIMCONNECT_RECV_SIMOBJECT_DATA_BYTYPE")
         .allowlist_type("SIMCONNECT_RECV_SIMOBJECT_DATA")
+        .allowlist_type("SIMCONNECT_RECV_SYSTEM_STATE")
         .allowlist_type("SIMCONNECT_RECV_VOR_LIST")
         .allowlist_type("SIMCONNECT_RECV_WAYPOINT_LIST")
         .allowlist_type("SIMCONNECT_RECV")
+        .allowlist_type("SIMCONNECT_SIMOBJECT_TYPE")
+        .allowlist_type("SIMCONNECT_STATE")
+        .allowlist_type("SIMCONNECT_WEATHER_MODE")
+        .allowlist_var("INFINITE")
+        .allowlist_var("INITPOSITION_AIRSPEED_CRUISE")
+        .allowlist_var("INITPOSITION_AIRSPEED_KEEP")
         .allowlist_var("SIMCONNECT_DATA_REQUEST_FLAG_CHANGED")
+        .allowlist_var("SIMCONNECT_EVENT_FLAG_GROUPID_IS_PRIORITY")
         .allowlist_var("SIMCONNECT_GROUP_PRIORITY_DEFAULT")
         .allowlist_var("SIMCONNECT_GROUP_PRIORITY_HIGHEST_MASKABLE")
         .allowlist_var("SIMCONNECT_GROUP_PRIORITY_HIGHEST")
@@ -65,9 +112,19 @@ fn main() {
         .allowlist_var("SIMCONNECT_RECV_ID_VOR_LIST_HAS_LOCALIZER")
         .allowlist_var("SIMCONNECT_RECV_ID_VOR_LIST_HAS_NAV_SIGNAL")
         .allowlist_var("SIMCONNECT_SOUND_SYSTEM_EVENT_DATA_MASTER")
+        .allowlist_var("SIMCONNECT_UNUSED")
         .allowlist_var("SIMCONNECT_VIEW_SYSTEM_EVENT_DATA_COCKPIT_2D")
         .allowlist_var("SIMCONNECT_VIEW_SYSTEM_EVENT_DATA_COCKPIT_VIRTUAL")
         .allowlist_var("SIMCONNECT_VIEW_SYSTEM_EVENT_DATA_ORTHOGONAL")
+        .allowlist_var("SIMCONNECT_WAYPOINT_ALTITUDE_IS_AGL")
+        .allowlist_var("SIMCONNECT_WAYPOINT_ALWAYS_BACKUP")
+        .allowlist_var("SIMCONNECT_WAYPOINT_COMPUTE_VERTICAL_SPEED")
+        .allowlist_var("SIMCONNECT_WAYPOINT_NONE")
+        .allowlist_var("SIMCONNECT_WAYPOINT_ON_GROUND")
+        .allowlist_var("SIMCONNECT_WAYPOINT_REVERSE")
+        .allowlist_var("SIMCONNECT_WAYPOINT_SPEED_REQUESTED")
+        .allowlist_var("SIMCONNECT_WAYPOINT_THROTTLE_REQUESTED")
+        .allowlist_var("SIMCONNECT_WAYPOINT_WRAP_TO_FIRST")
         .generate()
         .expect("Unable to generate bindings");
 
@@ -75,11 +132,306 @@ fn main() {
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Failed to write the bindings");
 
-    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("Failed to find `CARGO_MANIFEST_DIR`");
-    let lib_path = PathBuf::from(manifest_dir).join("ffi/lib");
+    generate_client_events(
+        Path::new("data/client_events.txt"),
+        &out_path.join("client_events.rs"),
+    );
+
+    if is_windows {
+        let manifest_dir =
+            env::var("CARGO_MANIFEST_DIR").expect("Failed to find `CARGO_MANIFEST_DIR`");
+        let lib_path = PathBuf::from(manifest_dir).join("ffi/lib");
+
+        for file in &["SimConnect.lib", "SimConnect.dll"] {
+            std::fs::copy(lib_path.join(file), out_path.join(file))
+                .unwrap_or_else(|_| panic!("Failed to copy `{file}`"));
+        }
+    }
+}
+
+/// How a client event uses its data parameter.
+enum ClientEventData {
+    Unused,
+    Range(i32, i32),
+    Value,
+}
+
+struct ClientEvent {
+    section: String,
+    name: String,
+    variant: String,
+    data: ClientEventData,
+    description: String,
+    value_description: Option<String>,
+}
+
+/// Generate `ClientEventRequest`, `ClientEvent` and their conversions from the key event catalog.
+fn generate_client_events(catalog_path: &Path, out_path: &Path) {
+    let catalog = std::fs::read_to_string(catalog_path)
+        .unwrap_or_else(|_| panic!("Failed to read `{}`", catalog_path.display()));
+    let events = parse_client_events(&catalog);
+
+    let mut code = String::new();
+
+    // ClientEventRequest
+    code.push_str("/// SimConnect Client Event Request.\n///\n");
+    code.push_str("/// Generated from the key event catalog in `data/client_events.txt`.\n///\n");
+    code.push_str("/// Defined by <https://www.prepar3d.com/SDKv5/sdk/references/variables/event_ids.html>.\n");
+    code.push_str("/// Extended by <https://docs.flightsimulator.com/html/Programming_Tools/Event_IDs/Event_IDs.htm>.\n");
+    code.push_str("#[derive(Debug, Copy, Clone, PartialEq, Eq)]\n");
+    code.push_str("#[repr(u32)]\n#[non_exhaustive]\npub enum ClientEventRequest {\n");
+    for (i, event) in events.iter().enumerate() {
+        write_section_comment(&mut code, &events, i, "    ");
+        writeln!(code, "    #[doc = {:?}]", event.description).unwrap();
+        match i {
+            0 => writeln!(
+                code,
+                "    {} = CLIENT_EVENT_DISCRIMINANT_START,",
+                event.variant
+            ),
+            _ => writeln!(
+                code,
+                "    {} = CLIENT_EVENT_DISCRIMINANT_START + {i},",
+                event.variant
+            ),
+        }
+        .unwrap();
+    }
+    code.push_str("}\n\n");
+
+    // The variants in the order of their discriminants.
+    // `num_enum` overflows the stack of the compiler on an enum this large, so `TryFrom<u32>` looks them up here instead.
+    writeln!(
+        code,
+        "const CLIENT_EVENT_REQUESTS: [ClientEventRequest; {}] = [",
+        events.len()
+    )
+    .unwrap();
+    for event in &events {
+        writeln!(code, "    ClientEventRequest::{},", event.variant).unwrap();
+    }
+    code.push_str("];\n\n");
+
+    code.push_str("impl TryFrom<u32> for ClientEventRequest {\n");
+    code.push_str("    type Error = SimConnectError;\n\n");
+    code.push_str("    fn try_from(id: u32) -> Result<Self, Self::Error> {\n");
+    code.push_str("        id.checked_sub(CLIENT_EVENT_DISCRIMINANT_START)\n");
+    code.push_str("            .and_then(|index| CLIENT_EVENT_REQUESTS.get(index as usize))\n");
+    code.push_str("            .copied()\n");
+    code.push_str("            .ok_or(SimConnectError::UnimplementedEventType(id))\n");
+    code.push_str("    }\n}\n\n");
+
+    code.push_str("impl ClientEventRequest {\n");
+    code.push_str(
+        "    pub(crate) fn into_event_name(self) -> &'static str {\n        match self {\n",
+    );
+    for (i, event) in events.iter().enumerate() {
+        write_section_comment(&mut code, &events, i, "            ");
+        writeln!(
+            code,
+            "            Self::{} => {:?},",
+            event.variant, event.name
+        )
+        .unwrap();
+    }
+    code.push_str("        }\n    }\n\n");
+
+    code.push_str("    /// How the event uses its data parameter.\n");
+    code.push_str("    pub fn data(self) -> ClientEventData {\n        match self {\n");
+    for (i, event) in events.iter().enumerate() {
+        write_section_comment(&mut code, &events, i, "            ");
+        let data = match event.data {
+            ClientEventData::Unused => "ClientEventData::Unused".to_string(),
+            ClientEventData::Range(min, max) => {
+                format!("ClientEventData::Range {{ min: {min}, max: {max} }}")
+            }
+            ClientEventData::Value => "ClientEventData::Value".to_string(),
+        };
+        writeln!(code, "            Self::{} => {data},", event.variant).unwrap();
+    }
+    code.push_str("        }\n    }\n}\n\n");
+
+    // ClientEvent
+    code.push_str("/// SimConnect Client Event.\n///\n");
+    code.push_str("/// Generated from the key event catalog in `data/client_events.txt`.\n///\n");
+    code.push_str("/// Defined by <https://www.prepar3d.com/SDKv5/sdk/references/variables/event_ids.html>.\n");
+    code.push_str("/// Extended by <https://docs.flightsimulator.com/html/Programming_Tools/Event_IDs/Event_IDs.htm>.\n");
+    code.push_str(
+        "#[derive(Debug, Copy, Clone, PartialEq, Eq)]\n#[non_exhaustive]\npub enum ClientEvent {\n",
+    );
+    for (i, event) in events.iter().enumerate() {
+        write_section_comment(&mut code, &events, i, "    ");
+        writeln!(code, "    #[doc = {:?}]", event.description).unwrap();
+        match &event.value_description {
+            Some(value_description) => {
+                writeln!(code, "    {} {{", event.variant).unwrap();
+                writeln!(code, "        #[doc = {value_description:?}]").unwrap();
+                code.push_str("        value: i32,\n    },\n");
+            }
+            None => writeln!(code, "    {},", event.variant).unwrap(),
+        }
+    }
+    code.push_str("}\n\n");
+
+    code.push_str("impl TryFrom<&bindings::SIMCONNECT_RECV_EVENT> for ClientEvent {\n");
+    code.push_str("    type Error = SimConnectError;\n\n");
+    code.push_str(
+        "    fn try_from(event: &bindings::SIMCONNECT_RECV_EVENT) -> Result<Self, Self::Error> {\n",
+    );
+    code.push_str("        let request = ClientEventRequest::try_from(event.uEventID)?;\n\n");
+    code.push_str("        match request {\n");
+    for (i, event) in events.iter().enumerate() {
+        write_section_comment(&mut code, &events, i, "            ");
+        match event.value_description {
+            Some(_) => writeln!(
+                code,
+                "            ClientEventRequest::{0} => Ok(Self::{0} {{ value: event.dwData as i32 }}),",
+                event.variant
+            ),
+            None => writeln!(
+                code,
+                "            ClientEventRequest::{0} => Ok(Self::{0}),",
+                event.variant
+            ),
+        }
+        .unwrap();
+    }
+    code.push_str("        }\n    }\n}\n\n");
+
+    code.push_str("impl From<ClientEvent> for (ClientEventRequest, i32) {\n");
+    code.push_str("    fn from(event: ClientEvent) -> Self {\n        match event {\n");
+    for (i, event) in events.iter().enumerate() {
+        write_section_comment(&mut code, &events, i, "            ");
+        match event.value_description {
+            Some(_) => writeln!(
+                code,
+                "            ClientEvent::{0} {{ value }} => (ClientEventRequest::{0}, value),",
+                event.variant
+            ),
+            None => writeln!(
+                code,
+                "            ClientEvent::{0} => (ClientEventRequest::{0}, 0),",
+                event.variant
+            ),
+        }
+        .unwrap();
+    }
+    code.push_str("        }\n    }\n}\n");
+
+    std::fs::write(out_path, code)
+        .unwrap_or_else(|_| panic!("Failed to write `{}`", out_path.display()));
+}
+
+fn parse_client_events(catalog: &str) -> Vec<ClientEvent> {
+    let mut events: Vec<ClientEvent> = Vec::new();
+    let mut section = None;
+
+    for (index, line) in catalog.lines().enumerate() {
+        let line = line.trim();
+        let line_number = index + 1;
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(name.to_string());
+            continue;
+        }
+
+        let section = section
+            .clone()
+            .unwrap_or_else(|| panic!("Line {line_number}: the event is outside of a section"));
+
+        let columns = line.split('|').map(str::trim).collect::<Vec<_>>();
+        let (name, data, description, value_description) = match columns[..] {
+            [name, data, description] => (name, data, description, None),
+            [name, data, description, value_description] => {
+                (name, data, description, Some(value_description))
+            }
+            _ => panic!("Line {line_number}: expected 3 or 4 `|`-separated columns"),
+        };
 
-    for file in &["SimConnect.lib", "SimConnect.dll"] {
-        std::fs::copy(lib_path.join(file), out_path.join(file))
-            .unwrap_or_else(|_| panic!("Failed to copy `{file}`"));
+        let data = match data {
+            "-" => ClientEventData::Unused,
+            "any" => ClientEventData::Value,
+            range => {
+                let (min, max) = range
+                    .split_once("..")
+                    .and_then(|(min, max)| Some((min.parse().ok()?, max.parse().ok()?)))
+                    .unwrap_or_else(|| {
+                        panic!("Line {line_number}: expected `-`, `any` or `min..max`, found `{range}`")
+                    });
+                assert!(min < max, "Line {line_number}: `{range}` is an empty range");
+
+                ClientEventData::Range(min, max)
+            }
+        };
+
+        let value_description = match (&data, value_description) {
+            (ClientEventData::Unused, None) => None,
+            (ClientEventData::Unused, Some(_)) => {
+                panic!(
+                    "Line {line_number}: `{name}` doesn't use its data parameter, but documents it"
+                )
+            }
+            (ClientEventData::Range(min, max), None) => Some(format!("{min} to {max}.")),
+            (ClientEventData::Value, None) => {
+                panic!(
+                    "Line {line_number}: `{name}` is missing the description of its data parameter"
+                )
+            }
+            (_, Some(value_description)) => Some(value_description.to_string()),
+        };
+
+        assert!(
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'),
+            "Line {line_number}: `{name}` is not a valid event name"
+        );
+
+        let variant = to_pascal_case(name);
+        if let Some(existing) = events
+            .iter()
+            .find(|e| e.name == name || e.variant == variant)
+        {
+            panic!(
+                "Line {line_number}: `{name}` collides with `{}`, which is already defined",
+                existing.name
+            );
+        }
+
+        events.push(ClientEvent {
+            section,
+            name: name.to_string(),
+            variant,
+            data,
+            description: description.to_string(),
+            value_description,
+        });
+    }
+
+    assert!(!events.is_empty(), "The key event catalog is empty");
+
+    events
+}
+
+fn write_section_comment(code: &mut String, events: &[ClientEvent], index: usize, indent: &str) {
+    if index == 0 || events[index - 1].section != events[index].section {
+        writeln!(code, "{indent}// {}", events[index].section).unwrap();
     }
 }
+
+/// `AXIS_ELEVATOR_SET` -> `AxisElevatorSet`
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().unwrap();
+            first.to_string() + &chars.as_str().to_ascii_lowercase()
+        })
+        .collect()
+}
@@ -1,4 +1,5 @@
-// fake defs in lieu of Windows.h that is used for the docs.rs build
+// fake defs in lieu of Windows.h that is used for the docs.rs build and for non-Windows targets
+// the integer types are pinned to their Windows (LLP64) sizes so that the struct layouts match the wire format
 #define MAX_PATH 260
 #define FALSE 0
 #define CONST const
@@ -10,17 +11,17 @@
     };                       \
     typedef struct name##__ *name
 
-typedef unsigned long DWORD;
+typedef unsigned int DWORD;
 typedef unsigned char BYTE;
 typedef int BOOL;
-typedef long LONG;
+typedef int LONG;
 typedef LONG HRESULT;
 typedef void *HANDLE;
 typedef char CHAR;
 typedef CONST CHAR *LPCSTR;
 typedef struct _GUID
 {
-    unsigned long Data1;
+    unsigned int Data1;
     unsigned short Data2;
     unsigned short Data3;
     unsigned char Data4[8];
@@ -0,0 +1,141 @@
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+#[cfg(windows)]
+use crate::bindings;
+
+// `WaitForSingleObject` return value when the event has been signaled.
+#[cfg(windows)]
+const WAIT_OBJECT_0: u32 = 0;
+
+/// An auto-reset event that a [`crate::SimConnectBackend`] signals when new messages are ready to be received.
+///
+/// A single [`DispatchEvent::wait`] consumes every signal that has been raised since the previous one,
+/// so the messages have to be drained by calling [`crate::SimConnectBackend::get_next_dispatch`] until it returns `None`.
+/// Clones share the same event, so it can be waited on from another thread than the one that owns the backend.
+#[derive(Debug, Clone)]
+pub struct DispatchEvent {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+enum Inner {
+    Condvar {
+        signaled: Mutex<bool>,
+        condvar: Condvar,
+    },
+    /// A Win32 event, which is signaled by the SimConnect C API itself.
+    #[cfg(windows)]
+    Win32(bindings::HANDLE),
+}
+
+// Win32 event handles can be used from any thread.
+#[cfg(windows)]
+unsafe impl Send for Inner {}
+#[cfg(windows)]
+unsafe impl Sync for Inner {}
+
+impl DispatchEvent {
+    /// Create a new event, in the non-signaled state.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner::Condvar {
+                signaled: Mutex::new(false),
+                condvar: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Create a new Win32 event, in the non-signaled state. Its handle can be passed to `SimConnect_Open`.
+    #[cfg(windows)]
+    pub(crate) fn win32() -> Result<Self, crate::SimConnectError> {
+        let handle =
+            unsafe { bindings::CreateEventW(std::ptr::null_mut(), 0, 0, std::ptr::null()) };
+
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        Ok(Self {
+            inner: Arc::new(Inner::Win32(handle)),
+        })
+    }
+
+    /// The Win32 handle of the event, if it is a Win32 event.
+    #[cfg(windows)]
+    pub(crate) fn handle(&self) -> Option<bindings::HANDLE> {
+        match *self.inner {
+            Inner::Win32(handle) => Some(handle),
+            Inner::Condvar { .. } => None,
+        }
+    }
+
+    /// Signal the event, waking up a pending [`DispatchEvent::wait`].
+    pub fn signal(&self) {
+        match &*self.inner {
+            Inner::Condvar { signaled, condvar } => {
+                *lock(signaled) = true;
+                condvar.notify_all();
+            }
+            #[cfg(windows)]
+            Inner::Win32(handle) => unsafe {
+                bindings::SetEvent(*handle);
+            },
+        }
+    }
+
+    /// Block until the event is signaled, or until `timeout` elapses. `None` waits forever.
+    ///
+    /// Returns `true` if the event has been signaled and `false` if the timeout has elapsed.
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        match &*self.inner {
+            Inner::Condvar { signaled, condvar } => {
+                let guard = lock(signaled);
+
+                let mut guard = match timeout {
+                    Some(timeout) => {
+                        condvar
+                            .wait_timeout_while(guard, timeout, |signaled| !*signaled)
+                            .unwrap_or_else(|e| e.into_inner())
+                            .0
+                    }
+                    None => condvar
+                        .wait_while(guard, |signaled| !*signaled)
+                        .unwrap_or_else(|e| e.into_inner()),
+                };
+
+                std::mem::replace(&mut *guard, false)
+            }
+            #[cfg(windows)]
+            Inner::Win32(handle) => {
+                let milliseconds = timeout
+                    .map(|timeout| timeout.as_millis().min(u128::from(u32::MAX - 1)) as u32)
+                    .unwrap_or(bindings::INFINITE);
+
+                unsafe { bindings::WaitForSingleObject(*handle, milliseconds) == WAIT_OBJECT_0 }
+            }
+        }
+    }
+}
+
+impl Default for DispatchEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Inner::Win32(handle) = self {
+            unsafe {
+                bindings::CloseHandle(*handle);
+            }
+        }
+    }
+}
+
+fn lock(signaled: &Mutex<bool>) -> std::sync::MutexGuard<'_, bool> {
+    // The flag is always in a consistent state, even if a thread panicked while holding the lock.
+    signaled.lock().unwrap_or_else(|e| e.into_inner())
+}
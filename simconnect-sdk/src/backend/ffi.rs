@@ -0,0 +1,442 @@
+use std::ffi::c_void;
+
+use crate::{
+    as_c_string, bindings, ok_if_fail, success, DispatchEvent, SimConnectBackend, SimConnectError,
+};
+
+/// [`SimConnectBackend`] that uses the SimConnect C API.
+#[derive(Debug)]
+pub struct FfiBackend {
+    handle: std::ptr::NonNull<c_void>,
+    dispatch_event: DispatchEvent,
+}
+
+// The SimConnect handle is not tied to the thread that opened it.
+unsafe impl Send for FfiBackend {}
+
+impl FfiBackend {
+    /// Open a connection to the simulator through the SimConnect C API.
+    #[tracing::instrument(name = "FfiBackend::open", level = "debug")]
+    pub fn open(name: &str) -> Result<Self, SimConnectError> {
+        let mut handle = std::ptr::null_mut();
+        // SimConnect signals the event every time a message is ready to be received.
+        let dispatch_event = DispatchEvent::win32()?;
+
+        success!(unsafe {
+            bindings::SimConnect_Open(
+                &mut handle,
+                as_c_string!(name),
+                std::ptr::null_mut(),
+                0,
+                dispatch_event.handle().unwrap_or(std::ptr::null_mut()),
+                0,
+            )
+        })?;
+
+        Ok(Self {
+            handle: std::ptr::NonNull::new(handle).ok_or_else(|| {
+                SimConnectError::UnexpectedError(
+                    "SimConnect_Open returned null pointer on success".to_string(),
+                )
+            })?,
+            dispatch_event,
+        })
+    }
+}
+
+impl SimConnectBackend for FfiBackend {
+    fn add_to_data_definition(
+        &self,
+        define_id: u32,
+        datum_name: &str,
+        units_name: &str,
+        datum_type: i32,
+        epsilon: f32,
+        datum_id: u32,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_AddToDataDefinition(
+                self.handle.as_ptr(),
+                define_id,
+                as_c_string!(datum_name),
+                as_c_string!(units_name),
+                datum_type,
+                epsilon,
+                datum_id,
+            )
+        })
+    }
+
+    fn clear_data_definition(&self, define_id: u32) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_ClearDataDefinition(self.handle.as_ptr(), define_id)
+        })
+    }
+
+    fn request_data_on_sim_object(
+        &self,
+        request_id: u32,
+        define_id: u32,
+        object_id: u32,
+        period: i32,
+        flags: u32,
+        origin: u32,
+        interval: u32,
+        limit: u32,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_RequestDataOnSimObject(
+                self.handle.as_ptr(),
+                request_id,
+                define_id,
+                object_id,
+                period,
+                flags,
+                origin,
+                interval,
+                limit,
+            )
+        })
+    }
+
+    fn request_data_on_sim_object_type(
+        &self,
+        request_id: u32,
+        define_id: u32,
+        radius_meters: u32,
+        object_type: i32,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_RequestDataOnSimObjectType(
+                self.handle.as_ptr(),
+                request_id,
+                define_id,
+                radius_meters,
+                object_type,
+            )
+        })
+    }
+
+    fn set_data_on_sim_object(
+        &self,
+        define_id: u32,
+        object_id: u32,
+        flags: u32,
+        array_count: u32,
+        unit_size: u32,
+        data: &[u8],
+    ) -> Result<(), SimConnectError> {
+        // SimConnect only reads from the buffer, despite the `*mut` in its signature.
+        success!(unsafe {
+            bindings::SimConnect_SetDataOnSimObject(
+                self.handle.as_ptr(),
+                define_id,
+                object_id,
+                flags,
+                array_count,
+                unit_size,
+                data.as_ptr() as *mut c_void,
+            )
+        })
+    }
+
+    fn subscribe_to_system_event(
+        &self,
+        event_id: u32,
+        event_name: &str,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_SubscribeToSystemEvent(
+                self.handle.as_ptr(),
+                event_id,
+                as_c_string!(event_name),
+            )
+        })
+    }
+
+    fn unsubscribe_from_system_event(&self, event_id: u32) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_UnsubscribeFromSystemEvent(self.handle.as_ptr(), event_id)
+        })
+    }
+
+    fn set_system_event_state(&self, event_id: u32, state: u32) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_SetSystemEventState(self.handle.as_ptr(), event_id, state as i32)
+        })
+    }
+
+    fn request_system_state(&self, request_id: u32, state: &str) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_RequestSystemState(
+                self.handle.as_ptr(),
+                request_id,
+                as_c_string!(state),
+            )
+        })
+    }
+
+    fn set_system_state(
+        &self,
+        state: &str,
+        integer: u32,
+        float: f32,
+        string: &str,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_SetSystemState(
+                self.handle.as_ptr(),
+                as_c_string!(state),
+                integer,
+                float,
+                as_c_string!(string),
+            )
+        })
+    }
+
+    fn map_client_event_to_sim_event(
+        &self,
+        event_id: u32,
+        event_name: &str,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_MapClientEventToSimEvent(
+                self.handle.as_ptr(),
+                event_id,
+                as_c_string!(event_name),
+            )
+        })
+    }
+
+    fn transmit_client_event(
+        &self,
+        object_id: u32,
+        event_id: u32,
+        data: u32,
+        group_id: u32,
+        flags: u32,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_TransmitClientEvent(
+                self.handle.as_ptr(),
+                object_id,
+                event_id,
+                data,
+                group_id,
+                flags,
+            )
+        })
+    }
+
+    fn transmit_client_event_ex1(
+        &self,
+        object_id: u32,
+        event_id: u32,
+        group_id: u32,
+        flags: u32,
+        data: [u32; 5],
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_TransmitClientEvent_EX1(
+                self.handle.as_ptr(),
+                object_id,
+                event_id,
+                group_id,
+                flags,
+                data[0],
+                data[1],
+                data[2],
+                data[3],
+                data[4],
+            )
+        })
+    }
+
+    fn add_client_event_to_notification_group(
+        &self,
+        group_id: u32,
+        event_id: u32,
+        maskable: bool,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_AddClientEventToNotificationGroup(
+                self.handle.as_ptr(),
+                group_id,
+                event_id,
+                maskable as i32,
+            )
+        })
+    }
+
+    fn remove_client_event(&self, group_id: u32, event_id: u32) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_RemoveClientEvent(self.handle.as_ptr(), group_id, event_id)
+        })
+    }
+
+    fn set_notification_group_priority(
+        &self,
+        group_id: u32,
+        priority: u32,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_SetNotificationGroupPriority(
+                self.handle.as_ptr(),
+                group_id,
+                priority,
+            )
+        })
+    }
+
+    fn clear_notification_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_ClearNotificationGroup(self.handle.as_ptr(), group_id)
+        })
+    }
+
+    fn request_notification_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_RequestNotificationGroup(self.handle.as_ptr(), group_id, 0, 0)
+        })
+    }
+
+    fn map_input_event_to_client_event(
+        &self,
+        group_id: u32,
+        input_definition: &str,
+        down_event_id: u32,
+        down_value: u32,
+        up_event_id: u32,
+        up_value: u32,
+        maskable: bool,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_MapInputEventToClientEvent(
+                self.handle.as_ptr(),
+                group_id,
+                as_c_string!(input_definition),
+                down_event_id,
+                down_value,
+                up_event_id,
+                up_value,
+                maskable as i32,
+            )
+        })
+    }
+
+    fn set_input_group_priority(
+        &self,
+        group_id: u32,
+        priority: u32,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_SetInputGroupPriority(self.handle.as_ptr(), group_id, priority)
+        })
+    }
+
+    fn remove_input_event(
+        &self,
+        group_id: u32,
+        input_definition: &str,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_RemoveInputEvent(
+                self.handle.as_ptr(),
+                group_id,
+                as_c_string!(input_definition),
+            )
+        })
+    }
+
+    fn clear_input_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        success!(unsafe { bindings::SimConnect_ClearInputGroup(self.handle.as_ptr(), group_id) })
+    }
+
+    fn set_input_group_state(&self, group_id: u32, state: u32) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_SetInputGroupState(self.handle.as_ptr(), group_id, state)
+        })
+    }
+
+    fn request_reserved_key(
+        &self,
+        event_id: u32,
+        key_choice1: &str,
+        key_choice2: &str,
+        key_choice3: &str,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_RequestReservedKey(
+                self.handle.as_ptr(),
+                event_id,
+                as_c_string!(key_choice1),
+                as_c_string!(key_choice2),
+                as_c_string!(key_choice3),
+            )
+        })
+    }
+
+    fn request_facilities_list(
+        &self,
+        facility_type: i32,
+        request_id: u32,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_RequestFacilitiesList(
+                self.handle.as_ptr(),
+                facility_type,
+                request_id,
+            )
+        })
+    }
+
+    fn subscribe_to_facilities(
+        &self,
+        facility_type: i32,
+        request_id: u32,
+    ) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_SubscribeToFacilities(
+                self.handle.as_ptr(),
+                facility_type,
+                request_id,
+            )
+        })
+    }
+
+    fn unsubscribe_to_facilities(&self, facility_type: i32) -> Result<(), SimConnectError> {
+        success!(unsafe {
+            bindings::SimConnect_UnsubscribeToFacilities(self.handle.as_ptr(), facility_type)
+        })
+    }
+
+    fn get_next_dispatch(&mut self) -> Result<Option<&[u8]>, SimConnectError> {
+        let mut data_buf: *mut bindings::SIMCONNECT_RECV = std::ptr::null_mut();
+        let mut size_buf: bindings::DWORD = 32;
+        let size_buf_pointer: *mut bindings::DWORD = &mut size_buf;
+
+        unsafe {
+            ok_if_fail!(
+                bindings::SimConnect_GetNextDispatch(
+                    self.handle.as_ptr(),
+                    &mut data_buf,
+                    size_buf_pointer
+                ),
+                None
+            );
+        };
+
+        // The buffer is owned by SimConnect and stays valid until the next call.
+        let data = unsafe { std::slice::from_raw_parts(data_buf as *const u8, size_buf as usize) };
+
+        Ok(Some(data))
+    }
+
+    fn dispatch_event(&self) -> &DispatchEvent {
+        &self.dispatch_event
+    }
+
+    fn close(&mut self) -> Result<(), SimConnectError> {
+        success!(unsafe { bindings::SimConnect_Close(self.handle.as_ptr()) })
+    }
+}
@@ -0,0 +1,1061 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{
+    bindings, string_to_fixed_c_str, Airport, ClientEventRequest, DispatchEvent, ObjectId,
+    SimConnectBackend, SimConnectError, SimObjectType, SystemEventRequest, SystemStateRequest,
+    Waypoint, NDB, VOR,
+};
+
+// The group ID SimConnect uses for system events, `SIMCONNECT_UNUSED`.
+const UNUSED_GROUP_ID: u32 = u32::MAX;
+// The notification group used internally by `SimConnect` for client events.
+const CLIENT_EVENT_GROUP_ID: u32 = 0;
+
+/// A call made by [`crate::SimConnect`] to a [`MockSimulator`].
+///
+/// Each variant maps 1:1 to a method of [`crate::SimConnectBackend`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MockCall {
+    AddToDataDefinition {
+        define_id: u32,
+        datum_name: String,
+        units_name: String,
+        datum_type: i32,
+        epsilon: f32,
+        datum_id: u32,
+    },
+    ClearDataDefinition {
+        define_id: u32,
+    },
+    RequestDataOnSimObject {
+        request_id: u32,
+        define_id: u32,
+        object_id: u32,
+        period: i32,
+        flags: u32,
+        origin: u32,
+        interval: u32,
+        limit: u32,
+    },
+    RequestDataOnSimObjectType {
+        request_id: u32,
+        define_id: u32,
+        radius_meters: u32,
+        object_type: i32,
+    },
+    SetDataOnSimObject {
+        define_id: u32,
+        object_id: u32,
+        flags: u32,
+        array_count: u32,
+        unit_size: u32,
+        data: Vec<u8>,
+    },
+    SubscribeToSystemEvent {
+        event_id: u32,
+        event_name: String,
+    },
+    UnsubscribeFromSystemEvent {
+        event_id: u32,
+    },
+    SetSystemEventState {
+        event_id: u32,
+        state: u32,
+    },
+    RequestSystemState {
+        request_id: u32,
+        state: String,
+    },
+    SetSystemState {
+        state: String,
+        integer: u32,
+        float: f32,
+        string: String,
+    },
+    MapClientEventToSimEvent {
+        event_id: u32,
+        event_name: String,
+    },
+    TransmitClientEvent {
+        object_id: u32,
+        event_id: u32,
+        data: u32,
+        group_id: u32,
+        flags: u32,
+    },
+    TransmitClientEventEx1 {
+        object_id: u32,
+        event_id: u32,
+        group_id: u32,
+        flags: u32,
+        data: [u32; 5],
+    },
+    AddClientEventToNotificationGroup {
+        group_id: u32,
+        event_id: u32,
+        maskable: bool,
+    },
+    RemoveClientEvent {
+        group_id: u32,
+        event_id: u32,
+    },
+    SetNotificationGroupPriority {
+        group_id: u32,
+        priority: u32,
+    },
+    ClearNotificationGroup {
+        group_id: u32,
+    },
+    RequestNotificationGroup {
+        group_id: u32,
+    },
+    MapInputEventToClientEvent {
+        group_id: u32,
+        input_definition: String,
+        down_event_id: u32,
+        down_value: u32,
+        up_event_id: u32,
+        up_value: u32,
+        maskable: bool,
+    },
+    SetInputGroupPriority {
+        group_id: u32,
+        priority: u32,
+    },
+    RemoveInputEvent {
+        group_id: u32,
+        input_definition: String,
+    },
+    ClearInputGroup {
+        group_id: u32,
+    },
+    SetInputGroupState {
+        group_id: u32,
+        state: u32,
+    },
+    RequestReservedKey {
+        event_id: u32,
+        key_choices: [String; 3],
+    },
+    RequestFacilitiesList {
+        facility_type: i32,
+        request_id: u32,
+    },
+    SubscribeToFacilities {
+        facility_type: i32,
+        request_id: u32,
+    },
+    UnsubscribeToFacilities {
+        facility_type: i32,
+    },
+    Close,
+}
+
+/// A single entry of a data definition registered with a [`MockSimulator`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockDatum {
+    /// The name of the simulation variable.
+    pub name: String,
+    /// The unit of the simulation variable.
+    pub unit: String,
+    /// The raw `SIMCONNECT_DATATYPE` of the simulation variable.
+    pub datum_type: i32,
+    /// The epsilon of the simulation variable.
+    pub epsilon: f32,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    calls: Vec<MockCall>,
+    data_definitions: HashMap<u32, Vec<MockDatum>>,
+    queue: VecDeque<Vec<u8>>,
+    disconnected: bool,
+}
+
+/// In-process [`crate::SimConnectBackend`] that stands in for the simulator in tests.
+///
+/// It records every call made by [`crate::SimConnect`] and hands back scripted `SIMCONNECT_RECV` messages, in the order they were pushed.
+/// Clones share the same state, so a clone can be kept around to script messages and to make assertions after the original has been moved into [`crate::SimConnect::with_backend`].
+///
+/// # Example
+///
+/// ```rust
+/// use simconnect_sdk::{MockSimulator, Notification, SimConnect};
+///
+/// let simulator = MockSimulator::new();
+/// let mut client = SimConnect::with_backend(simulator.clone());
+///
+/// simulator.push_open();
+///
+/// assert!(matches!(client.get_next_dispatch(), Ok(Some(Notification::Open))));
+/// assert!(matches!(client.get_next_dispatch(), Ok(None)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockSimulator {
+    state: Arc<Mutex<MockState>>,
+    dispatch_event: DispatchEvent,
+    current: Vec<u8>,
+}
+
+impl MockSimulator {
+    /// Create a new mock simulator with no scripted messages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All the calls made so far, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    /// All the calls made so far, in order. The recorded calls are cleared.
+    pub fn take_calls(&self) -> Vec<MockCall> {
+        std::mem::take(&mut self.state().calls)
+    }
+
+    /// The entries of a data definition, in the order they were added, or `None` if the definition doesn't exist or has been cleared.
+    pub fn data_definition(&self, define_id: u32) -> Option<Vec<MockDatum>> {
+        self.state().data_definitions.get(&define_id).cloned()
+    }
+
+    /// Whether the connection has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.state().calls.contains(&MockCall::Close)
+    }
+
+    /// The number of scripted messages that haven't been received yet.
+    pub fn pending_messages(&self) -> usize {
+        self.state().queue.len()
+    }
+
+    /// Script a raw message. `data` must start with a `SIMCONNECT_RECV` header.
+    ///
+    /// # Remarks
+    /// The message is checked when it is received, so a message that is too short for its `SIMCONNECT_RECV_*` struct is returned as a [`SimConnectError::UnexpectedError`] by [`crate::SimConnect::get_next_dispatch`].
+    pub fn push_raw(&self, data: Vec<u8>) {
+        self.state().queue.push_back(data);
+        self.dispatch_event.signal();
+    }
+
+    /// Drop the connection without a `SIMCONNECT_RECV_QUIT`. Once the scripted messages have been received, [`SimConnectError::Disconnected`] is returned.
+    pub fn disconnect(&self) {
+        self.state().disconnected = true;
+        self.dispatch_event.signal();
+    }
+
+    /// Script a `SIMCONNECT_RECV_OPEN` message.
+    pub fn push_open(&self) {
+        let message = bindings::SIMCONNECT_RECV_OPEN {
+            _base: header::<bindings::SIMCONNECT_RECV_OPEN>(
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_OPEN,
+            ),
+            szApplicationName: string_to_fixed_c_str("MockSimulator"),
+            dwApplicationVersionMajor: 11,
+            dwApplicationVersionMinor: 0,
+            dwApplicationBuildMajor: 0,
+            dwApplicationBuildMinor: 0,
+            dwSimConnectVersionMajor: 11,
+            dwSimConnectVersionMinor: 0,
+            dwSimConnectBuildMajor: 0,
+            dwSimConnectBuildMinor: 0,
+            dwReserved1: 0,
+            dwReserved2: 0,
+        };
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_QUIT` message.
+    pub fn push_quit(&self) {
+        let message = header::<bindings::SIMCONNECT_RECV>(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_QUIT,
+        );
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_EXCEPTION` message.
+    pub fn push_exception(&self, exception: u32, send_id: u32, index: u32) {
+        let message = bindings::SIMCONNECT_RECV_EXCEPTION {
+            _base: header::<bindings::SIMCONNECT_RECV_EXCEPTION>(
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION,
+            ),
+            dwException: exception,
+            dwSendID: send_id,
+            dwIndex: index,
+        };
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_EVENT` message for a system event.
+    pub fn push_system_event(&self, event: SystemEventRequest, data: u32) {
+        let message = event_message(UNUSED_GROUP_ID, event as u32, data);
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_EVENT_FILENAME` message for a system event.
+    pub fn push_system_event_filename(&self, event: SystemEventRequest, file_name: &str) {
+        let mut message = bindings::SIMCONNECT_RECV_EVENT_FILENAME {
+            _base: event_message(UNUSED_GROUP_ID, event as u32, 0),
+            szFileName: string_to_fixed_c_str(file_name),
+            dwFlags: 0,
+        };
+        message._base._base = header::<bindings::SIMCONNECT_RECV_EVENT_FILENAME>(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME,
+        );
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_EVENT_FRAME` message for a system event.
+    pub fn push_system_event_frame(
+        &self,
+        event: SystemEventRequest,
+        frame_rate: f32,
+        sim_speed: f32,
+    ) {
+        let mut message = bindings::SIMCONNECT_RECV_EVENT_FRAME {
+            _base: event_message(UNUSED_GROUP_ID, event as u32, 0),
+            fFrameRate: frame_rate,
+            fSimSpeed: sim_speed,
+        };
+        message._base._base = header::<bindings::SIMCONNECT_RECV_EVENT_FRAME>(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FRAME,
+        );
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE` message for [`SystemEventRequest::ObjectAdded`] or [`SystemEventRequest::ObjectRemoved`].
+    pub fn push_system_event_object(
+        &self,
+        event: SystemEventRequest,
+        object_id: ObjectId,
+        object_type: SimObjectType,
+    ) {
+        let mut message = bindings::SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE {
+            _base: event_message(UNUSED_GROUP_ID, event as u32, object_id.into()),
+            eObjType: object_type.into(),
+        };
+        message._base._base = header::<bindings::SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE>(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_OBJECT_ADDREMOVE,
+        );
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_EVENT` message for a client event.
+    pub fn push_client_event(&self, event: ClientEventRequest, data: u32) {
+        let message = event_message(CLIENT_EVENT_GROUP_ID, event as u32, data);
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_EVENT_EX1` message for a client event.
+    pub fn push_client_event_ex1(&self, event: ClientEventRequest, data: [u32; 5]) {
+        let message = bindings::SIMCONNECT_RECV_EVENT_EX1 {
+            _base: header::<bindings::SIMCONNECT_RECV_EVENT_EX1>(
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_EX1,
+            ),
+            uGroupID: CLIENT_EVENT_GROUP_ID,
+            uEventID: event as u32,
+            dwData0: data[0],
+            dwData1: data[1],
+            dwData2: data[2],
+            dwData3: data[3],
+            dwData4: data[4],
+        };
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_EVENT` message for a client event that has been mapped by name, e.g. through [`crate::SimConnect::subscribe_to_custom_client_event`].
+    ///
+    /// # Panics
+    /// If no client event has been mapped to `name`.
+    pub fn push_custom_client_event(&self, name: &str, data: u32) {
+        let event_id = self
+            .state()
+            .calls
+            .iter()
+            .rev()
+            .find_map(|call| match call {
+                MockCall::MapClientEventToSimEvent {
+                    event_id,
+                    event_name,
+                } if event_name == name => Some(*event_id),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no client event has been mapped to `{name}`"));
+
+        let message = event_message(CLIENT_EVENT_GROUP_ID, event_id, data);
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_SYSTEM_STATE` message, in answer to the latest [`crate::SimConnect::request_system_state`] call for `request`.
+    ///
+    /// # Panics
+    /// If the system state has not been requested.
+    pub fn push_system_state(
+        &self,
+        request: SystemStateRequest,
+        integer: u32,
+        float: f32,
+        string: &str,
+    ) {
+        let request_id = self
+            .state()
+            .calls
+            .iter()
+            .rev()
+            .find_map(|call| match call {
+                MockCall::RequestSystemState { request_id, state }
+                    if state == request.into_state_name() =>
+                {
+                    Some(*request_id)
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("the system state {request:?} has not been requested"));
+
+        let message = bindings::SIMCONNECT_RECV_SYSTEM_STATE {
+            _base: header::<bindings::SIMCONNECT_RECV_SYSTEM_STATE>(
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SYSTEM_STATE,
+            ),
+            dwRequestID: request_id,
+            dwInteger: integer,
+            fFloat: float,
+            szString: string_to_fixed_c_str(string),
+        };
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_RESERVED_KEY` message, in answer to [`crate::SimConnect::request_reserved_key`].
+    pub fn push_reserved_key(&self, choice: &str, key: &str) {
+        let message = bindings::SIMCONNECT_RECV_RESERVED_KEY {
+            _base: header::<bindings::SIMCONNECT_RECV_RESERVED_KEY>(
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_RESERVED_KEY,
+            ),
+            szChoiceReserved: string_to_fixed_c_str(choice),
+            szReservedKey: string_to_fixed_c_str(key),
+        };
+
+        self.push_raw(as_bytes(&message).to_vec());
+    }
+
+    /// Script a `SIMCONNECT_RECV_SIMOBJECT_DATA` message for the user aircraft.
+    ///
+    /// `data` is the packed content of the data definition registered under `request_id`, in the order the entries were added.
+    pub fn push_sim_object_data(&self, request_id: u32, data: &[u8]) {
+        self.push_sim_object_data_for(request_id, ObjectId::USER, data);
+    }
+
+    /// Script a `SIMCONNECT_RECV_SIMOBJECT_DATA` message for the simulation object `object_id`. See [`MockSimulator::push_sim_object_data`].
+    pub fn push_sim_object_data_for(&self, request_id: u32, object_id: ObjectId, data: &[u8]) {
+        self.push_sim_object_data_message(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA,
+            request_id,
+            object_id,
+            1,
+            1,
+            data,
+        );
+    }
+
+    /// Script a `SIMCONNECT_RECV_SIMOBJECT_DATA_BYTYPE` message, in answer to [`crate::SimConnect::request_data_by_type`].
+    ///
+    /// Each simulation object in range is sent in a message of its own. `entry_number` counts from `1` up to `out_of`.
+    /// `data` is the packed content of the data definition registered under `request_id`, in the order the entries were added.
+    pub fn push_sim_object_data_by_type(
+        &self,
+        request_id: u32,
+        object_id: ObjectId,
+        entry_number: u32,
+        out_of: u32,
+        data: &[u8],
+    ) {
+        self.push_sim_object_data_message(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE,
+            request_id,
+            object_id,
+            entry_number,
+            out_of,
+            data,
+        );
+    }
+
+    fn push_sim_object_data_message(
+        &self,
+        recv_id: bindings::SIMCONNECT_RECV_ID,
+        request_id: u32,
+        object_id: ObjectId,
+        entry_number: u32,
+        out_of: u32,
+        data: &[u8],
+    ) {
+        let define_count = self
+            .data_definition(request_id)
+            .map(|definition| definition.len() as u32)
+            .unwrap_or_default();
+
+        // `dwData` is the first DWORD of the data, so it is not part of the header.
+        let header_size = std::mem::size_of::<bindings::SIMCONNECT_RECV_SIMOBJECT_DATA>()
+            - std::mem::size_of::<bindings::DWORD>();
+        // The message is padded to a full `SIMCONNECT_RECV_SIMOBJECT_DATA`, e.g. when no objects are in range.
+        let size = (header_size + data.len())
+            .max(std::mem::size_of::<bindings::SIMCONNECT_RECV_SIMOBJECT_DATA>());
+
+        let message = bindings::SIMCONNECT_RECV_SIMOBJECT_DATA {
+            _base: bindings::SIMCONNECT_RECV {
+                dwSize: size as u32,
+                dwVersion: 0,
+                dwID: recv_id as u32,
+            },
+            dwRequestID: request_id,
+            dwObjectID: object_id.into(),
+            dwDefineID: request_id,
+            dwFlags: 0,
+            dwentrynumber: entry_number,
+            dwoutof: out_of,
+            dwDefineCount: define_count,
+            dwData: 0,
+        };
+
+        let mut bytes = as_bytes(&message)[..header_size].to_vec();
+        bytes.extend_from_slice(data);
+        bytes.resize(size, 0);
+
+        self.push_raw(bytes);
+    }
+
+    /// Script a `SIMCONNECT_RECV_AIRPORT_LIST` message.
+    pub fn push_airport_list(
+        &self,
+        request_id: u32,
+        entry_number: u32,
+        out_of: u32,
+        airports: &[Airport],
+    ) {
+        let records = airports
+            .iter()
+            .map(|airport| facility(&airport.icao, airport.lat, airport.lon, airport.alt))
+            .collect::<Vec<_>>();
+
+        self.push_facilities_list(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_AIRPORT_LIST,
+            request_id,
+            entry_number,
+            out_of,
+            &records,
+        );
+    }
+
+    /// Script a `SIMCONNECT_RECV_WAYPOINT_LIST` message.
+    pub fn push_waypoint_list(
+        &self,
+        request_id: u32,
+        entry_number: u32,
+        out_of: u32,
+        waypoints: &[Waypoint],
+    ) {
+        let records = waypoints
+            .iter()
+            .map(|waypoint| bindings::SIMCONNECT_DATA_FACILITY_WAYPOINT {
+                _base: facility(&waypoint.icao, waypoint.lat, waypoint.lon, waypoint.alt),
+                fMagVar: waypoint.mag_var,
+            })
+            .collect::<Vec<_>>();
+
+        self.push_facilities_list(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_WAYPOINT_LIST,
+            request_id,
+            entry_number,
+            out_of,
+            &records,
+        );
+    }
+
+    /// Script a `SIMCONNECT_RECV_NDB_LIST` message.
+    pub fn push_ndb_list(&self, request_id: u32, entry_number: u32, out_of: u32, ndbs: &[NDB]) {
+        let records = ndbs
+            .iter()
+            .map(|ndb| bindings::SIMCONNECT_DATA_FACILITY_NDB {
+                _base: bindings::SIMCONNECT_DATA_FACILITY_WAYPOINT {
+                    _base: facility(&ndb.icao, ndb.lat, ndb.lon, ndb.alt),
+                    fMagVar: ndb.mag_var,
+                },
+                fFrequency: ndb.frequency,
+            })
+            .collect::<Vec<_>>();
+
+        self.push_facilities_list(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NDB_LIST,
+            request_id,
+            entry_number,
+            out_of,
+            &records,
+        );
+    }
+
+    /// Script a `SIMCONNECT_RECV_VOR_LIST` message.
+    ///
+    /// The flags of each station are taken from `has_nav_signal`, `has_localizer`, `has_glide_slope` and `has_dme`.
+    pub fn push_vor_list(&self, request_id: u32, entry_number: u32, out_of: u32, vors: &[VOR]) {
+        let records = vors
+            .iter()
+            .map(|vor| {
+                let mut flags = 0;
+                if vor.has_nav_signal {
+                    flags |= bindings::SIMCONNECT_RECV_ID_VOR_LIST_HAS_NAV_SIGNAL;
+                }
+                if vor.has_localizer {
+                    flags |= bindings::SIMCONNECT_RECV_ID_VOR_LIST_HAS_LOCALIZER;
+                }
+                if vor.has_glide_slope {
+                    flags |= bindings::SIMCONNECT_RECV_ID_VOR_LIST_HAS_GLIDE_SLOPE;
+                }
+                if vor.has_dme {
+                    flags |= bindings::SIMCONNECT_RECV_ID_VOR_LIST_HAS_DME;
+                }
+
+                bindings::SIMCONNECT_DATA_FACILITY_VOR {
+                    _base: bindings::SIMCONNECT_DATA_FACILITY_NDB {
+                        _base: bindings::SIMCONNECT_DATA_FACILITY_WAYPOINT {
+                            _base: facility(&vor.icao, vor.lat, vor.lon, vor.alt),
+                            fMagVar: vor.mag_var,
+                        },
+                        fFrequency: vor.frequency.unwrap_or_default(),
+                    },
+                    Flags: flags,
+                    fLocalizer: vor.localizer.unwrap_or_default(),
+                    GlideLat: vor.glide_lat.unwrap_or_default(),
+                    GlideLon: vor.glide_lon.unwrap_or_default(),
+                    GlideAlt: vor.glide_alt.unwrap_or_default(),
+                    fGlideSlopeAngle: vor.glide_slope_angle.unwrap_or_default(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.push_facilities_list(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_VOR_LIST,
+            request_id,
+            entry_number,
+            out_of,
+            &records,
+        );
+    }
+
+    fn push_facilities_list<T: Copy>(
+        &self,
+        recv_id: bindings::SIMCONNECT_RECV_ID,
+        request_id: u32,
+        entry_number: u32,
+        out_of: u32,
+        records: &[T],
+    ) {
+        let header_size = std::mem::size_of::<bindings::SIMCONNECT_RECV_FACILITIES_LIST>();
+        let size = header_size + std::mem::size_of_val(records);
+
+        let message = bindings::SIMCONNECT_RECV_FACILITIES_LIST {
+            _base: bindings::SIMCONNECT_RECV {
+                dwSize: size as u32,
+                dwVersion: 0,
+                dwID: recv_id as u32,
+            },
+            dwRequestID: request_id,
+            dwArraySize: records.len() as u32,
+            dwEntryNumber: entry_number,
+            dwOutOf: out_of,
+        };
+
+        let mut bytes = Vec::with_capacity(size);
+        bytes.extend_from_slice(as_bytes(&message));
+        for record in records {
+            bytes.extend_from_slice(as_bytes(record));
+        }
+
+        self.push_raw(bytes);
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        // A panic while the lock is held can only come from a failed assertion in a test, so the state is still usable.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, call: MockCall) -> Result<(), SimConnectError> {
+        self.state().calls.push(call);
+
+        Ok(())
+    }
+}
+
+impl SimConnectBackend for MockSimulator {
+    fn add_to_data_definition(
+        &self,
+        define_id: u32,
+        datum_name: &str,
+        units_name: &str,
+        datum_type: i32,
+        epsilon: f32,
+        datum_id: u32,
+    ) -> Result<(), SimConnectError> {
+        self.state()
+            .data_definitions
+            .entry(define_id)
+            .or_default()
+            .push(MockDatum {
+                name: datum_name.to_string(),
+                unit: units_name.to_string(),
+                datum_type,
+                epsilon,
+            });
+
+        self.record(MockCall::AddToDataDefinition {
+            define_id,
+            datum_name: datum_name.to_string(),
+            units_name: units_name.to_string(),
+            datum_type,
+            epsilon,
+            datum_id,
+        })
+    }
+
+    fn clear_data_definition(&self, define_id: u32) -> Result<(), SimConnectError> {
+        self.state().data_definitions.remove(&define_id);
+
+        self.record(MockCall::ClearDataDefinition { define_id })
+    }
+
+    fn request_data_on_sim_object(
+        &self,
+        request_id: u32,
+        define_id: u32,
+        object_id: u32,
+        period: i32,
+        flags: u32,
+        origin: u32,
+        interval: u32,
+        limit: u32,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::RequestDataOnSimObject {
+            request_id,
+            define_id,
+            object_id,
+            period,
+            flags,
+            origin,
+            interval,
+            limit,
+        })
+    }
+
+    fn request_data_on_sim_object_type(
+        &self,
+        request_id: u32,
+        define_id: u32,
+        radius_meters: u32,
+        object_type: i32,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::RequestDataOnSimObjectType {
+            request_id,
+            define_id,
+            radius_meters,
+            object_type,
+        })
+    }
+
+    fn set_data_on_sim_object(
+        &self,
+        define_id: u32,
+        object_id: u32,
+        flags: u32,
+        array_count: u32,
+        unit_size: u32,
+        data: &[u8],
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::SetDataOnSimObject {
+            define_id,
+            object_id,
+            flags,
+            array_count,
+            unit_size,
+            data: data.to_vec(),
+        })
+    }
+
+    fn subscribe_to_system_event(
+        &self,
+        event_id: u32,
+        event_name: &str,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::SubscribeToSystemEvent {
+            event_id,
+            event_name: event_name.to_string(),
+        })
+    }
+
+    fn unsubscribe_from_system_event(&self, event_id: u32) -> Result<(), SimConnectError> {
+        self.record(MockCall::UnsubscribeFromSystemEvent { event_id })
+    }
+
+    fn set_system_event_state(&self, event_id: u32, state: u32) -> Result<(), SimConnectError> {
+        self.record(MockCall::SetSystemEventState { event_id, state })
+    }
+
+    fn request_system_state(&self, request_id: u32, state: &str) -> Result<(), SimConnectError> {
+        self.record(MockCall::RequestSystemState {
+            request_id,
+            state: state.to_string(),
+        })
+    }
+
+    fn set_system_state(
+        &self,
+        state: &str,
+        integer: u32,
+        float: f32,
+        string: &str,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::SetSystemState {
+            state: state.to_string(),
+            integer,
+            float,
+            string: string.to_string(),
+        })
+    }
+
+    fn map_client_event_to_sim_event(
+        &self,
+        event_id: u32,
+        event_name: &str,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::MapClientEventToSimEvent {
+            event_id,
+            event_name: event_name.to_string(),
+        })
+    }
+
+    fn transmit_client_event(
+        &self,
+        object_id: u32,
+        event_id: u32,
+        data: u32,
+        group_id: u32,
+        flags: u32,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::TransmitClientEvent {
+            object_id,
+            event_id,
+            data,
+            group_id,
+            flags,
+        })
+    }
+
+    fn transmit_client_event_ex1(
+        &self,
+        object_id: u32,
+        event_id: u32,
+        group_id: u32,
+        flags: u32,
+        data: [u32; 5],
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::TransmitClientEventEx1 {
+            object_id,
+            event_id,
+            group_id,
+            flags,
+            data,
+        })
+    }
+
+    fn add_client_event_to_notification_group(
+        &self,
+        group_id: u32,
+        event_id: u32,
+        maskable: bool,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::AddClientEventToNotificationGroup {
+            group_id,
+            event_id,
+            maskable,
+        })
+    }
+
+    fn remove_client_event(&self, group_id: u32, event_id: u32) -> Result<(), SimConnectError> {
+        self.record(MockCall::RemoveClientEvent { group_id, event_id })
+    }
+
+    fn set_notification_group_priority(
+        &self,
+        group_id: u32,
+        priority: u32,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::SetNotificationGroupPriority { group_id, priority })
+    }
+
+    fn clear_notification_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        self.record(MockCall::ClearNotificationGroup { group_id })
+    }
+
+    fn request_notification_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        self.record(MockCall::RequestNotificationGroup { group_id })
+    }
+
+    fn map_input_event_to_client_event(
+        &self,
+        group_id: u32,
+        input_definition: &str,
+        down_event_id: u32,
+        down_value: u32,
+        up_event_id: u32,
+        up_value: u32,
+        maskable: bool,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::MapInputEventToClientEvent {
+            group_id,
+            input_definition: input_definition.to_string(),
+            down_event_id,
+            down_value,
+            up_event_id,
+            up_value,
+            maskable,
+        })
+    }
+
+    fn set_input_group_priority(
+        &self,
+        group_id: u32,
+        priority: u32,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::SetInputGroupPriority { group_id, priority })
+    }
+
+    fn remove_input_event(
+        &self,
+        group_id: u32,
+        input_definition: &str,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::RemoveInputEvent {
+            group_id,
+            input_definition: input_definition.to_string(),
+        })
+    }
+
+    fn clear_input_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        self.record(MockCall::ClearInputGroup { group_id })
+    }
+
+    fn set_input_group_state(&self, group_id: u32, state: u32) -> Result<(), SimConnectError> {
+        self.record(MockCall::SetInputGroupState { group_id, state })
+    }
+
+    fn request_reserved_key(
+        &self,
+        event_id: u32,
+        key_choice1: &str,
+        key_choice2: &str,
+        key_choice3: &str,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::RequestReservedKey {
+            event_id,
+            key_choices: [
+                key_choice1.to_string(),
+                key_choice2.to_string(),
+                key_choice3.to_string(),
+            ],
+        })
+    }
+
+    fn request_facilities_list(
+        &self,
+        facility_type: i32,
+        request_id: u32,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::RequestFacilitiesList {
+            facility_type,
+            request_id,
+        })
+    }
+
+    fn subscribe_to_facilities(
+        &self,
+        facility_type: i32,
+        request_id: u32,
+    ) -> Result<(), SimConnectError> {
+        self.record(MockCall::SubscribeToFacilities {
+            facility_type,
+            request_id,
+        })
+    }
+
+    fn unsubscribe_to_facilities(&self, facility_type: i32) -> Result<(), SimConnectError> {
+        self.record(MockCall::UnsubscribeToFacilities { facility_type })
+    }
+
+    fn get_next_dispatch(&mut self) -> Result<Option<&[u8]>, SimConnectError> {
+        let mut state = self.state();
+
+        match state.queue.pop_front() {
+            Some(data) => {
+                drop(state);
+                self.current = data;
+                Ok(Some(&self.current))
+            }
+            None if state.disconnected => Err(SimConnectError::Disconnected),
+            None => Ok(None),
+        }
+    }
+
+    fn dispatch_event(&self) -> &DispatchEvent {
+        &self.dispatch_event
+    }
+
+    fn close(&mut self) -> Result<(), SimConnectError> {
+        self.record(MockCall::Close)
+    }
+}
+
+fn header<T>(recv_id: bindings::SIMCONNECT_RECV_ID) -> bindings::SIMCONNECT_RECV {
+    bindings::SIMCONNECT_RECV {
+        dwSize: std::mem::size_of::<T>() as u32,
+        dwVersion: 0,
+        dwID: recv_id as u32,
+    }
+}
+
+fn event_message(group_id: u32, event_id: u32, data: u32) -> bindings::SIMCONNECT_RECV_EVENT {
+    bindings::SIMCONNECT_RECV_EVENT {
+        _base: header::<bindings::SIMCONNECT_RECV_EVENT>(
+            bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT,
+        ),
+        uGroupID: group_id,
+        uEventID: event_id,
+        dwData: data,
+    }
+}
+
+fn facility(
+    icao: &str,
+    lat: f64,
+    lon: f64,
+    alt: f64,
+) -> bindings::SIMCONNECT_DATA_FACILITY_AIRPORT {
+    bindings::SIMCONNECT_DATA_FACILITY_AIRPORT {
+        Icao: string_to_fixed_c_str(icao),
+        Latitude: lat,
+        Longitude: lon,
+        Altitude: alt,
+    }
+}
+
+/// View a `#[repr(C, packed)]` binding as the bytes that are sent over the wire.
+fn as_bytes<T: Copy>(value: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>()) }
+}
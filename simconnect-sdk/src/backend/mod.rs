@@ -0,0 +1,224 @@
+mod dispatch_event;
+#[cfg(windows)]
+mod ffi;
+#[cfg(feature = "mock")]
+mod mock;
+mod tcp;
+
+pub use dispatch_event::DispatchEvent;
+#[cfg(windows)]
+pub use ffi::FfiBackend;
+#[cfg(feature = "mock")]
+pub use mock::{MockCall, MockDatum, MockSimulator};
+pub use tcp::TcpBackend;
+
+use crate::SimConnectError;
+
+/// The transport used by [`crate::SimConnect`] to talk to the simulator.
+///
+/// Every method maps 1:1 to a function of the SimConnect C API and takes the same arguments, minus the connection handle.
+/// [`crate::SimConnect`] is responsible for the ID bookkeeping and for decoding the received data, so a backend only has to carry requests to the simulator and hand back the raw `SIMCONNECT_RECV` messages.
+///
+/// [`FfiBackend`] is the default implementation and is backed by the statically linked `SimConnect.lib`. It is only available on Windows.
+/// [`TcpBackend`] speaks the SimConnect network protocol directly and is available on every platform.
+/// Other implementations can be plugged in by using [`crate::SimConnect::with_backend`].
+/// With the `mock` feature enabled, `MockSimulator` provides one that can be scripted from tests.
+pub trait SimConnectBackend: std::fmt::Debug + Send {
+    /// `SimConnect_AddToDataDefinition`.
+    fn add_to_data_definition(
+        &self,
+        define_id: u32,
+        datum_name: &str,
+        units_name: &str,
+        datum_type: i32,
+        epsilon: f32,
+        datum_id: u32,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_ClearDataDefinition`.
+    fn clear_data_definition(&self, define_id: u32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_RequestDataOnSimObject`.
+    #[allow(clippy::too_many_arguments)]
+    fn request_data_on_sim_object(
+        &self,
+        request_id: u32,
+        define_id: u32,
+        object_id: u32,
+        period: i32,
+        flags: u32,
+        origin: u32,
+        interval: u32,
+        limit: u32,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_RequestDataOnSimObjectType`.
+    fn request_data_on_sim_object_type(
+        &self,
+        request_id: u32,
+        define_id: u32,
+        radius_meters: u32,
+        object_type: i32,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_SetDataOnSimObject`. `data` holds `array_count` entries of `unit_size` bytes each.
+    fn set_data_on_sim_object(
+        &self,
+        define_id: u32,
+        object_id: u32,
+        flags: u32,
+        array_count: u32,
+        unit_size: u32,
+        data: &[u8],
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_SubscribeToSystemEvent`.
+    fn subscribe_to_system_event(
+        &self,
+        event_id: u32,
+        event_name: &str,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_UnsubscribeFromSystemEvent`.
+    fn unsubscribe_from_system_event(&self, event_id: u32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_SetSystemEventState`.
+    fn set_system_event_state(&self, event_id: u32, state: u32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_RequestSystemState`.
+    fn request_system_state(&self, request_id: u32, state: &str) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_SetSystemState`.
+    fn set_system_state(
+        &self,
+        state: &str,
+        integer: u32,
+        float: f32,
+        string: &str,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_MapClientEventToSimEvent`.
+    fn map_client_event_to_sim_event(
+        &self,
+        event_id: u32,
+        event_name: &str,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_TransmitClientEvent`.
+    fn transmit_client_event(
+        &self,
+        object_id: u32,
+        event_id: u32,
+        data: u32,
+        group_id: u32,
+        flags: u32,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_TransmitClientEvent_EX1`.
+    fn transmit_client_event_ex1(
+        &self,
+        object_id: u32,
+        event_id: u32,
+        group_id: u32,
+        flags: u32,
+        data: [u32; 5],
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_AddClientEventToNotificationGroup`.
+    fn add_client_event_to_notification_group(
+        &self,
+        group_id: u32,
+        event_id: u32,
+        maskable: bool,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_RemoveClientEvent`.
+    fn remove_client_event(&self, group_id: u32, event_id: u32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_SetNotificationGroupPriority`.
+    fn set_notification_group_priority(
+        &self,
+        group_id: u32,
+        priority: u32,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_ClearNotificationGroup`.
+    fn clear_notification_group(&self, group_id: u32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_RequestNotificationGroup`.
+    fn request_notification_group(&self, group_id: u32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_MapInputEventToClientEvent`.
+    #[allow(clippy::too_many_arguments)]
+    fn map_input_event_to_client_event(
+        &self,
+        group_id: u32,
+        input_definition: &str,
+        down_event_id: u32,
+        down_value: u32,
+        up_event_id: u32,
+        up_value: u32,
+        maskable: bool,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_SetInputGroupPriority`.
+    fn set_input_group_priority(&self, group_id: u32, priority: u32)
+        -> Result<(), SimConnectError>;
+
+    /// `SimConnect_RemoveInputEvent`.
+    fn remove_input_event(
+        &self,
+        group_id: u32,
+        input_definition: &str,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_ClearInputGroup`.
+    fn clear_input_group(&self, group_id: u32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_SetInputGroupState`.
+    fn set_input_group_state(&self, group_id: u32, state: u32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_RequestReservedKey`. Unused key choices are empty strings.
+    fn request_reserved_key(
+        &self,
+        event_id: u32,
+        key_choice1: &str,
+        key_choice2: &str,
+        key_choice3: &str,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_RequestFacilitiesList`.
+    fn request_facilities_list(
+        &self,
+        facility_type: i32,
+        request_id: u32,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_SubscribeToFacilities`.
+    fn subscribe_to_facilities(
+        &self,
+        facility_type: i32,
+        request_id: u32,
+    ) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_UnsubscribeToFacilities`.
+    fn unsubscribe_to_facilities(&self, facility_type: i32) -> Result<(), SimConnectError>;
+
+    /// `SimConnect_GetNextDispatch`.
+    ///
+    /// Returns the next raw message, starting with its `SIMCONNECT_RECV` header, or `None` if there is nothing to receive.
+    /// The returned buffer only has to stay valid until the next call.
+    ///
+    /// Once the connection is lost without a `SIMCONNECT_RECV_QUIT`, [`SimConnectError::Disconnected`] is returned and the dispatch event is signaled, so that waits for new messages end.
+    fn get_next_dispatch(&mut self) -> Result<Option<&[u8]>, SimConnectError>;
+
+    /// The event that is signaled when new messages are ready to be received through [`SimConnectBackend::get_next_dispatch`].
+    ///
+    /// It allows waiting for messages instead of polling for them.
+    fn dispatch_event(&self) -> &DispatchEvent;
+
+    /// `SimConnect_Close`.
+    ///
+    /// Called once, when the owning [`crate::SimConnect`] is dropped.
+    fn close(&mut self) -> Result<(), SimConnectError>;
+}
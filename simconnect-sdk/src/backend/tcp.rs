@@ -0,0 +1,619 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+
+use crate::{DispatchEvent, SimConnectBackend, SimConnectError};
+
+// The version of the SimConnect network protocol.
+const PROTOCOL_VERSION: u32 = 4;
+
+// Every packet sent by the client starts with a header made of the packet size, the protocol version, the packet type and the send ID.
+const HEADER_SIZE: usize = 16;
+// The packet type is the packet ID with the highest nibble set.
+const PACKET_TYPE_MASK: u32 = 0xF000_0000;
+// The size of the string arguments, which are sent as fixed size, NUL padded, strings.
+const STRING_SIZE: usize = 256;
+// The key choices of `SimConnect_RequestReservedKey` are sent as shorter strings.
+const KEY_CHOICE_SIZE: usize = 30;
+// The size of the `SIMCONNECT_RECV` header of every message sent by the server.
+const RECV_HEADER_SIZE: usize = 12;
+// Upper bound for the size of a single message sent by the server, to guard against a corrupt stream.
+const MAX_RECV_SIZE: usize = 16 * 1024 * 1024;
+
+// The version of SimConnect announced in the `Open` packet.
+const SIMCONNECT_VERSION: [u32; 4] = [11, 0, 62651, 3];
+
+const PACKET_OPEN: u32 = 0x01;
+const PACKET_MAP_CLIENT_EVENT_TO_SIM_EVENT: u32 = 0x04;
+const PACKET_TRANSMIT_CLIENT_EVENT: u32 = 0x05;
+const PACKET_SET_SYSTEM_EVENT_STATE: u32 = 0x06;
+const PACKET_ADD_CLIENT_EVENT_TO_NOTIFICATION_GROUP: u32 = 0x07;
+const PACKET_REMOVE_CLIENT_EVENT: u32 = 0x08;
+const PACKET_SET_NOTIFICATION_GROUP_PRIORITY: u32 = 0x09;
+const PACKET_CLEAR_NOTIFICATION_GROUP: u32 = 0x0A;
+const PACKET_REQUEST_NOTIFICATION_GROUP: u32 = 0x0B;
+const PACKET_ADD_TO_DATA_DEFINITION: u32 = 0x0C;
+const PACKET_CLEAR_DATA_DEFINITION: u32 = 0x0D;
+const PACKET_REQUEST_DATA_ON_SIM_OBJECT: u32 = 0x0E;
+const PACKET_REQUEST_DATA_ON_SIM_OBJECT_TYPE: u32 = 0x0F;
+const PACKET_SET_DATA_ON_SIM_OBJECT: u32 = 0x10;
+const PACKET_MAP_INPUT_EVENT_TO_CLIENT_EVENT: u32 = 0x11;
+const PACKET_SET_INPUT_GROUP_PRIORITY: u32 = 0x12;
+const PACKET_REMOVE_INPUT_EVENT: u32 = 0x13;
+const PACKET_CLEAR_INPUT_GROUP: u32 = 0x14;
+const PACKET_SET_INPUT_GROUP_STATE: u32 = 0x15;
+const PACKET_REQUEST_RESERVED_KEY: u32 = 0x16;
+const PACKET_SUBSCRIBE_TO_SYSTEM_EVENT: u32 = 0x17;
+const PACKET_UNSUBSCRIBE_FROM_SYSTEM_EVENT: u32 = 0x18;
+const PACKET_REQUEST_SYSTEM_STATE: u32 = 0x35;
+const PACKET_SET_SYSTEM_STATE: u32 = 0x36;
+const PACKET_SUBSCRIBE_TO_FACILITIES: u32 = 0x41;
+const PACKET_UNSUBSCRIBE_TO_FACILITIES: u32 = 0x42;
+const PACKET_REQUEST_FACILITIES_LIST: u32 = 0x43;
+const PACKET_TRANSMIT_CLIENT_EVENT_EX1: u32 = 0x44;
+
+/// [`SimConnectBackend`] that speaks the SimConnect network protocol over TCP, without going through the SimConnect C API.
+///
+/// The simulator has to be configured to accept remote connections through the `SimConnect.xml` file, e.g.
+///
+/// ```xml
+/// <SimConnect.Comm>
+///     <Protocol>IPv4</Protocol>
+///     <Scope>global</Scope>
+///     <Address>0.0.0.0</Address>
+///     <Port>500</Port>
+/// </SimConnect.Comm>
+/// ```
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use simconnect_sdk::{SimConnect, TcpBackend};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let backend = TcpBackend::connect("Remote client", "192.168.1.10:500")?;
+///     let mut client = SimConnect::with_backend(backend);
+///
+///     let notification = client.get_next_dispatch()?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TcpBackend {
+    stream: TcpStream,
+    next_send_id: AtomicU32,
+    receiver: Receiver<Result<Vec<u8>, SimConnectError>>,
+    dispatch_event: DispatchEvent,
+    current: Vec<u8>,
+}
+
+impl TcpBackend {
+    /// Open a connection to the simulator listening on `addr`.
+    ///
+    /// # Remarks
+    /// The simulator answers with a `SIMCONNECT_RECV_OPEN` message, which is received as [`crate::Notification::Open`].
+    #[tracing::instrument(name = "TcpBackend::connect", level = "debug", skip(addr))]
+    pub fn connect(name: &str, addr: impl ToSocketAddrs) -> Result<Self, SimConnectError> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+
+        let (sender, receiver) = mpsc::channel();
+        let dispatch_event = DispatchEvent::new();
+
+        let reader = stream.try_clone()?;
+        let reader_event = dispatch_event.clone();
+        std::thread::Builder::new()
+            .name("simconnect-tcp-reader".to_string())
+            .spawn(move || read_messages(reader, sender, reader_event))?;
+
+        let backend = Self {
+            stream,
+            next_send_id: AtomicU32::new(1),
+            receiver,
+            dispatch_event,
+            current: Vec::new(),
+        };
+
+        let mut packet = Packet::new(PACKET_OPEN);
+        packet.string(name);
+        packet.u32(0);
+        packet.bytes(&[0, b'X', b'S', b'F']);
+        for version in SIMCONNECT_VERSION {
+            packet.u32(version);
+        }
+        backend.send(packet)?;
+
+        Ok(backend)
+    }
+
+    fn send(&self, packet: Packet) -> Result<(), SimConnectError> {
+        let send_id = self.next_send_id.fetch_add(1, Ordering::Relaxed);
+
+        (&self.stream).write_all(&packet.finish(send_id))?;
+
+        Ok(())
+    }
+}
+
+impl SimConnectBackend for TcpBackend {
+    fn add_to_data_definition(
+        &self,
+        define_id: u32,
+        datum_name: &str,
+        units_name: &str,
+        datum_type: i32,
+        epsilon: f32,
+        datum_id: u32,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_ADD_TO_DATA_DEFINITION);
+        packet.u32(define_id);
+        packet.string(datum_name);
+        packet.string(units_name);
+        packet.i32(datum_type);
+        packet.f32(epsilon);
+        packet.u32(datum_id);
+
+        self.send(packet)
+    }
+
+    fn clear_data_definition(&self, define_id: u32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_CLEAR_DATA_DEFINITION);
+        packet.u32(define_id);
+
+        self.send(packet)
+    }
+
+    fn request_data_on_sim_object(
+        &self,
+        request_id: u32,
+        define_id: u32,
+        object_id: u32,
+        period: i32,
+        flags: u32,
+        origin: u32,
+        interval: u32,
+        limit: u32,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_REQUEST_DATA_ON_SIM_OBJECT);
+        packet.u32(request_id);
+        packet.u32(define_id);
+        packet.u32(object_id);
+        packet.i32(period);
+        packet.u32(flags);
+        packet.u32(origin);
+        packet.u32(interval);
+        packet.u32(limit);
+
+        self.send(packet)
+    }
+
+    fn request_data_on_sim_object_type(
+        &self,
+        request_id: u32,
+        define_id: u32,
+        radius_meters: u32,
+        object_type: i32,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_REQUEST_DATA_ON_SIM_OBJECT_TYPE);
+        packet.u32(request_id);
+        packet.u32(define_id);
+        packet.u32(radius_meters);
+        packet.i32(object_type);
+
+        self.send(packet)
+    }
+
+    fn set_data_on_sim_object(
+        &self,
+        define_id: u32,
+        object_id: u32,
+        flags: u32,
+        array_count: u32,
+        unit_size: u32,
+        data: &[u8],
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_SET_DATA_ON_SIM_OBJECT);
+        packet.u32(define_id);
+        packet.u32(object_id);
+        packet.u32(flags);
+        packet.u32(array_count);
+        packet.u32(unit_size);
+        packet.bytes(data);
+
+        self.send(packet)
+    }
+
+    fn subscribe_to_system_event(
+        &self,
+        event_id: u32,
+        event_name: &str,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_SUBSCRIBE_TO_SYSTEM_EVENT);
+        packet.u32(event_id);
+        packet.string(event_name);
+
+        self.send(packet)
+    }
+
+    fn unsubscribe_from_system_event(&self, event_id: u32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_UNSUBSCRIBE_FROM_SYSTEM_EVENT);
+        packet.u32(event_id);
+
+        self.send(packet)
+    }
+
+    fn set_system_event_state(&self, event_id: u32, state: u32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_SET_SYSTEM_EVENT_STATE);
+        packet.u32(event_id);
+        packet.u32(state);
+
+        self.send(packet)
+    }
+
+    fn request_system_state(&self, request_id: u32, state: &str) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_REQUEST_SYSTEM_STATE);
+        packet.u32(request_id);
+        packet.string(state);
+
+        self.send(packet)
+    }
+
+    fn set_system_state(
+        &self,
+        state: &str,
+        integer: u32,
+        float: f32,
+        string: &str,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_SET_SYSTEM_STATE);
+        packet.string(state);
+        packet.u32(integer);
+        packet.f32(float);
+        packet.string(string);
+
+        self.send(packet)
+    }
+
+    fn map_client_event_to_sim_event(
+        &self,
+        event_id: u32,
+        event_name: &str,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_MAP_CLIENT_EVENT_TO_SIM_EVENT);
+        packet.u32(event_id);
+        packet.string(event_name);
+
+        self.send(packet)
+    }
+
+    fn transmit_client_event(
+        &self,
+        object_id: u32,
+        event_id: u32,
+        data: u32,
+        group_id: u32,
+        flags: u32,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_TRANSMIT_CLIENT_EVENT);
+        packet.u32(object_id);
+        packet.u32(event_id);
+        packet.u32(data);
+        packet.u32(group_id);
+        packet.u32(flags);
+
+        self.send(packet)
+    }
+
+    fn transmit_client_event_ex1(
+        &self,
+        object_id: u32,
+        event_id: u32,
+        group_id: u32,
+        flags: u32,
+        data: [u32; 5],
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_TRANSMIT_CLIENT_EVENT_EX1);
+        packet.u32(object_id);
+        packet.u32(event_id);
+        packet.u32(group_id);
+        packet.u32(flags);
+        for value in data {
+            packet.u32(value);
+        }
+
+        self.send(packet)
+    }
+
+    fn add_client_event_to_notification_group(
+        &self,
+        group_id: u32,
+        event_id: u32,
+        maskable: bool,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_ADD_CLIENT_EVENT_TO_NOTIFICATION_GROUP);
+        packet.u32(group_id);
+        packet.u32(event_id);
+        packet.u32(maskable as u32);
+
+        self.send(packet)
+    }
+
+    fn remove_client_event(&self, group_id: u32, event_id: u32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_REMOVE_CLIENT_EVENT);
+        packet.u32(group_id);
+        packet.u32(event_id);
+
+        self.send(packet)
+    }
+
+    fn set_notification_group_priority(
+        &self,
+        group_id: u32,
+        priority: u32,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_SET_NOTIFICATION_GROUP_PRIORITY);
+        packet.u32(group_id);
+        packet.u32(priority);
+
+        self.send(packet)
+    }
+
+    fn clear_notification_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_CLEAR_NOTIFICATION_GROUP);
+        packet.u32(group_id);
+
+        self.send(packet)
+    }
+
+    fn request_notification_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_REQUEST_NOTIFICATION_GROUP);
+        packet.u32(group_id);
+        packet.u32(0);
+        packet.u32(0);
+
+        self.send(packet)
+    }
+
+    fn map_input_event_to_client_event(
+        &self,
+        group_id: u32,
+        input_definition: &str,
+        down_event_id: u32,
+        down_value: u32,
+        up_event_id: u32,
+        up_value: u32,
+        maskable: bool,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_MAP_INPUT_EVENT_TO_CLIENT_EVENT);
+        packet.u32(group_id);
+        packet.string(input_definition);
+        packet.u32(down_event_id);
+        packet.u32(down_value);
+        packet.u32(up_event_id);
+        packet.u32(up_value);
+        packet.u32(maskable as u32);
+
+        self.send(packet)
+    }
+
+    fn set_input_group_priority(
+        &self,
+        group_id: u32,
+        priority: u32,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_SET_INPUT_GROUP_PRIORITY);
+        packet.u32(group_id);
+        packet.u32(priority);
+
+        self.send(packet)
+    }
+
+    fn remove_input_event(
+        &self,
+        group_id: u32,
+        input_definition: &str,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_REMOVE_INPUT_EVENT);
+        packet.u32(group_id);
+        packet.string(input_definition);
+
+        self.send(packet)
+    }
+
+    fn clear_input_group(&self, group_id: u32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_CLEAR_INPUT_GROUP);
+        packet.u32(group_id);
+
+        self.send(packet)
+    }
+
+    fn set_input_group_state(&self, group_id: u32, state: u32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_SET_INPUT_GROUP_STATE);
+        packet.u32(group_id);
+        packet.u32(state);
+
+        self.send(packet)
+    }
+
+    fn request_reserved_key(
+        &self,
+        event_id: u32,
+        key_choice1: &str,
+        key_choice2: &str,
+        key_choice3: &str,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_REQUEST_RESERVED_KEY);
+        packet.u32(event_id);
+        packet.fixed_string(key_choice1, KEY_CHOICE_SIZE);
+        packet.fixed_string(key_choice2, KEY_CHOICE_SIZE);
+        packet.fixed_string(key_choice3, KEY_CHOICE_SIZE);
+
+        self.send(packet)
+    }
+
+    fn request_facilities_list(
+        &self,
+        facility_type: i32,
+        request_id: u32,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_REQUEST_FACILITIES_LIST);
+        packet.i32(facility_type);
+        packet.u32(request_id);
+
+        self.send(packet)
+    }
+
+    fn subscribe_to_facilities(
+        &self,
+        facility_type: i32,
+        request_id: u32,
+    ) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_SUBSCRIBE_TO_FACILITIES);
+        packet.i32(facility_type);
+        packet.u32(request_id);
+
+        self.send(packet)
+    }
+
+    fn unsubscribe_to_facilities(&self, facility_type: i32) -> Result<(), SimConnectError> {
+        let mut packet = Packet::new(PACKET_UNSUBSCRIBE_TO_FACILITIES);
+        packet.i32(facility_type);
+
+        self.send(packet)
+    }
+
+    fn get_next_dispatch(&mut self) -> Result<Option<&[u8]>, SimConnectError> {
+        match self.receiver.try_recv() {
+            Ok(message) => {
+                self.current = message?;
+                Ok(Some(&self.current))
+            }
+            Err(TryRecvError::Empty) => Ok(None),
+            // the reader thread stops once the connection is gone
+            Err(TryRecvError::Disconnected) => Err(SimConnectError::Disconnected),
+        }
+    }
+
+    fn dispatch_event(&self) -> &DispatchEvent {
+        &self.dispatch_event
+    }
+
+    fn close(&mut self) -> Result<(), SimConnectError> {
+        // The reader thread stops as soon as the socket is shut down.
+        self.stream.shutdown(Shutdown::Both)?;
+
+        Ok(())
+    }
+}
+
+/// A packet sent by the client, in the making.
+struct Packet {
+    data: Vec<u8>,
+}
+
+impl Packet {
+    fn new(id: u32) -> Self {
+        let mut data = Vec::with_capacity(HEADER_SIZE + 2 * STRING_SIZE);
+        // the size and the send ID are filled in by `finish`
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+        data.extend_from_slice(&(PACKET_TYPE_MASK | id).to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+
+        Self { data }
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn bytes(&mut self, value: &[u8]) {
+        self.data.extend_from_slice(value);
+    }
+
+    /// Strings are truncated to 255 bytes so that they are always NUL terminated.
+    fn string(&mut self, value: &str) {
+        self.fixed_string(value, STRING_SIZE);
+    }
+
+    /// Strings are truncated to `size - 1` bytes so that they are always NUL terminated.
+    fn fixed_string(&mut self, value: &str, size: usize) {
+        let bytes = &value.as_bytes()[..value.len().min(size - 1)];
+
+        self.data.extend_from_slice(bytes);
+        self.data.resize(self.data.len() + size - bytes.len(), 0);
+    }
+
+    fn finish(mut self, send_id: u32) -> Vec<u8> {
+        let size = self.data.len() as u32;
+
+        self.data[0..4].copy_from_slice(&size.to_le_bytes());
+        self.data[12..16].copy_from_slice(&send_id.to_le_bytes());
+
+        self.data
+    }
+}
+
+/// Split the stream sent by the server into messages. Each message starts with a `SIMCONNECT_RECV` header, whose first field is the size of the message.
+fn read_messages(
+    mut stream: TcpStream,
+    sender: Sender<Result<Vec<u8>, SimConnectError>>,
+    dispatch_event: DispatchEvent,
+) {
+    loop {
+        let message = match read_message(&mut stream) {
+            Ok(Some(message)) => Ok(message),
+            // the connection has been closed, possibly without the server quitting
+            Ok(None) => Err(SimConnectError::Disconnected),
+            Err(e) => Err(e),
+        };
+
+        let failed = message.is_err();
+
+        if sender.send(message).is_err() {
+            break;
+        }
+        dispatch_event.signal();
+
+        if failed {
+            break;
+        }
+    }
+}
+
+fn read_message(stream: &mut TcpStream) -> Result<Option<Vec<u8>>, SimConnectError> {
+    let mut size = [0; 4];
+
+    match stream.read_exact(&mut size) {
+        Ok(()) => (),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        // `close` shuts the socket down while the read is still pending
+        Err(e) if e.kind() == ErrorKind::ConnectionAborted => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let size = u32::from_le_bytes(size) as usize;
+
+    // Only the header is checked here, the size of the rest of the message is checked against its ID by `SimConnect::dispatch`.
+    if !(RECV_HEADER_SIZE..=MAX_RECV_SIZE).contains(&size) {
+        return Err(SimConnectError::UnexpectedError(format!(
+            "received a message with an invalid size of {size} bytes"
+        )));
+    }
+
+    let mut message = vec![0; size];
+    message[..4].copy_from_slice(&(size as u32).to_le_bytes());
+    stream.read_exact(&mut message[4..])?;
+
+    Ok(Some(message))
+}
@@ -0,0 +1,285 @@
+use crate::{bindings, string_to_fixed_c_str, DataReader, DataType, SimConnectError};
+
+// The size of the marker name of `SIMCONNECT_DATA_MARKERSTATE`.
+const MARKER_NAME_SIZE: usize = 64;
+
+/// A structured SimConnect data type, which can be used as the type of a [`crate::SimConnectObject`] field.
+///
+/// # Remarks
+/// The structs with structured fields are read one field at a time instead of being transmuted from a packed struct.
+pub trait DataStruct: Sized {
+    /// The data type the field is requested as.
+    const DATA_TYPE: DataType;
+
+    /// Read the value from the data of an object.
+    fn read(reader: &mut DataReader<'_>) -> Result<Self, SimConnectError>;
+
+    /// Append the value to the data of an object.
+    fn write(&self, data: &mut Vec<u8>);
+}
+
+/// `SIMCONNECT_DATA_LATLONALT`. A position, e.g. `STRUCT LATLONALT`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct LatLonAlt {
+    /// Latitude in degrees.
+    pub lat: f64,
+    /// Longitude in degrees.
+    pub lon: f64,
+    /// Altitude in feet.
+    pub alt: f64,
+}
+
+impl DataStruct for LatLonAlt {
+    const DATA_TYPE: DataType = DataType::LatLonAlt;
+
+    fn read(reader: &mut DataReader<'_>) -> Result<Self, SimConnectError> {
+        Ok(Self {
+            lat: reader.f64()?,
+            lon: reader.f64()?,
+            alt: reader.f64()?,
+        })
+    }
+
+    fn write(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.lat.to_le_bytes());
+        data.extend_from_slice(&self.lon.to_le_bytes());
+        data.extend_from_slice(&self.alt.to_le_bytes());
+    }
+}
+
+/// `SIMCONNECT_DATA_XYZ`. A vector, e.g. `STRUCT WORLDVELOCITY`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct XYZ {
+    /// The X component.
+    pub x: f64,
+    /// The Y component.
+    pub y: f64,
+    /// The Z component.
+    pub z: f64,
+}
+
+impl DataStruct for XYZ {
+    const DATA_TYPE: DataType = DataType::XYZ;
+
+    fn read(reader: &mut DataReader<'_>) -> Result<Self, SimConnectError> {
+        Ok(Self {
+            x: reader.f64()?,
+            y: reader.f64()?,
+            z: reader.f64()?,
+        })
+    }
+
+    fn write(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.x.to_le_bytes());
+        data.extend_from_slice(&self.y.to_le_bytes());
+        data.extend_from_slice(&self.z.to_le_bytes());
+    }
+}
+
+/// The airspeed of an [`InitPosition`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum InitPositionAirspeed {
+    /// The airspeed in knots.
+    Knots(u32),
+    /// The cruise speed of the aircraft.
+    Cruise,
+    /// The current airspeed of the aircraft.
+    #[default]
+    Keep,
+}
+
+impl From<u32> for InitPositionAirspeed {
+    fn from(value: u32) -> Self {
+        match value {
+            bindings::INITPOSITION_AIRSPEED_CRUISE => InitPositionAirspeed::Cruise,
+            bindings::INITPOSITION_AIRSPEED_KEEP => InitPositionAirspeed::Keep,
+            knots => InitPositionAirspeed::Knots(knots),
+        }
+    }
+}
+
+impl From<InitPositionAirspeed> for u32 {
+    fn from(airspeed: InitPositionAirspeed) -> Self {
+        match airspeed {
+            InitPositionAirspeed::Knots(knots) => knots,
+            InitPositionAirspeed::Cruise => bindings::INITPOSITION_AIRSPEED_CRUISE,
+            InitPositionAirspeed::Keep => bindings::INITPOSITION_AIRSPEED_KEEP,
+        }
+    }
+}
+
+/// `SIMCONNECT_DATA_INITPOSITION`. The initial position of an aircraft, e.g. `Initial Position`, which repositions the aircraft when set.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct InitPosition {
+    /// Latitude in degrees.
+    pub lat: f64,
+    /// Longitude in degrees.
+    pub lon: f64,
+    /// Altitude in feet.
+    pub alt: f64,
+    /// Pitch in degrees.
+    pub pitch: f64,
+    /// Bank in degrees.
+    pub bank: f64,
+    /// Heading in degrees.
+    pub heading: f64,
+    /// Whether the aircraft is on the ground. If `true`, `alt` is ignored.
+    pub on_ground: bool,
+    /// The airspeed of the aircraft.
+    pub airspeed: InitPositionAirspeed,
+}
+
+impl DataStruct for InitPosition {
+    const DATA_TYPE: DataType = DataType::InitPosition;
+
+    fn read(reader: &mut DataReader<'_>) -> Result<Self, SimConnectError> {
+        Ok(Self {
+            lat: reader.f64()?,
+            lon: reader.f64()?,
+            alt: reader.f64()?,
+            pitch: reader.f64()?,
+            bank: reader.f64()?,
+            heading: reader.f64()?,
+            on_ground: reader.i32()? != 0,
+            airspeed: InitPositionAirspeed::from(reader.i32()? as u32),
+        })
+    }
+
+    fn write(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.lat.to_le_bytes());
+        data.extend_from_slice(&self.lon.to_le_bytes());
+        data.extend_from_slice(&self.alt.to_le_bytes());
+        data.extend_from_slice(&self.pitch.to_le_bytes());
+        data.extend_from_slice(&self.bank.to_le_bytes());
+        data.extend_from_slice(&self.heading.to_le_bytes());
+        data.extend_from_slice(&u32::from(self.on_ground).to_le_bytes());
+        data.extend_from_slice(&u32::from(self.airspeed).to_le_bytes());
+    }
+}
+
+/// `SIMCONNECT_DATA_MARKERSTATE`. The state of a marker of the aircraft, e.g. `Tailhook` or `Canopy`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MarkerState {
+    /// The name of the marker. At most 63 characters.
+    pub name: String,
+    /// Whether the marker is on.
+    pub on: bool,
+}
+
+impl DataStruct for MarkerState {
+    const DATA_TYPE: DataType = DataType::MarkerState;
+
+    fn read(reader: &mut DataReader<'_>) -> Result<Self, SimConnectError> {
+        Ok(Self {
+            name: reader.fixed_string(MARKER_NAME_SIZE)?,
+            on: reader.i32()? != 0,
+        })
+    }
+
+    fn write(&self, data: &mut Vec<u8>) {
+        let name = string_to_fixed_c_str::<MARKER_NAME_SIZE>(&self.name);
+
+        data.extend(name.iter().map(|c| *c as u8));
+        data.extend_from_slice(&u32::from(self.on).to_le_bytes());
+    }
+}
+
+/// `SIMCONNECT_DATA_WAYPOINT`. A waypoint of the flight plan of an AI aircraft, e.g. `AI WAYPOINT LIST`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct AiWaypoint {
+    /// Latitude in degrees.
+    pub lat: f64,
+    /// Longitude in degrees.
+    pub lon: f64,
+    /// Altitude in feet.
+    pub alt: f64,
+    /// The speed in knots the aircraft should fly at.
+    pub speed: Option<f64>,
+    /// The throttle percentage the aircraft should fly at.
+    pub throttle: Option<f64>,
+    /// True if the vertical speed should be calculated to reach the altitude of the waypoint when the previous waypoint is left.
+    pub compute_vertical_speed: bool,
+    /// True if `alt` is above ground level, instead of above mean sea level.
+    pub altitude_is_agl: bool,
+    /// True if the waypoint is on the ground, e.g. for taxiing.
+    pub on_ground: bool,
+    /// True if the aircraft should back up to the waypoint.
+    pub reverse: bool,
+    /// True if the aircraft should go back to the first waypoint after the last one.
+    pub wrap_to_first: bool,
+    /// True if the aircraft should always back up to the waypoint.
+    pub always_backup: bool,
+}
+
+impl DataStruct for AiWaypoint {
+    const DATA_TYPE: DataType = DataType::Waypoint;
+
+    fn read(reader: &mut DataReader<'_>) -> Result<Self, SimConnectError> {
+        let lat = reader.f64()?;
+        let lon = reader.f64()?;
+        let alt = reader.f64()?;
+        let flags = reader.i32()? as u32;
+        let speed = reader.f64()?;
+        let throttle = reader.f64()?;
+
+        let has_flag = |flag: u32| flags & flag == flag;
+
+        Ok(Self {
+            lat,
+            lon,
+            alt,
+            speed: has_flag(bindings::SIMCONNECT_WAYPOINT_SPEED_REQUESTED).then_some(speed),
+            throttle: has_flag(bindings::SIMCONNECT_WAYPOINT_THROTTLE_REQUESTED)
+                .then_some(throttle),
+            compute_vertical_speed: has_flag(bindings::SIMCONNECT_WAYPOINT_COMPUTE_VERTICAL_SPEED),
+            altitude_is_agl: has_flag(bindings::SIMCONNECT_WAYPOINT_ALTITUDE_IS_AGL),
+            on_ground: has_flag(bindings::SIMCONNECT_WAYPOINT_ON_GROUND),
+            reverse: has_flag(bindings::SIMCONNECT_WAYPOINT_REVERSE),
+            wrap_to_first: has_flag(bindings::SIMCONNECT_WAYPOINT_WRAP_TO_FIRST),
+            always_backup: has_flag(bindings::SIMCONNECT_WAYPOINT_ALWAYS_BACKUP),
+        })
+    }
+
+    fn write(&self, data: &mut Vec<u8>) {
+        let mut flags = bindings::SIMCONNECT_WAYPOINT_NONE;
+        for (set, flag) in [
+            (
+                self.speed.is_some(),
+                bindings::SIMCONNECT_WAYPOINT_SPEED_REQUESTED,
+            ),
+            (
+                self.throttle.is_some(),
+                bindings::SIMCONNECT_WAYPOINT_THROTTLE_REQUESTED,
+            ),
+            (
+                self.compute_vertical_speed,
+                bindings::SIMCONNECT_WAYPOINT_COMPUTE_VERTICAL_SPEED,
+            ),
+            (
+                self.altitude_is_agl,
+                bindings::SIMCONNECT_WAYPOINT_ALTITUDE_IS_AGL,
+            ),
+            (self.on_ground, bindings::SIMCONNECT_WAYPOINT_ON_GROUND),
+            (self.reverse, bindings::SIMCONNECT_WAYPOINT_REVERSE),
+            (
+                self.wrap_to_first,
+                bindings::SIMCONNECT_WAYPOINT_WRAP_TO_FIRST,
+            ),
+            (
+                self.always_backup,
+                bindings::SIMCONNECT_WAYPOINT_ALWAYS_BACKUP,
+            ),
+        ] {
+            if set {
+                flags |= flag;
+            }
+        }
+
+        data.extend_from_slice(&self.lat.to_le_bytes());
+        data.extend_from_slice(&self.lon.to_le_bytes());
+        data.extend_from_slice(&self.alt.to_le_bytes());
+        data.extend_from_slice(&flags.to_le_bytes());
+        data.extend_from_slice(&self.speed.unwrap_or_default().to_le_bytes());
+        data.extend_from_slice(&self.throttle.unwrap_or_default().to_le_bytes());
+    }
+}
@@ -1,7 +1,38 @@
 /// [`crate::SimConnectObject`] object property data type.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DataType {
+    /// `SIMCONNECT_DATATYPE_FLOAT64`.
     Float64,
+    /// `SIMCONNECT_DATATYPE_FLOAT32`.
+    Float32,
+    /// `SIMCONNECT_DATATYPE_INT64`.
+    Int64,
+    /// `SIMCONNECT_DATATYPE_INT32`.
+    Int32,
+    /// `SIMCONNECT_DATATYPE_INT32`, received as `0` or `1`.
     Bool,
+    /// `SIMCONNECT_DATATYPE_STRING256`.
     String,
+    /// `SIMCONNECT_DATATYPE_STRING8`.
+    String8,
+    /// `SIMCONNECT_DATATYPE_STRING32`.
+    String32,
+    /// `SIMCONNECT_DATATYPE_STRING64`.
+    String64,
+    /// `SIMCONNECT_DATATYPE_STRING128`.
+    String128,
+    /// `SIMCONNECT_DATATYPE_STRING260`.
+    String260,
+    /// `SIMCONNECT_DATATYPE_STRINGV`, a variable-length string.
+    StringV,
+    /// `SIMCONNECT_DATATYPE_LATLONALT`. See [`crate::LatLonAlt`].
+    LatLonAlt,
+    /// `SIMCONNECT_DATATYPE_XYZ`. See [`crate::XYZ`].
+    XYZ,
+    /// `SIMCONNECT_DATATYPE_INITPOSITION`. See [`crate::InitPosition`].
+    InitPosition,
+    /// `SIMCONNECT_DATATYPE_MARKERSTATE`. See [`crate::MarkerState`].
+    MarkerState,
+    /// `SIMCONNECT_DATATYPE_WAYPOINT`. See [`crate::AiWaypoint`].
+    Waypoint,
 }
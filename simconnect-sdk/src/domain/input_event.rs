@@ -0,0 +1,305 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{bindings, fixed_c_str_to_string, SimConnectError};
+
+// `SimConnect_RequestReservedKey` accepts up to three key choices.
+pub(crate) const MAX_RESERVED_KEY_CHOICES: usize = 3;
+// The function keys go up to F24.
+const MAX_FUNCTION_KEY: u8 = 24;
+
+/// A keyboard key. See [`KeyChord`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A letter or a digit, e.g. `U` or `7`. Letters are always uppercase.
+    Char(char),
+    /// A function key, from `F1` to `F24`.
+    Function(u8),
+    /// Any other key, by the name SimConnect knows it by, e.g. `Space`, `Enter`, `Tab` or `Esc`.
+    Named(String),
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(c) => write!(f, "{c}"),
+            Key::Function(n) => write!(f, "F{n}"),
+            Key::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = SimConnectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphanumeric() => {
+                return Ok(Key::Char(c.to_ascii_uppercase()))
+            }
+            (Some('F' | 'f'), Some(_)) if s[1..].chars().all(|c| c.is_ascii_digit()) => {
+                return match s[1..].parse::<u8>() {
+                    Ok(n @ 1..=MAX_FUNCTION_KEY) => Ok(Key::Function(n)),
+                    _ => Err(SimConnectError::InvalidInputEvent(s.to_string())),
+                };
+            }
+            _ => (),
+        }
+
+        if !s.is_empty()
+            && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && Modifier::parse(s).is_none()
+        {
+            Ok(Key::Named(s.to_string()))
+        } else {
+            Err(SimConnectError::InvalidInputEvent(s.to_string()))
+        }
+    }
+}
+
+/// A keyboard key along with the modifiers that have to be held down with it, e.g. `Shift+Ctrl+U`.
+///
+/// It is parsed case-insensitively from strings like `"shift+ctrl+U"`, `"Alt+F4"` or `"Space"`.
+///
+/// # Example
+///
+/// ```rust
+/// use simconnect_sdk::{Key, KeyChord};
+///
+/// let chord: KeyChord = "shift+ctrl+u".parse().unwrap();
+///
+/// assert!(chord.shift && chord.ctrl && !chord.alt);
+/// assert_eq!(chord.key, Key::Char('U'));
+/// assert_eq!(chord.to_string(), "Shift+Ctrl+U");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    /// Whether the Shift key has to be held down.
+    pub shift: bool,
+    /// Whether the Ctrl key has to be held down.
+    pub ctrl: bool,
+    /// Whether the Alt key has to be held down.
+    pub alt: bool,
+    /// The key.
+    pub key: Key,
+}
+
+impl KeyChord {
+    /// A key without any modifiers.
+    pub fn new(key: Key) -> Self {
+        Self {
+            shift: false,
+            ctrl: false,
+            alt: false,
+            key,
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+
+        write!(f, "{}", self.key)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = SimConnectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SimConnectError::InvalidInputEvent(s.to_string());
+
+        let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+        let key = parts.pop().ok_or_else(invalid)?;
+
+        let mut chord = KeyChord::new(key.parse().map_err(|_| invalid())?);
+
+        for part in parts {
+            let modifier = match Modifier::parse(part) {
+                Some(Modifier::Shift) => &mut chord.shift,
+                Some(Modifier::Ctrl) => &mut chord.ctrl,
+                Some(Modifier::Alt) => &mut chord.alt,
+                None => return Err(invalid()),
+            };
+
+            if *modifier {
+                return Err(invalid());
+            }
+            *modifier = true;
+        }
+
+        Ok(chord)
+    }
+}
+
+enum Modifier {
+    Shift,
+    Ctrl,
+    Alt,
+}
+
+impl Modifier {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "shift" => Some(Modifier::Shift),
+            "ctrl" | "control" => Some(Modifier::Ctrl),
+            "alt" => Some(Modifier::Alt),
+            _ => None,
+        }
+    }
+}
+
+/// A joystick axis. See [`InputEvent::JoystickAxis`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum JoystickAxis {
+    /// The X axis, usually the roll of a stick.
+    X,
+    /// The Y axis, usually the pitch of a stick.
+    Y,
+    /// The Z axis, usually a throttle.
+    Z,
+    /// The rotation around the X axis.
+    Rx,
+    /// The rotation around the Y axis.
+    Ry,
+    /// The rotation around the Z axis, usually the twist of a stick or the rudder pedals.
+    Rz,
+    /// The slider.
+    Slider,
+}
+
+impl JoystickAxis {
+    fn name(self) -> &'static str {
+        match self {
+            JoystickAxis::X => "XAxis",
+            JoystickAxis::Y => "YAxis",
+            JoystickAxis::Z => "ZAxis",
+            JoystickAxis::Rx => "RxAxis",
+            JoystickAxis::Ry => "RyAxis",
+            JoystickAxis::Rz => "RzAxis",
+            JoystickAxis::Slider => "Slider",
+        }
+    }
+}
+
+/// An input event, i.e. a key chord or a joystick input, that can be mapped to a client event. See [`crate::InputGroup`].
+///
+/// It is parsed from the input definitions SimConnect uses, e.g. `"shift+ctrl+U"`, `"joystick:0:button:3"`, `"joystick:1:XAxis"` or `"joystick:0:POV"`.
+///
+/// # Remarks
+/// The client events mapped to a joystick axis are transmitted with the position of the axis as their data, from -16383 to +16383.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InputEvent {
+    /// A key chord.
+    Key(KeyChord),
+    /// A joystick button.
+    JoystickButton {
+        /// The index of the joystick.
+        joystick: u32,
+        /// The index of the button.
+        button: u32,
+    },
+    /// A joystick axis.
+    JoystickAxis {
+        /// The index of the joystick.
+        joystick: u32,
+        /// The axis.
+        axis: JoystickAxis,
+    },
+    /// The point of view hat of a joystick.
+    JoystickPov {
+        /// The index of the joystick.
+        joystick: u32,
+    },
+}
+
+impl From<KeyChord> for InputEvent {
+    fn from(chord: KeyChord) -> Self {
+        InputEvent::Key(chord)
+    }
+}
+
+impl fmt::Display for InputEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputEvent::Key(chord) => write!(f, "{chord}"),
+            InputEvent::JoystickButton { joystick, button } => {
+                write!(f, "joystick:{joystick}:button:{button}")
+            }
+            InputEvent::JoystickAxis { joystick, axis } => {
+                write!(f, "joystick:{joystick}:{}", axis.name())
+            }
+            InputEvent::JoystickPov { joystick } => write!(f, "joystick:{joystick}:POV"),
+        }
+    }
+}
+
+impl FromStr for InputEvent {
+    type Err = SimConnectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SimConnectError::InvalidInputEvent(s.to_string());
+
+        let parts = s.split(':').map(str::trim).collect::<Vec<_>>();
+        if !parts[0].eq_ignore_ascii_case("joystick") {
+            return s.parse().map(InputEvent::Key);
+        }
+
+        let joystick = parts
+            .get(1)
+            .and_then(|joystick| joystick.parse().ok())
+            .ok_or_else(invalid)?;
+
+        match parts[2..] {
+            [kind, button] if kind.eq_ignore_ascii_case("button") => {
+                let button = button.parse().map_err(|_| invalid())?;
+
+                Ok(InputEvent::JoystickButton { joystick, button })
+            }
+            [kind] if kind.eq_ignore_ascii_case("pov") => Ok(InputEvent::JoystickPov { joystick }),
+            [name] => [
+                JoystickAxis::X,
+                JoystickAxis::Y,
+                JoystickAxis::Z,
+                JoystickAxis::Rx,
+                JoystickAxis::Ry,
+                JoystickAxis::Rz,
+                JoystickAxis::Slider,
+            ]
+            .into_iter()
+            .find(|axis| axis.name().eq_ignore_ascii_case(name))
+            .map(|axis| InputEvent::JoystickAxis { joystick, axis })
+            .ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// The key that has been reserved for the client, in answer to [`crate::SimConnect::request_reserved_key`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReservedKey {
+    /// The key choice that has been reserved, as it was requested.
+    pub choice: String,
+    /// The key combination that has been reserved, e.g. `Tab+U`.
+    pub key: String,
+}
+
+impl From<&bindings::SIMCONNECT_RECV_RESERVED_KEY> for ReservedKey {
+    fn from(reserved_key: &bindings::SIMCONNECT_RECV_RESERVED_KEY) -> Self {
+        Self {
+            choice: fixed_c_str_to_string(&reserved_key.szChoiceReserved),
+            key: fixed_c_str_to_string(&reserved_key.szReservedKey),
+        }
+    }
+}
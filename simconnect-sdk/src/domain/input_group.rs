@@ -0,0 +1,102 @@
+use crate::{ClientEventRequest, InputEvent, NotificationGroupEvent, NotificationGroupPriority};
+
+/// A group of input events, i.e. key chords and joystick inputs, mapped to client events. See [`crate::SimConnect::add_input_group`].
+///
+/// When an input event of the group is triggered, its client event is transmitted to the client, and received as [`crate::Notification::ClientEvent`] or [`crate::Notification::CustomClientEvent`].
+/// The client events don't have to be subscribed to.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use simconnect_sdk::{
+///     ClientEventRequest, InputEvent, InputGroup, JoystickAxis, NotificationGroupPriority,
+///     SimConnect,
+/// };
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut client = SimConnect::new("Input group example")?;
+///
+///     let group = InputGroup::new(NotificationGroupPriority::Highest)
+///         // A custom hotkey of the add-on, received as `Notification::CustomClientEvent`.
+///         .with_maskable_custom_event("shift+ctrl+U".parse::<InputEvent>()?, "MyAddon.Toggle")
+///         .with_event(
+///             InputEvent::JoystickAxis {
+///                 joystick: 0,
+///                 axis: JoystickAxis::Z,
+///             },
+///             ClientEventRequest::AxisThrottleSet,
+///         );
+///
+///     let group_id = client.add_input_group(group)?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputGroup {
+    pub(crate) priority: NotificationGroupPriority,
+    pub(crate) events: Vec<(InputEvent, NotificationGroupEvent, bool)>,
+}
+
+impl InputGroup {
+    /// Create an empty input group with the given priority.
+    ///
+    /// Input groups use the same priorities as notification groups. The groups of a higher priority receive the input events first.
+    pub fn new(priority: NotificationGroupPriority) -> Self {
+        Self {
+            priority,
+            events: Vec::new(),
+        }
+    }
+
+    /// Map an input event to a client event.
+    pub fn with_event(mut self, input: impl Into<InputEvent>, event: ClientEventRequest) -> Self {
+        self.events
+            .push((input.into(), NotificationGroupEvent::Client(event), false));
+        self
+    }
+
+    /// Map an input event to a client event, and mask the input event from the groups of a lower priority, e.g. from the key bindings of the simulator.
+    pub fn with_maskable_event(
+        mut self,
+        input: impl Into<InputEvent>,
+        event: ClientEventRequest,
+    ) -> Self {
+        self.events
+            .push((input.into(), NotificationGroupEvent::Client(event), true));
+        self
+    }
+
+    /// Map an input event to a client event, by name. See [`crate::SimConnect::map_custom_client_event`].
+    pub fn with_custom_event(
+        mut self,
+        input: impl Into<InputEvent>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.events.push((
+            input.into(),
+            NotificationGroupEvent::Custom(name.into()),
+            false,
+        ));
+        self
+    }
+
+    /// Map an input event to a client event by name, and mask the input event from the groups of a lower priority, e.g. from the key bindings of the simulator.
+    pub fn with_maskable_custom_event(
+        mut self,
+        input: impl Into<InputEvent>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.events.push((
+            input.into(),
+            NotificationGroupEvent::Custom(name.into()),
+            true,
+        ));
+        self
+    }
+
+    /// The priority of the group.
+    pub fn priority(&self) -> NotificationGroupPriority {
+        self.priority
+    }
+}
@@ -1,15 +1,29 @@
 mod client_event;
 mod condition;
+mod data_struct;
 mod data_type;
 mod facilities;
+mod input_event;
+mod input_group;
 mod notification;
+mod notification_group;
+mod object_id;
 mod period;
+mod sim_object_type;
 mod system_event;
+mod system_state;
 
 pub use client_event::*;
 pub use condition::*;
+pub use data_struct::*;
 pub use data_type::*;
 pub use facilities::*;
+pub use input_event::*;
+pub use input_group::*;
 pub use notification::*;
+pub use notification_group::*;
+pub use object_id::*;
 pub use period::*;
+pub use sim_object_type::*;
 pub use system_event::*;
+pub use system_state::*;
@@ -1,5 +1,6 @@
 use crate::{
-    Airport, ClientEvent, SimConnectError, SimConnectObjectExt, SystemEvent, Waypoint, NDB, VOR,
+    Airport, ClientEvent, DataReader, ObjectId, ReservedKey, SimConnectError, SimConnectObjectExt,
+    SystemEvent, SystemState, Waypoint, NDB, VOR,
 };
 
 /// Notification received from SimConnect.
@@ -12,6 +13,24 @@ pub enum Notification {
     ClientEvent(ClientEvent),
     /// SimConnect system event
     SystemEvent(SystemEvent),
+    /// A custom client event, subscribed to by name through [`crate::SimConnect::subscribe_to_custom_client_event`].
+    CustomClientEvent {
+        /// The name the event has been subscribed to with.
+        name: String,
+        /// The data of the event.
+        data: i32,
+    },
+    /// The system state, in answer to [`crate::SimConnect::request_system_state`].
+    SystemState(SystemState),
+    /// A client event transmitted with up to five data parameters, e.g. through `SimConnect_TransmitClientEvent_EX1`.
+    ClientEventEx1 {
+        /// The name of the sim event, e.g. `AP_MASTER`, or the name the event has been mapped with through [`crate::SimConnect::map_custom_client_event`].
+        name: String,
+        /// The data parameters of the event.
+        data: [i32; 5],
+    },
+    /// The key that has been reserved, in answer to [`crate::SimConnect::request_reserved_key`].
+    ReservedKey(ReservedKey),
     /// SimConnect object
     Object(Object),
     /// A list of [crate::Airport].
@@ -22,6 +41,15 @@ pub enum Notification {
     NdbList(Vec<NDB>),
     /// A list of [crate::VOR].
     VorList(Vec<VOR>),
+    /// The data of the simulation objects in range, in answer to [`crate::SimConnect::request_data_by_type`].
+    ObjectsByType {
+        /// The request ID returned by [`crate::SimConnect::request_data_by_type`].
+        request_id: u32,
+        /// The type name of the struct the data has been requested as, i.e. `std::any::type_name::<T>()`.
+        type_name: String,
+        /// The simulation objects and their data. Empty if there were no objects in range.
+        objects: Vec<(ObjectId, Object)>,
+    },
     /// SimConnect quit
     Quit,
 }
@@ -30,19 +58,40 @@ pub enum Notification {
 #[derive(Debug)]
 pub struct Object {
     pub(crate) type_name: String,
-    pub(crate) data_addr: *const u32,
+    pub(crate) request_id: u32,
+    pub(crate) object_id: ObjectId,
+    pub(crate) data: Vec<u8>,
 }
 
 impl Object {
+    /// The request ID of the registration this data has been received for. See [`crate::Subscription::request_id`].
+    pub fn request_id(&self) -> u32 {
+        self.request_id
+    }
+
+    /// The ID of the simulation object this data belongs to.
+    pub fn object_id(&self) -> ObjectId {
+        self.object_id
+    }
+
     /// Try and transmute this SimConnect object as a `T` struct.
     ///
     /// # Errors
     /// - [`crate::SimConnectError::ObjectMismatch`] -- The type of this SimConnect object is different from `T`.
+    /// - [`crate::SimConnectError::UnexpectedError`] -- The data is shorter than `I`.
     pub fn try_transmute<T: SimConnectObjectExt, I>(&self) -> Result<I, SimConnectError> {
         let type_name: String = std::any::type_name::<T>().into();
 
         if self.type_name == type_name {
-            let data: I = unsafe { std::ptr::read_unaligned(self.data_addr as *const I) };
+            if self.data.len() < std::mem::size_of::<I>() {
+                return Err(SimConnectError::UnexpectedError(format!(
+                    "received {} bytes of data for `{type_name}`, expected at least {}",
+                    self.data.len(),
+                    std::mem::size_of::<I>()
+                )));
+            }
+
+            let data: I = unsafe { std::ptr::read_unaligned(self.data.as_ptr() as *const I) };
             Ok(data)
         } else {
             Err(SimConnectError::ObjectMismatch {
@@ -51,4 +100,21 @@ impl Object {
             })
         }
     }
+
+    /// Try and read this SimConnect object as a `T` struct, one field at a time.
+    ///
+    /// # Errors
+    /// - [`crate::SimConnectError::ObjectMismatch`] -- The type of this SimConnect object is different from `T`.
+    pub fn try_reader<T: SimConnectObjectExt>(&self) -> Result<DataReader<'_>, SimConnectError> {
+        let type_name: String = std::any::type_name::<T>().into();
+
+        if self.type_name == type_name {
+            Ok(DataReader::new(&self.data))
+        } else {
+            Err(SimConnectError::ObjectMismatch {
+                actual: self.type_name.clone(),
+                expected: type_name,
+            })
+        }
+    }
 }
@@ -0,0 +1,122 @@
+use crate::{bindings, ClientEventRequest};
+
+/// The priority of a [`NotificationGroup`].
+///
+/// SimConnect passes client events to the notification groups in order of priority, highest first.
+/// A group can mask its maskable events, which means that they are not passed to the groups of a lower priority.
+/// The simulator processes the events after the groups with a priority of [`NotificationGroupPriority::HighestMaskable`] or higher,
+/// so only these groups can keep the simulator from processing them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NotificationGroupPriority {
+    /// The highest priority.
+    Highest,
+    /// The highest priority that can mask events.
+    HighestMaskable,
+    /// The priority most clients should use.
+    Standard,
+    /// A priority lower than the standard one.
+    Default,
+    /// The lowest priority.
+    Lowest,
+    /// A priority between [`NotificationGroupPriority::Highest`] (`1`) and [`NotificationGroupPriority::Lowest`] (`4000000000`). Lower values are higher priorities.
+    Custom(u32),
+}
+
+impl From<NotificationGroupPriority> for u32 {
+    fn from(priority: NotificationGroupPriority) -> Self {
+        match priority {
+            NotificationGroupPriority::Highest => bindings::SIMCONNECT_GROUP_PRIORITY_HIGHEST,
+            NotificationGroupPriority::HighestMaskable => {
+                bindings::SIMCONNECT_GROUP_PRIORITY_HIGHEST_MASKABLE
+            }
+            NotificationGroupPriority::Standard => bindings::SIMCONNECT_GROUP_PRIORITY_STANDARD,
+            NotificationGroupPriority::Default => bindings::SIMCONNECT_GROUP_PRIORITY_DEFAULT,
+            NotificationGroupPriority::Lowest => bindings::SIMCONNECT_GROUP_PRIORITY_LOWEST,
+            NotificationGroupPriority::Custom(priority) => priority,
+        }
+    }
+}
+
+/// A client event of a [`NotificationGroup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NotificationGroupEvent {
+    Client(ClientEventRequest),
+    Custom(String),
+}
+
+/// A group of client events that are notified with the same priority. See [`crate::SimConnect::add_notification_group`].
+///
+/// The events of the group are received as [`crate::Notification::ClientEvent`] or [`crate::Notification::CustomClientEvent`], like the subscribed ones.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use simconnect_sdk::{ClientEventRequest, NotificationGroup, NotificationGroupPriority, SimConnect};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut client = SimConnect::new("Notification group example")?;
+///
+///     // The parking brake is intercepted before the simulator processes it.
+///     let group = NotificationGroup::new(NotificationGroupPriority::HighestMaskable)
+///         .with_maskable_event(ClientEventRequest::ParkingBrakes)
+///         .with_event(ClientEventRequest::GearToggle);
+///
+///     let group_id = client.add_notification_group(group)?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationGroup {
+    pub(crate) priority: NotificationGroupPriority,
+    pub(crate) events: Vec<(NotificationGroupEvent, bool)>,
+}
+
+impl NotificationGroup {
+    /// Create an empty notification group with the given priority.
+    pub fn new(priority: NotificationGroupPriority) -> Self {
+        Self {
+            priority,
+            events: Vec::new(),
+        }
+    }
+
+    /// Add a client event to the group.
+    pub fn with_event(mut self, event: ClientEventRequest) -> Self {
+        self.events
+            .push((NotificationGroupEvent::Client(event), false));
+        self
+    }
+
+    /// Add a client event to the group, which the group masks from the groups of a lower priority.
+    ///
+    /// # Remarks
+    /// The event is only masked from the simulator if the priority of the group is [`NotificationGroupPriority::HighestMaskable`] or higher.
+    pub fn with_maskable_event(mut self, event: ClientEventRequest) -> Self {
+        self.events
+            .push((NotificationGroupEvent::Client(event), true));
+        self
+    }
+
+    /// Add a client event to the group, by name. See [`crate::SimConnect::map_custom_client_event`].
+    pub fn with_custom_event(mut self, name: impl Into<String>) -> Self {
+        self.events
+            .push((NotificationGroupEvent::Custom(name.into()), false));
+        self
+    }
+
+    /// Add a client event to the group by name, which the group masks from the groups of a lower priority.
+    ///
+    /// # Remarks
+    /// The event is only masked from the simulator if the priority of the group is [`NotificationGroupPriority::HighestMaskable`] or higher.
+    pub fn with_maskable_custom_event(mut self, name: impl Into<String>) -> Self {
+        self.events
+            .push((NotificationGroupEvent::Custom(name.into()), true));
+        self
+    }
+
+    /// The priority of the group.
+    pub fn priority(&self) -> NotificationGroupPriority {
+        self.priority
+    }
+}
@@ -0,0 +1,30 @@
+use crate::bindings;
+
+/// The ID of a simulation object, e.g. an aircraft.
+///
+/// The IDs of the objects other than the user aircraft are assigned by the simulator, e.g. when requesting data by type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId(pub u32);
+
+impl ObjectId {
+    /// The user aircraft.
+    pub const USER: ObjectId = ObjectId(bindings::SIMCONNECT_OBJECT_ID_USER);
+}
+
+impl Default for ObjectId {
+    fn default() -> Self {
+        Self::USER
+    }
+}
+
+impl From<u32> for ObjectId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl From<ObjectId> for u32 {
+    fn from(id: ObjectId) -> Self {
+        id.0
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{bindings, SimConnectError};
+
+/// The type of the simulation objects to request data for. See [`crate::SimConnect::request_data_by_type`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SimObjectType {
+    /// The user aircraft only.
+    User,
+    /// All the objects.
+    All,
+    /// All the aircraft.
+    Aircraft,
+    /// All the helicopters.
+    Helicopter,
+    /// All the boats.
+    Boat,
+    /// All the ground vehicles.
+    Ground,
+}
+
+impl From<SimObjectType> for i32 {
+    fn from(object_type: SimObjectType) -> Self {
+        match object_type {
+            SimObjectType::User => {
+                bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_USER
+            }
+            SimObjectType::All => bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_ALL,
+            SimObjectType::Aircraft => {
+                bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT
+            }
+            SimObjectType::Helicopter => {
+                bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_HELICOPTER
+            }
+            SimObjectType::Boat => {
+                bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_BOAT
+            }
+            SimObjectType::Ground => {
+                bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_GROUND
+            }
+        }
+    }
+}
+
+impl TryFrom<i32> for SimObjectType {
+    type Error = SimConnectError;
+
+    fn try_from(object_type: i32) -> Result<Self, Self::Error> {
+        match object_type {
+            bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_USER => {
+                Ok(SimObjectType::User)
+            }
+            bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_ALL => {
+                Ok(SimObjectType::All)
+            }
+            bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT => {
+                Ok(SimObjectType::Aircraft)
+            }
+            bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_HELICOPTER => {
+                Ok(SimObjectType::Helicopter)
+            }
+            bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_BOAT => {
+                Ok(SimObjectType::Boat)
+            }
+            bindings::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_GROUND => {
+                Ok(SimObjectType::Ground)
+            }
+            _ => Err(SimConnectError::UnexpectedError(format!(
+                "unknown simulation object type {object_type}"
+            ))),
+        }
+    }
+}
@@ -1,6 +1,4 @@
-use std::os::raw::c_char;
-
-use crate::{bindings, fixed_c_str_to_string, SimConnectError};
+use crate::{bindings, fixed_c_str_to_string, ObjectId, SimConnectError, SimObjectType};
 
 /// SimConnect System Event Request.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, num_enum::TryFromPrimitive)]
@@ -49,36 +47,59 @@ pub enum SystemEventRequest {
     Unpaused,
     /// Requests a notification when the user aircraft view is changed. This request will also return the current view immediately.
     View,
+    /// Request a notification when an AI object is added to the simulation.
+    ObjectAdded,
+    /// Request a notification when an AI object is removed from the simulation.
+    ObjectRemoved,
+    /// Request a notification when the client is the host of a multiplayer session that has started.
+    MultiplayerServerStarted,
+    /// Request a notification when the client has joined a multiplayer session.
+    MultiplayerClientStarted,
+    /// Request a notification when the multiplayer session the client takes part in has ended.
+    MultiplayerSessionEnded,
+    /// Request a notification when the weather mode is changed.
+    WeatherModeChanged,
+    /// Request a notification for each racer when a multiplayer race ends.
+    RaceEnd,
+    /// Request a notification for each racer when they complete a lap of a multiplayer race.
+    RaceLap,
+    /// Request a notification when a custom action of a mission is executed.
+    CustomMissionActionExecuted,
 }
 
 impl SystemEventRequest {
-    pub(crate) fn into_c_char(self) -> *const c_char {
+    pub(crate) fn into_event_name(self) -> &'static str {
         match self {
-            SystemEventRequest::OneSecond => c"1sec".as_ptr() as *const c_char,
-            SystemEventRequest::FourSeconds => c"4sec".as_ptr() as *const c_char,
-            SystemEventRequest::SixTimesPerSecond => c"6Hz".as_ptr() as *const c_char,
-            SystemEventRequest::AircraftLoaded => c"AircraftLoaded".as_ptr() as *const c_char,
-            SystemEventRequest::Crashed => c"Crashed".as_ptr() as *const c_char,
-            SystemEventRequest::CrashReset => c"CrashReset".as_ptr() as *const c_char,
-            SystemEventRequest::FlightLoaded => c"FlightLoaded".as_ptr() as *const c_char,
-            SystemEventRequest::FlightSaved => c"FlightSaved".as_ptr() as *const c_char,
-            SystemEventRequest::FlightPlanActivated => {
-                c"FlightPlanActivated".as_ptr() as *const c_char
-            }
-            SystemEventRequest::FlightPlanDeactivated => {
-                c"FlightPlanDeactivated".as_ptr() as *const c_char
-            }
-            SystemEventRequest::Frame => c"Frame".as_ptr() as *const c_char,
-            SystemEventRequest::Pause => c"Pause".as_ptr() as *const c_char,
-            SystemEventRequest::Paused => c"Paused".as_ptr() as *const c_char,
-            SystemEventRequest::PauseFrame => c"PauseFrame".as_ptr() as *const c_char,
-            SystemEventRequest::PositionChanged => c"PositionChanged".as_ptr() as *const c_char,
-            SystemEventRequest::Sim => c"Sim".as_ptr() as *const c_char,
-            SystemEventRequest::SimStart => c"SimStart".as_ptr() as *const c_char,
-            SystemEventRequest::SimStop => c"SimStop".as_ptr() as *const c_char,
-            SystemEventRequest::Sound => c"Sound".as_ptr() as *const c_char,
-            SystemEventRequest::Unpaused => c"Unpaused".as_ptr() as *const c_char,
-            SystemEventRequest::View => c"View".as_ptr() as *const c_char,
+            SystemEventRequest::OneSecond => "1sec",
+            SystemEventRequest::FourSeconds => "4sec",
+            SystemEventRequest::SixTimesPerSecond => "6Hz",
+            SystemEventRequest::AircraftLoaded => "AircraftLoaded",
+            SystemEventRequest::Crashed => "Crashed",
+            SystemEventRequest::CrashReset => "CrashReset",
+            SystemEventRequest::FlightLoaded => "FlightLoaded",
+            SystemEventRequest::FlightSaved => "FlightSaved",
+            SystemEventRequest::FlightPlanActivated => "FlightPlanActivated",
+            SystemEventRequest::FlightPlanDeactivated => "FlightPlanDeactivated",
+            SystemEventRequest::Frame => "Frame",
+            SystemEventRequest::Pause => "Pause",
+            SystemEventRequest::Paused => "Paused",
+            SystemEventRequest::PauseFrame => "PauseFrame",
+            SystemEventRequest::PositionChanged => "PositionChanged",
+            SystemEventRequest::Sim => "Sim",
+            SystemEventRequest::SimStart => "SimStart",
+            SystemEventRequest::SimStop => "SimStop",
+            SystemEventRequest::Sound => "Sound",
+            SystemEventRequest::Unpaused => "Unpaused",
+            SystemEventRequest::View => "View",
+            SystemEventRequest::ObjectAdded => "ObjectAdded",
+            SystemEventRequest::ObjectRemoved => "ObjectRemoved",
+            SystemEventRequest::MultiplayerServerStarted => "MultiplayerServerStarted",
+            SystemEventRequest::MultiplayerClientStarted => "MultiplayerClientStarted",
+            SystemEventRequest::MultiplayerSessionEnded => "MultiplayerSessionEnded",
+            SystemEventRequest::WeatherModeChanged => "WeatherModeChanged",
+            SystemEventRequest::RaceEnd => "RaceEnd",
+            SystemEventRequest::RaceLap => "RaceLap",
+            SystemEventRequest::CustomMissionActionExecuted => "CustomMissionActionExecuted",
         }
     }
 }
@@ -97,6 +118,82 @@ pub enum ViewType {
     Orthogonal = bindings::SIMCONNECT_VIEW_SYSTEM_EVENT_DATA_ORTHOGONAL,
 }
 
+/// Weather mode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WeatherMode {
+    /// A weather theme.
+    Theme,
+    /// Real-world weather.
+    RealWorld,
+    /// Custom weather.
+    Custom,
+    /// The same weather everywhere.
+    Global,
+}
+
+impl TryFrom<u32> for WeatherMode {
+    type Error = SimConnectError;
+
+    fn try_from(mode: u32) -> Result<Self, Self::Error> {
+        match mode as i32 {
+            bindings::SIMCONNECT_WEATHER_MODE_SIMCONNECT_WEATHER_MODE_THEME => {
+                Ok(WeatherMode::Theme)
+            }
+            bindings::SIMCONNECT_WEATHER_MODE_SIMCONNECT_WEATHER_MODE_RWW => {
+                Ok(WeatherMode::RealWorld)
+            }
+            bindings::SIMCONNECT_WEATHER_MODE_SIMCONNECT_WEATHER_MODE_CUSTOM => {
+                Ok(WeatherMode::Custom)
+            }
+            bindings::SIMCONNECT_WEATHER_MODE_SIMCONNECT_WEATHER_MODE_GLOBAL => {
+                Ok(WeatherMode::Global)
+            }
+            _ => Err(SimConnectError::UnexpectedError(format!(
+                "unknown weather mode {mode}"
+            ))),
+        }
+    }
+}
+
+/// The result of a racer in a multiplayer race. See [`SystemEvent::RaceEnd`] and [`SystemEvent::RaceLap`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaceResult {
+    /// The total number of racers.
+    pub number_of_racers: u32,
+    /// The GUID of the mission, e.g. `{7E7BB9C2-BB01-4D6B-8B47-F5D0D6DD3C5B}`.
+    pub mission_guid: String,
+    /// The name of the player.
+    pub player_name: String,
+    /// The type of the multiplayer session, e.g. `LAN` or `GAMESPY`.
+    pub session_type: String,
+    /// The aircraft of the player.
+    pub aircraft: String,
+    /// The role of the player in the mission, e.g. `Pilot` or `Tower`.
+    pub player_role: String,
+    /// The total time of the racer, in seconds. Zero if the racer hasn't finished.
+    pub total_time: f64,
+    /// The total penalty time of the racer, in seconds.
+    pub penalty_time: f64,
+    /// Whether the racer has been disqualified.
+    pub is_disqualified: bool,
+}
+
+impl From<&bindings::SIMCONNECT_DATA_RACE_RESULT> for RaceResult {
+    fn from(result: &bindings::SIMCONNECT_DATA_RACE_RESULT) -> Self {
+        Self {
+            number_of_racers: result.dwNumberOfRacers,
+            mission_guid: guid_to_string(result.MissionGUID),
+            player_name: fixed_c_str_to_string(&result.szPlayerName),
+            session_type: fixed_c_str_to_string(&result.szSessionType),
+            aircraft: fixed_c_str_to_string(&result.szAircraft),
+            player_role: fixed_c_str_to_string(&result.szPlayerRole),
+            total_time: result.fTotalTime,
+            penalty_time: result.fPenaltyTime,
+            is_disqualified: result.dwIsDisqualified != 0,
+        }
+    }
+}
+
 /// SimConnect System Event Notification.
 #[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
@@ -177,6 +274,54 @@ pub enum SystemEvent {
         /// The current cockpit view type.
         view: ViewType,
     },
+    /// A notification when an AI object is added to the simulation.
+    ObjectAdded {
+        /// The ID of the added object.
+        object_id: ObjectId,
+        /// The type of the added object.
+        object_type: SimObjectType,
+    },
+    /// A notification when an AI object is removed from the simulation.
+    ObjectRemoved {
+        /// The ID of the removed object.
+        object_id: ObjectId,
+        /// The type of the removed object.
+        object_type: SimObjectType,
+    },
+    /// A notification when the client is the host of a multiplayer session that has started.
+    MultiplayerServerStarted,
+    /// A notification when the client has joined a multiplayer session.
+    MultiplayerClientStarted,
+    /// A notification when the multiplayer session the client takes part in has ended.
+    MultiplayerSessionEnded,
+    /// A notification when the weather mode is changed.
+    WeatherModeChanged {
+        /// The new weather mode.
+        mode: WeatherMode,
+    },
+    /// A notification for each racer when a multiplayer race ends.
+    RaceEnd {
+        /// The index of the racer the result belongs to.
+        racer_number: u32,
+        /// The result of the racer.
+        result: RaceResult,
+    },
+    /// A notification for each racer when they complete a lap of a multiplayer race.
+    RaceLap {
+        /// The index of the completed lap, starting from zero.
+        lap_index: u32,
+        /// The result of the racer.
+        result: RaceResult,
+    },
+    /// A notification when a custom action of a mission is executed.
+    CustomMissionActionExecuted {
+        /// The GUID of the action instance.
+        instance_id: String,
+        /// Whether the mission waits for the client to complete the action.
+        wait_for_completion: bool,
+        /// The payload of the action, as defined in the mission file.
+        payload: String,
+    },
 }
 
 impl TryFrom<&bindings::SIMCONNECT_RECV_EVENT> for SystemEvent {
@@ -210,6 +355,16 @@ impl TryFrom<&bindings::SIMCONNECT_RECV_EVENT> for SystemEvent {
             SystemEventRequest::View => Ok(SystemEvent::View {
                 view: ViewType::try_from(event.dwData).unwrap_or(ViewType::None),
             }),
+            SystemEventRequest::MultiplayerServerStarted => {
+                Ok(SystemEvent::MultiplayerServerStarted)
+            }
+            SystemEventRequest::MultiplayerClientStarted => {
+                Ok(SystemEvent::MultiplayerClientStarted)
+            }
+            SystemEventRequest::MultiplayerSessionEnded => Ok(SystemEvent::MultiplayerSessionEnded),
+            SystemEventRequest::WeatherModeChanged => Ok(SystemEvent::WeatherModeChanged {
+                mode: WeatherMode::try_from(event.dwData)?,
+            }),
             _ => Err(SimConnectError::UnimplementedEventType(event.uEventID)),
         }
     }
@@ -268,3 +423,133 @@ impl TryFrom<&bindings::SIMCONNECT_RECV_EVENT_FRAME> for SystemEvent {
         }
     }
 }
+
+impl TryFrom<&bindings::SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE> for SystemEvent {
+    type Error = SimConnectError;
+
+    fn try_from(
+        event: &bindings::SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE,
+    ) -> Result<Self, Self::Error> {
+        let request = SystemEventRequest::try_from(event._base.uEventID)
+            .map_err(|_| SimConnectError::UnimplementedEventType(event._base.uEventID))?;
+
+        let object_id = ObjectId::from(event._base.dwData);
+        let object_type = SimObjectType::try_from(event.eObjType)?;
+
+        match request {
+            SystemEventRequest::ObjectAdded => Ok(SystemEvent::ObjectAdded {
+                object_id,
+                object_type,
+            }),
+            SystemEventRequest::ObjectRemoved => Ok(SystemEvent::ObjectRemoved {
+                object_id,
+                object_type,
+            }),
+            _ => Err(SimConnectError::UnimplementedEventType(
+                event._base.uEventID,
+            )),
+        }
+    }
+}
+
+impl TryFrom<&bindings::SIMCONNECT_RECV_EVENT_RACE_END> for SystemEvent {
+    type Error = SimConnectError;
+
+    fn try_from(event: &bindings::SIMCONNECT_RECV_EVENT_RACE_END) -> Result<Self, Self::Error> {
+        match SystemEventRequest::try_from(event._base.uEventID) {
+            Ok(SystemEventRequest::RaceEnd) => Ok(SystemEvent::RaceEnd {
+                racer_number: event.dwRacerNumber,
+                result: RaceResult::from(&event.RacerData),
+            }),
+            _ => Err(SimConnectError::UnimplementedEventType(
+                event._base.uEventID,
+            )),
+        }
+    }
+}
+
+impl TryFrom<&bindings::SIMCONNECT_RECV_EVENT_RACE_LAP> for SystemEvent {
+    type Error = SimConnectError;
+
+    fn try_from(event: &bindings::SIMCONNECT_RECV_EVENT_RACE_LAP) -> Result<Self, Self::Error> {
+        match SystemEventRequest::try_from(event._base.uEventID) {
+            Ok(SystemEventRequest::RaceLap) => Ok(SystemEvent::RaceLap {
+                lap_index: event.dwLapIndex,
+                result: RaceResult::from(&event.RacerData),
+            }),
+            _ => Err(SimConnectError::UnimplementedEventType(
+                event._base.uEventID,
+            )),
+        }
+    }
+}
+
+impl SystemEvent {
+    /// Decode a `SIMCONNECT_RECV_CUSTOM_ACTION` message. `payload` is the variable-length payload that follows the fixed fields of the message.
+    pub(crate) fn from_custom_action(
+        event: &bindings::SIMCONNECT_RECV_CUSTOM_ACTION,
+        payload: &[i8],
+    ) -> Result<Self, SimConnectError> {
+        match SystemEventRequest::try_from(event._base.uEventID) {
+            Ok(SystemEventRequest::CustomMissionActionExecuted) => {
+                Ok(SystemEvent::CustomMissionActionExecuted {
+                    instance_id: guid_to_string(event.guidInstanceId),
+                    wait_for_completion: event.dwWaitForCompletion != 0,
+                    payload: fixed_c_str_to_string(payload),
+                })
+            }
+            _ => Err(SimConnectError::UnimplementedEventType(
+                event._base.uEventID,
+            )),
+        }
+    }
+}
+
+impl From<&SystemEvent> for SystemEventRequest {
+    fn from(event: &SystemEvent) -> Self {
+        match event {
+            SystemEvent::OneSecond => SystemEventRequest::OneSecond,
+            SystemEvent::FourSeconds => SystemEventRequest::FourSeconds,
+            SystemEvent::SixTimesPerSecond => SystemEventRequest::SixTimesPerSecond,
+            SystemEvent::AircraftLoaded { .. } => SystemEventRequest::AircraftLoaded,
+            SystemEvent::Crashed => SystemEventRequest::Crashed,
+            SystemEvent::CrashReset => SystemEventRequest::CrashReset,
+            SystemEvent::FlightLoaded { .. } => SystemEventRequest::FlightLoaded,
+            SystemEvent::FlightSaved { .. } => SystemEventRequest::FlightSaved,
+            SystemEvent::FlightPlanActivated { .. } => SystemEventRequest::FlightPlanActivated,
+            SystemEvent::FlightPlanDeactivated => SystemEventRequest::FlightPlanDeactivated,
+            SystemEvent::Frame { .. } => SystemEventRequest::Frame,
+            SystemEvent::Pause { .. } => SystemEventRequest::Pause,
+            SystemEvent::Paused => SystemEventRequest::Paused,
+            SystemEvent::PauseFrame { .. } => SystemEventRequest::PauseFrame,
+            SystemEvent::PositionChanged => SystemEventRequest::PositionChanged,
+            SystemEvent::Sim { .. } => SystemEventRequest::Sim,
+            SystemEvent::SimStart => SystemEventRequest::SimStart,
+            SystemEvent::SimStop => SystemEventRequest::SimStop,
+            SystemEvent::Sound { .. } => SystemEventRequest::Sound,
+            SystemEvent::Unpaused => SystemEventRequest::Unpaused,
+            SystemEvent::View { .. } => SystemEventRequest::View,
+            SystemEvent::ObjectAdded { .. } => SystemEventRequest::ObjectAdded,
+            SystemEvent::ObjectRemoved { .. } => SystemEventRequest::ObjectRemoved,
+            SystemEvent::MultiplayerServerStarted => SystemEventRequest::MultiplayerServerStarted,
+            SystemEvent::MultiplayerClientStarted => SystemEventRequest::MultiplayerClientStarted,
+            SystemEvent::MultiplayerSessionEnded => SystemEventRequest::MultiplayerSessionEnded,
+            SystemEvent::WeatherModeChanged { .. } => SystemEventRequest::WeatherModeChanged,
+            SystemEvent::RaceEnd { .. } => SystemEventRequest::RaceEnd,
+            SystemEvent::RaceLap { .. } => SystemEventRequest::RaceLap,
+            SystemEvent::CustomMissionActionExecuted { .. } => {
+                SystemEventRequest::CustomMissionActionExecuted
+            }
+        }
+    }
+}
+
+/// Format a GUID the way Windows does, e.g. `{7E7BB9C2-BB01-4D6B-8B47-F5D0D6DD3C5B}`.
+fn guid_to_string(guid: bindings::GUID) -> String {
+    let [a, b, c, d, e, f, g, h] = guid.Data4;
+
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{a:02X}{b:02X}-{c:02X}{d:02X}{e:02X}{f:02X}{g:02X}{h:02X}}}",
+        guid.Data1, guid.Data2, guid.Data3
+    )
+}
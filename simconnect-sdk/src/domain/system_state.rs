@@ -0,0 +1,109 @@
+use crate::{bindings, fixed_c_str_to_string};
+
+/// SimConnect System State Request. See [`crate::SimConnect::request_system_state`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SystemStateRequest {
+    /// Request the full path name of the last loaded aircraft flight dynamics file. These files have a .AIR extension.
+    AircraftLoaded,
+    /// Request whether the simulation is in Dialog mode or not.
+    DialogMode,
+    /// Request the full path name of the last loaded flight. Flight files have the extension .FLT.
+    FlightLoaded,
+    /// Request the full path name of the active flight plan. An empty string will be returned if there is no active flight plan.
+    FlightPlan,
+    /// Request the state of the simulation. If `true` the user is in control of the aircraft, if `false` the user is navigating the UI.
+    Sim,
+}
+
+impl SystemStateRequest {
+    pub(crate) fn into_state_name(self) -> &'static str {
+        match self {
+            SystemStateRequest::AircraftLoaded => "AircraftLoaded",
+            SystemStateRequest::DialogMode => "DialogMode",
+            SystemStateRequest::FlightLoaded => "FlightLoaded",
+            SystemStateRequest::FlightPlan => "FlightPlan",
+            SystemStateRequest::Sim => "Sim",
+        }
+    }
+}
+
+/// SimConnect System State, in answer to [`crate::SimConnect::request_system_state`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SystemState {
+    /// The full path name of the last loaded aircraft flight dynamics file.
+    AircraftLoaded {
+        /// The returned filename.
+        file_name: String,
+    },
+    /// Whether the simulation is in Dialog mode or not.
+    DialogMode {
+        /// The current state (`true` = in Dialog mode or `false` = not in Dialog mode).
+        state: bool,
+    },
+    /// The full path name of the last loaded flight.
+    FlightLoaded {
+        /// The returned filename.
+        file_name: String,
+    },
+    /// The full path name of the active flight plan.
+    FlightPlan {
+        /// The returned filename. Empty if there is no active flight plan.
+        file_name: String,
+    },
+    /// The state of the simulation.
+    Sim {
+        /// The current state (`true` = the user is in control of the aircraft or `false` = the user is navigating the UI).
+        state: bool,
+    },
+}
+
+impl SystemState {
+    pub(crate) fn new(
+        request: SystemStateRequest,
+        state: &bindings::SIMCONNECT_RECV_SYSTEM_STATE,
+    ) -> Self {
+        match request {
+            SystemStateRequest::AircraftLoaded => SystemState::AircraftLoaded {
+                file_name: fixed_c_str_to_string(&state.szString),
+            },
+            SystemStateRequest::DialogMode => SystemState::DialogMode {
+                state: state.dwInteger != 0,
+            },
+            SystemStateRequest::FlightLoaded => SystemState::FlightLoaded {
+                file_name: fixed_c_str_to_string(&state.szString),
+            },
+            SystemStateRequest::FlightPlan => SystemState::FlightPlan {
+                file_name: fixed_c_str_to_string(&state.szString),
+            },
+            SystemStateRequest::Sim => SystemState::Sim {
+                state: state.dwInteger != 0,
+            },
+        }
+    }
+
+    /// The integer, float and string values the state is set with through `SimConnect_SetSystemState`.
+    pub(crate) fn values(&self) -> (u32, f32, &str) {
+        match self {
+            SystemState::AircraftLoaded { file_name }
+            | SystemState::FlightLoaded { file_name }
+            | SystemState::FlightPlan { file_name } => (0, 0.0, file_name),
+            SystemState::DialogMode { state } | SystemState::Sim { state } => {
+                (*state as u32, 0.0, "")
+            }
+        }
+    }
+}
+
+impl From<&SystemState> for SystemStateRequest {
+    fn from(state: &SystemState) -> Self {
+        match state {
+            SystemState::AircraftLoaded { .. } => SystemStateRequest::AircraftLoaded,
+            SystemState::DialogMode { .. } => SystemStateRequest::DialogMode,
+            SystemState::FlightLoaded { .. } => SystemStateRequest::FlightLoaded,
+            SystemState::FlightPlan { .. } => SystemStateRequest::FlightPlan,
+            SystemState::Sim { .. } => SystemStateRequest::Sim,
+        }
+    }
+}
@@ -26,12 +26,21 @@ pub enum SimConnectError {
     EventAlreadySubscribedTo(String),
     #[error("Event `{0}` has not been subscribed to")]
     EventNotSubscribedTo(String),
+    /// Invalid input event, e.g. a key chord that can't be parsed.
+    #[error("Invalid input event `{0}`")]
+    InvalidInputEvent(String),
     /// Object mismatch.
     #[error("Tried to convert object of type {actual} to {expected}")]
     ObjectMismatch { actual: String, expected: String },
     /// Conversation error.
     #[error("Conversion error: {0}")]
     ConversionError(#[from] std::num::TryFromIntError),
+    /// The connection to the simulator has been lost without the simulator quitting.
+    #[error("The connection to the simulator has been lost")]
+    Disconnected,
+    /// I/O error of the connection to the simulator.
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
     /// Unexpected error.
     #[error("Unexpected error: {0}")]
     UnexpectedError(String),
@@ -1,5 +1,7 @@
 use std::ffi::CString;
 
+use crate::SimConnectError;
+
 pub fn fixed_c_str_to_string(data: &[i8]) -> String {
     let u8slice = unsafe { &*(data as *const _ as *const [u8]) };
 
@@ -12,3 +14,110 @@ pub fn fixed_c_str_to_string(data: &[i8]) -> String {
 
     result.to_str().unwrap_or_default().to_string()
 }
+
+/// Convert a string to a fixed size, NUL terminated, C string. Strings that don't fit are truncated.
+pub fn string_to_fixed_c_str<const N: usize>(value: &str) -> [i8; N] {
+    let mut result = [0; N];
+
+    let len = value.len().min(N.saturating_sub(1));
+    for (c, byte) in result.iter_mut().zip(&value.as_bytes()[..len]) {
+        *c = *byte as i8;
+    }
+
+    result
+}
+
+/// Copy the bytes of a `#[repr(C, packed)]` struct, which has no padding.
+pub fn packed_to_bytes<T: Sized>(value: &T) -> Vec<u8> {
+    let data = unsafe {
+        std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>())
+    };
+
+    data.to_vec()
+}
+
+/// Sequential reader of the data of an object, for the structs whose layout can't be transmuted from a packed struct, e.g. because of variable-length strings or structured data types.
+///
+/// # Remarks
+/// The [`crate::SimConnectObject`] macro will automatically use it for the structs that have `variable` string fields or [`crate::DataStruct`] fields.
+#[derive(Debug)]
+pub struct DataReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> DataReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SimConnectError> {
+        if self.data.len() < len {
+            return Err(SimConnectError::UnexpectedError(format!(
+                "received too little data, expected {len} more bytes but got {}",
+                self.data.len()
+            )));
+        }
+
+        let (value, rest) = self.data.split_at(len);
+        self.data = rest;
+
+        Ok(value)
+    }
+
+    /// Read a `SIMCONNECT_DATATYPE_INT32`.
+    pub fn i32(&mut self) -> Result<i32, SimConnectError> {
+        Ok(i32::from_le_bytes(
+            self.take(4)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    /// Read a `SIMCONNECT_DATATYPE_INT64`.
+    pub fn i64(&mut self) -> Result<i64, SimConnectError> {
+        Ok(i64::from_le_bytes(
+            self.take(8)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    /// Read a `SIMCONNECT_DATATYPE_FLOAT32`.
+    pub fn f32(&mut self) -> Result<f32, SimConnectError> {
+        Ok(f32::from_le_bytes(
+            self.take(4)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    /// Read a `SIMCONNECT_DATATYPE_FLOAT64`.
+    pub fn f64(&mut self) -> Result<f64, SimConnectError> {
+        Ok(f64::from_le_bytes(
+            self.take(8)?.try_into().unwrap_or_default(),
+        ))
+    }
+
+    /// Read a NUL padded string of `len` bytes, i.e. one of `SIMCONNECT_DATATYPE_STRING8` to `SIMCONNECT_DATATYPE_STRING260`.
+    pub fn fixed_string(&mut self, len: usize) -> Result<String, SimConnectError> {
+        let value = self.take(len)?;
+        let end = value.iter().position(|c| *c == 0).unwrap_or(value.len());
+
+        Ok(String::from_utf8_lossy(&value[..end]).into_owned())
+    }
+
+    /// Read a NUL terminated `SIMCONNECT_DATATYPE_STRINGV`.
+    pub fn string_v(&mut self) -> Result<String, SimConnectError> {
+        let end = self.data.iter().position(|c| *c == 0).ok_or_else(|| {
+            SimConnectError::UnexpectedError(
+                "received a variable-length string that is not NUL terminated".to_string(),
+            )
+        })?;
+        let value = self.take(end + 1)?;
+
+        Ok(String::from_utf8_lossy(&value[..end]).into_owned())
+    }
+}
+
+/// Convert a string to the NUL terminated bytes of a `SIMCONNECT_DATATYPE_STRINGV`.
+pub fn string_to_string_v(value: &str) -> Vec<u8> {
+    let mut data = Vec::with_capacity(value.len() + 1);
+    data.extend_from_slice(value.as_bytes());
+    data.push(0);
+
+    data
+}
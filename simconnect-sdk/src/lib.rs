@@ -45,7 +45,8 @@
 //!                         println!("Connection opened.");
 //!
 //!                         // After the connection is successfully open, we register the struct
-//!                         client.register_object::<AirplaneData>()?;
+//!                         // Detaching the subscription keeps the struct registered until we unregister it
+//!                         client.register_object::<AirplaneData>()?.detach();
 //!                     }
 //!                     Some(Notification::Object(data)) => {
 //!                         if let Ok(airplane_data) = AirplaneData::try_from(&data) {
@@ -79,21 +80,33 @@
 //!
 //! See [more examples](https://github.com/mihai-dinculescu/simconnect-sdk-rs/tree/main/examples).
 
+mod backend;
 mod bindings;
 mod domain;
 mod errors;
 mod helpers;
+#[cfg(windows)]
 mod macros;
 mod simconnect;
 mod simconnect_object_ext;
 
+#[cfg(windows)]
 pub(crate) use macros::{as_c_string, ok_if_fail, success};
 
+#[cfg(windows)]
+pub use backend::FfiBackend;
+pub use backend::{DispatchEvent, SimConnectBackend, TcpBackend};
+#[cfg(feature = "mock")]
+pub use backend::{MockCall, MockDatum, MockSimulator};
 pub use domain::*;
 pub use errors::SimConnectError;
-pub use helpers::fixed_c_str_to_string;
-pub use simconnect::SimConnect;
-pub use simconnect_object_ext::SimConnectObjectExt;
+pub use helpers::{
+    fixed_c_str_to_string, packed_to_bytes, string_to_fixed_c_str, string_to_string_v, DataReader,
+};
+#[cfg(feature = "tokio")]
+pub use simconnect::AsyncSimConnect;
+pub use simconnect::{Dispatcher, SimConnect, Subscription};
+pub use simconnect_object_ext::{SimConnectObjectExt, SimConnectSettableObjectExt};
 
 #[cfg(feature = "simconnect-sdk-derive")]
 extern crate simconnect_sdk_derive;
@@ -0,0 +1,166 @@
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::task::JoinHandle;
+
+use crate::simconnect::Dispatch;
+use crate::{DispatchEvent, Notification, SimConnect, SimConnectBackend, SimConnectError};
+
+/// Asynchronous SimConnect SDK Client.
+///
+/// It wraps a [`SimConnect`] client and turns its notifications into a [`Stream`].
+/// Instead of polling [`SimConnect::get_next_dispatch`] in a sleep loop, the stream waits for the [`crate::DispatchEvent`] of the backend,
+/// so a notification is yielded as soon as it is received and no CPU time is spent in between.
+/// The wait happens on the blocking thread pool of Tokio, so the stream has to be polled from within a Tokio runtime.
+///
+/// All the other methods of [`SimConnect`], e.g. [`SimConnect::register_object`], are available through `Deref`.
+/// The stream ends after [`Notification::Quit`] has been yielded, or after [`SimConnectError::Disconnected`] has been yielded once the connection is lost.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use futures_util::StreamExt;
+/// use simconnect_sdk::{AsyncSimConnect, Notification, SimConnectObject};
+///
+/// #[derive(Debug, Clone, SimConnectObject)]
+/// #[simconnect(period = "second")]
+/// #[allow(dead_code)]
+/// struct AirplaneData {
+///     #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+///     alt: f64,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut client = AsyncSimConnect::new("Async example")?;
+///
+///     while let Some(notification) = client.next().await {
+///         match notification? {
+///             Notification::Open => {
+///                 client.register_object::<AirplaneData>()?.detach();
+///             }
+///             Notification::Object(data) => {
+///                 if let Ok(airplane_data) = AirplaneData::try_from(&data) {
+///                     println!("{airplane_data:?}");
+///                 }
+///             }
+///             _ => (),
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncSimConnect {
+    client: SimConnect,
+    waiting: Option<Waiting>,
+    finished: bool,
+}
+
+/// A wait for the dispatch event, running on the blocking thread pool.
+#[derive(Debug)]
+struct Waiting {
+    handle: JoinHandle<bool>,
+    dispatch_event: DispatchEvent,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        // Release the blocking thread if the wait is abandoned, e.g. when the client is dropped.
+        if !self.handle.is_finished() {
+            self.dispatch_event.signal();
+        }
+    }
+}
+
+impl AsyncSimConnect {
+    /// Create a new asynchronous SimConnect SDK client. See [`SimConnect::new`].
+    pub fn new(name: &str) -> Result<Self, SimConnectError> {
+        Ok(Self::from(SimConnect::new(name)?))
+    }
+
+    /// Create a new asynchronous SimConnect SDK client that uses a custom [`crate::SimConnectBackend`]. See [`SimConnect::with_backend`].
+    pub fn with_backend(backend: impl SimConnectBackend + 'static) -> Self {
+        Self::from(SimConnect::with_backend(backend))
+    }
+
+    /// Unwrap the underlying [`SimConnect`] client.
+    pub fn into_inner(self) -> SimConnect {
+        self.client
+    }
+}
+
+impl From<SimConnect> for AsyncSimConnect {
+    fn from(client: SimConnect) -> Self {
+        Self {
+            client,
+            waiting: None,
+            finished: false,
+        }
+    }
+}
+
+impl Deref for AsyncSimConnect {
+    type Target = SimConnect;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
+impl DerefMut for AsyncSimConnect {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.client
+    }
+}
+
+impl Stream for AsyncSimConnect {
+    type Item = Result<Notification, SimConnectError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.finished {
+                return Poll::Ready(None);
+            }
+
+            if let Some(waiting) = &mut this.waiting {
+                // A failed wait is retried, there is no harm in receiving too early.
+                let _ = std::task::ready!(Pin::new(&mut waiting.handle).poll(cx));
+                this.waiting = None;
+            }
+
+            match this.client.dispatch() {
+                Ok(Dispatch::Notification(notification)) => {
+                    this.finished = matches!(notification, Notification::Quit);
+
+                    return Poll::Ready(Some(Ok(notification)));
+                }
+                Ok(Dispatch::Skipped) => (),
+                Ok(Dispatch::Empty) => {
+                    let dispatch_event = this.client.backend.dispatch_event().clone();
+                    let handle = tokio::task::spawn_blocking({
+                        let dispatch_event = dispatch_event.clone();
+                        move || dispatch_event.wait(None)
+                    });
+
+                    this.waiting = Some(Waiting {
+                        handle,
+                        dispatch_event,
+                    });
+                }
+                Err(e) => {
+                    // there is nothing left to wait for once the connection is lost
+                    this.finished = matches!(e, SimConnectError::Disconnected);
+
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,25 @@
-use std::{collections::HashMap, ffi::c_void};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
 
 use tracing::{error, span, trace, warn, Level};
 
 use crate::domain::{
-    Airport, ClientEvent, ClientEventRequest, Notification, Object, SystemEvent,
-    SystemEventRequest, Waypoint, CLIENT_EVENT_DISCRIMINANT_START, NDB, VOR,
+    Airport, ClientEvent, ClientEventRequest, Notification, Object, ObjectId, ReservedKey,
+    SystemEvent, SystemEventRequest, SystemState, SystemStateRequest, Waypoint,
+    CLIENT_EVENT_DISCRIMINANT_START, CUSTOM_CLIENT_EVENT_ID_START, NDB, VOR,
 };
 use crate::helpers::fixed_c_str_to_string;
-use crate::simconnect::EventRegister;
-use crate::{as_c_string, bindings, ok_if_fail, success, SimConnectError};
+use crate::simconnect::{EventRegister, Route};
+use crate::{bindings, SimConnectBackend, SimConnectError};
+
+// The messages that don't add anything to `SIMCONNECT_RECV_EVENT`.
+const PLAIN_EVENT_RECV_IDS: [bindings::SIMCONNECT_RECV_ID; 4] = [
+    bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_WEATHER_MODE,
+    bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_SERVER_STARTED,
+    bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_CLIENT_STARTED,
+    bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_SESSION_ENDED,
+];
 
 /// SimConnect SDK Client.
 ///
@@ -52,7 +63,8 @@ use crate::{as_c_string, bindings, ok_if_fail, success, SimConnectError};
 ///                         println!("Connection opened.");
 ///
 ///                         // After the connection is successfully open, we register the struct
-///                         client.register_object::<AirplaneData>()?;
+///                         // Detaching the subscription keeps the struct registered until we unregister it
+///                         client.register_object::<AirplaneData>()?.detach();
 ///                     }
 ///                     Some(Notification::Object(data)) => {
 ///                         if let Ok(airplane_data) = AirplaneData::try_from(&data) {
@@ -85,81 +97,164 @@ use crate::{as_c_string, bindings, ok_if_fail, success, SimConnectError};
 /// ```
 #[derive(Debug)]
 pub struct SimConnect {
-    pub(crate) handle: std::ptr::NonNull<c_void>,
+    pub(crate) backend: Box<dyn SimConnectBackend>,
     pub(crate) next_request_id: u32,
-    pub(crate) registered_objects: HashMap<String, RegisteredObject>,
+    pub(crate) registered_objects: HashMap<u32, RegisteredObject>,
     pub(crate) system_event_register: EventRegister<SystemEventRequest>,
     pub(crate) client_event_register: EventRegister<ClientEventRequest>,
+    pub(crate) mapped_client_events: Vec<ClientEventRequest>,
+    pub(crate) custom_client_events: HashMap<String, u32>,
+    pub(crate) custom_client_event_register: EventRegister<String>,
+    pub(crate) next_notification_group_id: u32,
+    pub(crate) next_input_group_id: u32,
+    pub(crate) dropped_subscriptions: (Sender<u32>, Receiver<u32>),
+    pub(crate) objects_by_type: HashMap<u32, Vec<(ObjectId, Object)>>,
+    pub(crate) system_state_requests: HashMap<u32, SystemStateRequest>,
 }
 
-/// A struct that represents a registered object.
-#[derive(Debug)]
+/// A struct that represents a registered object, keyed by its Request ID.
 pub(crate) struct RegisteredObject {
-    pub id: u32,
+    pub type_name: String,
     pub transient: bool,
+    pub route: Option<Route>,
+    /// Only defines the data that is set through [`crate::SimConnect::set_data_on_sim_object`], without requesting it.
+    pub set_only: bool,
+}
+
+impl std::fmt::Debug for RegisteredObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredObject")
+            .field("type_name", &self.type_name)
+            .field("transient", &self.transient)
+            .field("route", &self.route.is_some())
+            .field("set_only", &self.set_only)
+            .finish()
+    }
+}
+
+/// The outcome of receiving a single SimConnect message.
+#[derive(Debug)]
+pub(crate) enum Dispatch {
+    /// There was no message to receive.
+    Empty,
+    /// A message has been received, but there is nothing to notify about, e.g. data for an object that is no longer registered.
+    Skipped,
+    /// A message has been received and decoded.
+    Notification(Notification),
 }
 
 impl RegisteredObject {
-    pub(crate) fn new(id: u32, transient: bool) -> Self {
-        Self { id, transient }
+    pub(crate) fn new(type_name: String, transient: bool) -> Self {
+        Self {
+            type_name,
+            transient,
+            route: None,
+            set_only: false,
+        }
     }
 }
 
 impl SimConnect {
     /// Create a new SimConnect SDK client.
+    ///
+    /// # Remarks
+    /// The connection is made through the SimConnect C API, which is only available on Windows.
+    /// On other platforms use [`crate::SimConnect::with_backend`].
     #[tracing::instrument(name = "SimConnect::new", level = "debug")]
     pub fn new(name: &str) -> Result<Self, SimConnectError> {
-        let mut handle = std::ptr::null_mut();
-
-        success!(unsafe {
-            bindings::SimConnect_Open(
-                &mut handle,
-                as_c_string!(name),
-                std::ptr::null_mut(),
-                0,
-                std::ptr::null_mut(),
-                0,
-            )
-        })?;
-
-        Ok(Self {
-            handle: std::ptr::NonNull::new(handle).ok_or_else(|| {
-                SimConnectError::UnexpectedError(
-                    "SimConnect_Open returned null pointer on success".to_string(),
-                )
-            })?,
+        #[cfg(windows)]
+        {
+            Ok(Self::with_backend(crate::FfiBackend::open(name)?))
+        }
+
+        #[cfg(not(windows))]
+        {
+            Err(SimConnectError::UnexpectedError(format!(
+                "cannot open `{name}`: the SimConnect C API is only available on Windows"
+            )))
+        }
+    }
+
+    /// Create a new SimConnect SDK client that uses a custom [`crate::SimConnectBackend`].
+    pub fn with_backend(backend: impl SimConnectBackend + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
             next_request_id: 0,
             registered_objects: HashMap::new(),
             system_event_register: EventRegister::new(),
             client_event_register: EventRegister::new(),
-        })
+            mapped_client_events: Vec::new(),
+            custom_client_events: HashMap::new(),
+            custom_client_event_register: EventRegister::new(),
+            // Group 0 is used for the client events that are subscribed to individually.
+            next_notification_group_id: 1,
+            next_input_group_id: 0,
+            dropped_subscriptions: mpsc::channel(),
+            objects_by_type: HashMap::new(),
+            system_state_requests: HashMap::new(),
+        }
     }
 
     /// Receive the next SimConnect message.
     ///
     /// # Remarks
     /// This is a non-blocking function. If there are no messages to receive, it will return None immediately.
-    /// When called in a loop, it is recommended to use a short sleep time.
+    /// When called in a loop, it is recommended to use a short sleep time, or to use [`SimConnect::wait_next_dispatch`] instead.
     pub fn get_next_dispatch(&mut self) -> Result<Option<Notification>, SimConnectError> {
-        let mut data_buf: *mut bindings::SIMCONNECT_RECV = std::ptr::null_mut();
-        let mut size_buf: bindings::DWORD = 32;
-        let size_buf_pointer: *mut bindings::DWORD = &mut size_buf;
-
-        unsafe {
-            ok_if_fail!(
-                bindings::SimConnect_GetNextDispatch(
-                    self.handle.as_ptr(),
-                    &mut data_buf,
-                    size_buf_pointer
-                ),
-                None
-            );
+        match self.dispatch()? {
+            Dispatch::Notification(notification) => Ok(Some(notification)),
+            Dispatch::Empty | Dispatch::Skipped => Ok(None),
+        }
+    }
+
+    /// Receive the next SimConnect message, waiting up to `timeout` for one to arrive.
+    ///
+    /// # Remarks
+    /// This is a blocking function. It returns as soon as a message is received, without polling in between, so no CPU time is spent while the simulator is idle.
+    /// If there are still no messages to receive after `timeout`, it will return None.
+    pub fn wait_next_dispatch(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Notification>, SimConnectError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match self.dispatch()? {
+                Dispatch::Notification(notification) => return Ok(Some(notification)),
+                Dispatch::Skipped => (),
+                Dispatch::Empty => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+
+                    if remaining.is_zero() || !self.backend.dispatch_event().wait(Some(remaining)) {
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Receive and decode the next SimConnect message.
+    pub(crate) fn dispatch(&mut self) -> Result<Dispatch, SimConnectError> {
+        self.unregister_dropped_subscriptions()?;
+
+        let (data_buf, data_len) = match self.backend.get_next_dispatch()? {
+            Some(data) if data.len() >= std::mem::size_of::<bindings::SIMCONNECT_RECV>() => (
+                data.as_ptr() as *const bindings::SIMCONNECT_RECV,
+                data.len(),
+            ),
+            Some(data) => {
+                return Err(SimConnectError::UnexpectedError(format!(
+                    "received a message of {} bytes, which is too short to be a SIMCONNECT_RECV",
+                    data.len()
+                )))
+            }
+            None => return Ok(Dispatch::Empty),
         };
 
         let recv_id = unsafe { (*data_buf).dwID as i32 };
 
         if recv_id == bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NULL {
-            Ok(None)
+            Ok(Dispatch::Empty)
         } else {
             let span = span!(Level::TRACE, "SimConnect::get_next_dispatch");
             let _enter = span.enter();
@@ -167,167 +262,373 @@ impl SimConnect {
             match recv_id {
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_OPEN => {
                     trace!("Received SIMCONNECT_RECV_OPEN");
-                    Ok(Some(Notification::Open))
+                    Ok(Dispatch::Notification(Notification::Open))
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_QUIT => {
                     trace!("Received SIMCONNECT_RECV_QUIT");
-                    Ok(Some(Notification::Quit))
+                    Ok(Dispatch::Notification(Notification::Quit))
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT => {
                     trace!("Received SIMCONNECT_RECV_EVENT");
                     let event: &bindings::SIMCONNECT_RECV_EVENT =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_EVENT) };
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    if event.uEventID >= CUSTOM_CLIENT_EVENT_ID_START {
+                        let name = self.custom_client_event_name(event.uEventID)?;
 
-                    if event.uEventID >= CLIENT_EVENT_DISCRIMINANT_START {
+                        Ok(Dispatch::Notification(Notification::CustomClientEvent {
+                            name,
+                            data: event.dwData as i32,
+                        }))
+                    } else if event.uEventID >= CLIENT_EVENT_DISCRIMINANT_START {
                         let event = ClientEvent::try_from(event)?;
 
-                        Ok(Some(Notification::ClientEvent(event)))
+                        Ok(Dispatch::Notification(Notification::ClientEvent(event)))
                     } else {
                         let event = SystemEvent::try_from(event)?;
 
-                        Ok(Some(Notification::SystemEvent(event)))
+                        Ok(Dispatch::Notification(Notification::SystemEvent(event)))
                     }
                 }
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_EX1 => {
+                    trace!("Received SIMCONNECT_RECV_EVENT_EX1");
+                    let event: &bindings::SIMCONNECT_RECV_EVENT_EX1 =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    let data = [
+                        event.dwData0 as i32,
+                        event.dwData1 as i32,
+                        event.dwData2 as i32,
+                        event.dwData3 as i32,
+                        event.dwData4 as i32,
+                    ];
+
+                    let name = if event.uEventID >= CUSTOM_CLIENT_EVENT_ID_START {
+                        self.custom_client_event_name(event.uEventID)?
+                    } else if event.uEventID >= CLIENT_EVENT_DISCRIMINANT_START {
+                        ClientEventRequest::try_from(event.uEventID)?
+                            .into_event_name()
+                            .to_string()
+                    } else {
+                        // System events only carry a single data parameter.
+                        let event = SystemEvent::try_from(&bindings::SIMCONNECT_RECV_EVENT {
+                            _base: event._base,
+                            uGroupID: event.uGroupID,
+                            uEventID: event.uEventID,
+                            dwData: event.dwData0,
+                        })?;
+
+                        return Ok(Dispatch::Notification(Notification::SystemEvent(event)));
+                    };
+
+                    Ok(Dispatch::Notification(Notification::ClientEventEx1 {
+                        name,
+                        data,
+                    }))
+                }
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_OBJECT_ADDREMOVE => {
+                    trace!("Received SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE");
+                    let event: &bindings::SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    let event = SystemEvent::try_from(event)?;
+                    Ok(Dispatch::Notification(Notification::SystemEvent(event)))
+                }
+                recv_id if PLAIN_EVENT_RECV_IDS.contains(&recv_id) => {
+                    trace!("Received SIMCONNECT_RECV_EVENT based message {recv_id}");
+                    let event: &bindings::SIMCONNECT_RECV_EVENT =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    let event = SystemEvent::try_from(event)?;
+                    Ok(Dispatch::Notification(Notification::SystemEvent(event)))
+                }
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_RACE_END => {
+                    trace!("Received SIMCONNECT_RECV_EVENT_RACE_END");
+                    let event: &bindings::SIMCONNECT_RECV_EVENT_RACE_END =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    let event = SystemEvent::try_from(event)?;
+                    Ok(Dispatch::Notification(Notification::SystemEvent(event)))
+                }
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_RACE_LAP => {
+                    trace!("Received SIMCONNECT_RECV_EVENT_RACE_LAP");
+                    let event: &bindings::SIMCONNECT_RECV_EVENT_RACE_LAP =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    let event = SystemEvent::try_from(event)?;
+                    Ok(Dispatch::Notification(Notification::SystemEvent(event)))
+                }
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CUSTOM_ACTION => {
+                    trace!("Received SIMCONNECT_RECV_CUSTOM_ACTION");
+                    let event: &bindings::SIMCONNECT_RECV_CUSTOM_ACTION =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    // `szPayLoad` is defined as a 1-element array, but it is actually a variable-length string.
+                    let payload_offset =
+                        std::mem::offset_of!(bindings::SIMCONNECT_RECV_CUSTOM_ACTION, szPayLoad);
+                    let payload = unsafe {
+                        std::slice::from_raw_parts(
+                            (data_buf as *const i8).add(payload_offset),
+                            data_len.saturating_sub(payload_offset),
+                        )
+                    };
+
+                    let event = SystemEvent::from_custom_action(event, payload)?;
+                    Ok(Dispatch::Notification(Notification::SystemEvent(event)))
+                }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME => {
                     trace!("Received SIMCONNECT_RECV_EVENT_FILENAME");
                     let event: &bindings::SIMCONNECT_RECV_EVENT_FILENAME =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_EVENT_FILENAME) };
+                        unsafe { recv_as(data_buf, data_len)? };
 
                     let event = SystemEvent::try_from(event)?;
-                    Ok(Some(Notification::SystemEvent(event)))
+                    Ok(Dispatch::Notification(Notification::SystemEvent(event)))
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FRAME => {
                     trace!("Received SIMCONNECT_RECV_EVENT_FRAME");
                     let event: &bindings::SIMCONNECT_RECV_EVENT_FRAME =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_EVENT_FRAME) };
+                        unsafe { recv_as(data_buf, data_len)? };
 
                     let event = SystemEvent::try_from(event)?;
-                    Ok(Some(Notification::SystemEvent(event)))
+                    Ok(Dispatch::Notification(Notification::SystemEvent(event)))
+                }
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SYSTEM_STATE => {
+                    trace!("Received SIMCONNECT_RECV_SYSTEM_STATE");
+                    let event: &bindings::SIMCONNECT_RECV_SYSTEM_STATE =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    let request_id = event.dwRequestID;
+
+                    match self.system_state_requests.remove(&request_id) {
+                        Some(request) => {
+                            self.registered_objects.remove(&request_id);
+
+                            Ok(Dispatch::Notification(Notification::SystemState(
+                                SystemState::new(request, event),
+                            )))
+                        }
+                        None => {
+                            warn!("Received system state for unknown request ID {request_id}");
+                            Ok(Dispatch::Skipped)
+                        }
+                    }
+                }
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_RESERVED_KEY => {
+                    trace!("Received SIMCONNECT_RECV_RESERVED_KEY");
+                    let event: &bindings::SIMCONNECT_RECV_RESERVED_KEY =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    Ok(Dispatch::Notification(Notification::ReservedKey(
+                        ReservedKey::from(event),
+                    )))
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA => {
                     trace!("Received SIMCONNECT_RECV_SIMOBJECT_DATA");
 
                     let event: &bindings::SIMCONNECT_RECV_SIMOBJECT_DATA =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_SIMOBJECT_DATA) };
+                        unsafe { recv_as(data_buf, data_len)? };
 
-                    let type_name = self.get_type_name_by_request_id(event.dwDefineID);
+                    let request_id = event.dwRequestID;
+                    let type_name = self.get_type_name_by_request_id(request_id);
 
                     match type_name {
                         Some(type_name) => {
                             let data = Object {
                                 type_name,
-                                data_addr: std::ptr::addr_of!(event.dwData),
+                                request_id,
+                                object_id: ObjectId::from(event.dwObjectID),
+                                data: unsafe { sim_object_data(data_buf, data_len) },
                             };
 
-                            Ok(Some(Notification::Object(data)))
+                            if let Some(route) = self
+                                .registered_objects
+                                .get(&request_id)
+                                .and_then(|object| object.route.as_ref())
+                            {
+                                route(&data);
+                            }
+
+                            Ok(Dispatch::Notification(Notification::Object(data)))
                         }
-                        _ => Ok(None),
+                        _ => Ok(Dispatch::Skipped),
+                    }
+                }
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE => {
+                    trace!("Received SIMCONNECT_RECV_SIMOBJECT_DATA_BYTYPE");
+
+                    let event: &bindings::SIMCONNECT_RECV_SIMOBJECT_DATA_BYTYPE =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    let request_id = event._base.dwRequestID;
+                    let out_of = event._base.dwoutof;
+
+                    let Some(type_name) = self.get_type_name_by_request_id(request_id) else {
+                        return Ok(Dispatch::Skipped);
+                    };
+
+                    let objects = self.objects_by_type.entry(request_id).or_default();
+
+                    if out_of > 0 {
+                        let object_id = ObjectId::from(event._base.dwObjectID);
+
+                        objects.push((
+                            object_id,
+                            Object {
+                                type_name: type_name.clone(),
+                                request_id,
+                                object_id,
+                                data: unsafe { sim_object_data(data_buf, data_len) },
+                            },
+                        ));
+                    }
+
+                    // The objects are sent one per message, so they are collected until the last one is received.
+                    // When no objects are in range, a single message with an `out_of` of 0 and no data is received instead.
+                    if objects.len() as u32 >= out_of {
+                        let objects = self.objects_by_type.remove(&request_id).unwrap_or_default();
+
+                        // The request is one-off, so it gets unregistered.
+                        self.unregister_request_id(request_id)?;
+
+                        Ok(Dispatch::Notification(Notification::ObjectsByType {
+                            request_id,
+                            type_name,
+                            objects,
+                        }))
+                    } else {
+                        Ok(Dispatch::Skipped)
                     }
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_AIRPORT_LIST => {
                     trace!("Received SIMCONNECT_RECV_AIRPORT_LIST");
 
-                    let event: &bindings::SIMCONNECT_RECV_AIRPORT_LIST =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_AIRPORT_LIST) };
+                    let list: &bindings::SIMCONNECT_RECV_FACILITIES_LIST =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    // `rgData` is defined as a 1-element array, but it is actually a variable-length array.
+                    let records: &[bindings::SIMCONNECT_DATA_FACILITY_AIRPORT] = unsafe {
+                        list_records(
+                            data_buf,
+                            data_len,
+                            std::mem::offset_of!(bindings::SIMCONNECT_RECV_AIRPORT_LIST, rgData),
+                            list.dwArraySize,
+                        )?
+                    };
 
                     self.unregister_potential_transient_request(
-                        event._base.dwEntryNumber,
-                        event._base.dwOutOf,
-                        event._base.dwRequestID,
+                        list.dwEntryNumber,
+                        list.dwOutOf,
+                        list.dwRequestID,
                     );
 
-                    let data = (0..event._base.dwArraySize as usize)
-                        .map(|i| {
-                            // `rgData` is defined as a 1-element array, but it is actually a variable-length array.
-                            let record = unsafe { event.rgData.get_unchecked(i) };
-
-                            Airport {
-                                icao: fixed_c_str_to_string(&record.Icao),
-                                lat: record.Latitude,
-                                lon: record.Longitude,
-                                alt: record.Altitude,
-                            }
+                    let data = records
+                        .iter()
+                        .map(|record| Airport {
+                            icao: fixed_c_str_to_string(&record.Icao),
+                            lat: record.Latitude,
+                            lon: record.Longitude,
+                            alt: record.Altitude,
                         })
                         .collect::<Vec<_>>();
 
-                    Ok(Some(Notification::AirportList(data)))
+                    Ok(Dispatch::Notification(Notification::AirportList(data)))
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_WAYPOINT_LIST => {
                     trace!("Received SIMCONNECT_RECV_WAYPOINT_LIST");
 
-                    let event: &bindings::SIMCONNECT_RECV_WAYPOINT_LIST =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_WAYPOINT_LIST) };
+                    let list: &bindings::SIMCONNECT_RECV_FACILITIES_LIST =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    // `rgData` is defined as a 1-element array, but it is actually a variable-length array.
+                    let records: &[bindings::SIMCONNECT_DATA_FACILITY_WAYPOINT] = unsafe {
+                        list_records(
+                            data_buf,
+                            data_len,
+                            std::mem::offset_of!(bindings::SIMCONNECT_RECV_WAYPOINT_LIST, rgData),
+                            list.dwArraySize,
+                        )?
+                    };
 
                     self.unregister_potential_transient_request(
-                        event._base.dwEntryNumber,
-                        event._base.dwOutOf,
-                        event._base.dwRequestID,
+                        list.dwEntryNumber,
+                        list.dwOutOf,
+                        list.dwRequestID,
                     );
 
-                    let data = (0..event._base.dwArraySize as usize)
-                        .map(|i| {
-                            // `rgData` is defined as a 1-element array, but it is actually a variable-length array.
-                            let record = unsafe { event.rgData.get_unchecked(i) };
-
-                            Waypoint {
-                                icao: fixed_c_str_to_string(&record._base.Icao),
-                                lat: record._base.Latitude,
-                                lon: record._base.Longitude,
-                                alt: record._base.Altitude,
-                                mag_var: record.fMagVar,
-                            }
+                    let data = records
+                        .iter()
+                        .map(|record| Waypoint {
+                            icao: fixed_c_str_to_string(&record._base.Icao),
+                            lat: record._base.Latitude,
+                            lon: record._base.Longitude,
+                            alt: record._base.Altitude,
+                            mag_var: record.fMagVar,
                         })
                         .collect::<Vec<_>>();
 
-                    Ok(Some(Notification::WaypointList(data)))
+                    Ok(Dispatch::Notification(Notification::WaypointList(data)))
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NDB_LIST => {
                     trace!("Received SIMCONNECT_RECV_NDB_LIST");
 
-                    let event: &bindings::SIMCONNECT_RECV_NDB_LIST =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_NDB_LIST) };
+                    let list: &bindings::SIMCONNECT_RECV_FACILITIES_LIST =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    // `rgData` is defined as a 1-element array, but it is actually a variable-length array.
+                    let records: &[bindings::SIMCONNECT_DATA_FACILITY_NDB] = unsafe {
+                        list_records(
+                            data_buf,
+                            data_len,
+                            std::mem::offset_of!(bindings::SIMCONNECT_RECV_NDB_LIST, rgData),
+                            list.dwArraySize,
+                        )?
+                    };
 
                     self.unregister_potential_transient_request(
-                        event._base.dwEntryNumber,
-                        event._base.dwOutOf,
-                        event._base.dwRequestID,
+                        list.dwEntryNumber,
+                        list.dwOutOf,
+                        list.dwRequestID,
                     );
 
-                    let data = (0..event._base.dwArraySize as usize)
-                        .map(|i| {
-                            // `rgData` is defined as a 1-element array, but it is actually a variable-length array.
-                            let record = unsafe { event.rgData.get_unchecked(i) };
-
-                            NDB {
-                                icao: fixed_c_str_to_string(&record._base._base.Icao),
-                                lat: record._base._base.Latitude,
-                                lon: record._base._base.Longitude,
-                                alt: record._base._base.Altitude,
-                                mag_var: record._base.fMagVar,
-                                frequency: record.fFrequency,
-                            }
+                    let data = records
+                        .iter()
+                        .map(|record| NDB {
+                            icao: fixed_c_str_to_string(&record._base._base.Icao),
+                            lat: record._base._base.Latitude,
+                            lon: record._base._base.Longitude,
+                            alt: record._base._base.Altitude,
+                            mag_var: record._base.fMagVar,
+                            frequency: record.fFrequency,
                         })
                         .collect::<Vec<_>>();
 
-                    Ok(Some(Notification::NdbList(data)))
+                    Ok(Dispatch::Notification(Notification::NdbList(data)))
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_VOR_LIST => {
                     trace!("Received SIMCONNECT_RECV_VOR_LIST");
 
-                    let event: &bindings::SIMCONNECT_RECV_VOR_LIST =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_VOR_LIST) };
+                    let list: &bindings::SIMCONNECT_RECV_FACILITIES_LIST =
+                        unsafe { recv_as(data_buf, data_len)? };
+
+                    // `rgData` is defined as a 1-element array, but it is actually a variable-length array.
+                    let records: &[bindings::SIMCONNECT_DATA_FACILITY_VOR] = unsafe {
+                        list_records(
+                            data_buf,
+                            data_len,
+                            std::mem::offset_of!(bindings::SIMCONNECT_RECV_VOR_LIST, rgData),
+                            list.dwArraySize,
+                        )?
+                    };
 
                     self.unregister_potential_transient_request(
-                        event._base.dwEntryNumber,
-                        event._base.dwOutOf,
-                        event._base.dwRequestID,
+                        list.dwEntryNumber,
+                        list.dwOutOf,
+                        list.dwRequestID,
                     );
 
-                    let data = (0..event._base.dwArraySize as usize)
-                        .map(|i| {
-                            // `rgData` is defined as a 1-element array, but it is actually a variable-length array.
-                            let record = unsafe { event.rgData.get_unchecked(i) };
-
+                    let data = records
+                        .iter()
+                        .map(|record| {
                             let has_nav_signal = record.Flags
                                 & bindings::SIMCONNECT_RECV_ID_VOR_LIST_HAS_NAV_SIGNAL
                                 == bindings::SIMCONNECT_RECV_ID_VOR_LIST_HAS_NAV_SIGNAL;
@@ -385,17 +686,17 @@ impl SimConnect {
                         })
                         .collect::<Vec<_>>();
 
-                    Ok(Some(Notification::VorList(data)))
+                    Ok(Dispatch::Notification(Notification::VorList(data)))
                 }
                 bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION => {
                     let event: &bindings::SIMCONNECT_RECV_EXCEPTION =
-                        unsafe { &*(data_buf as *const bindings::SIMCONNECT_RECV_EXCEPTION) };
+                        unsafe { recv_as(data_buf, data_len)? };
 
                     warn!("Received {:?}", event);
 
                     Err(SimConnectError::SimConnectException(event.dwException))
                 }
-                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NULL => Ok(None),
+                bindings::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NULL => Ok(Dispatch::Empty),
                 id => {
                     error!("Received unhandled notification ID: {}", id);
                     Err(SimConnectError::UnimplementedNotification(id))
@@ -404,6 +705,15 @@ impl SimConnect {
         }
     }
 
+    /// The name of a client event that has been mapped through [`SimConnect::map_custom_client_event`].
+    fn custom_client_event_name(&self, event_id: u32) -> Result<String, SimConnectError> {
+        self.custom_client_events
+            .iter()
+            .find(|(_, id)| **id == event_id)
+            .map(|(name, _)| name.clone())
+            .ok_or(SimConnectError::UnimplementedEventType(event_id))
+    }
+
     /// Register a Request ID in the internal state so that the user doesn't have to manually manage Request IDs.
     #[tracing::instrument(name = "SimConnect::new_request_id", level = "trace", skip(self))]
     pub(crate) fn new_request_id(
@@ -411,38 +721,95 @@ impl SimConnect {
         type_name: String,
         transient: bool,
     ) -> Result<u32, SimConnectError> {
-        if self.registered_objects.contains_key(&type_name) {
+        if self
+            .registered_objects
+            .values()
+            .any(|obj| obj.type_name == type_name)
+        {
             return Err(SimConnectError::ObjectAlreadyRegistered(type_name));
         }
 
+        Ok(self.new_shared_request_id(type_name, transient))
+    }
+
+    /// Register a Request ID in the internal state, even if the Type Name is registered already.
+    #[tracing::instrument(
+        name = "SimConnect::new_shared_request_id",
+        level = "trace",
+        skip(self)
+    )]
+    pub(crate) fn new_shared_request_id(&mut self, type_name: String, transient: bool) -> u32 {
         let mut request_id = self.next_request_id;
-        self.next_request_id += 1;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
 
         // when `next_request_id` overflows some ids might still be in use
         // so we need to find the next available one
-        while self
-            .registered_objects
-            .values()
-            .any(|obj| obj.id == request_id)
-        {
+        while self.registered_objects.contains_key(&request_id) {
             request_id = self.next_request_id;
-            self.next_request_id += 1;
+            self.next_request_id = self.next_request_id.wrapping_add(1);
         }
 
         self.registered_objects
-            .insert(type_name, RegisteredObject::new(request_id, transient));
+            .insert(request_id, RegisteredObject::new(type_name, transient));
 
-        Ok(request_id)
+        request_id
     }
 
-    /// Unregister a Request ID in the internal state so that the user doesn't have to manually manage Request IDs.
+    /// Unregister all the Request IDs of a Type Name in the internal state so that the user doesn't have to manually manage Request IDs.
+    ///
+    /// The data definitions registered by [`crate::SimConnect::set_data_on_sim_object`] are kept, as they have not been registered by the user.
     #[tracing::instrument(
-        name = "SimConnect::unregister_request_id_by_type_name",
+        name = "SimConnect::unregister_request_ids_by_type_name",
         level = "trace",
         skip(self)
     )]
-    pub(crate) fn unregister_request_id_by_type_name(&mut self, type_name: &str) -> Option<u32> {
-        self.registered_objects.remove(type_name).map(|obj| obj.id)
+    pub(crate) fn unregister_request_ids_by_type_name(&mut self, type_name: &str) -> Vec<u32> {
+        let mut request_ids = self
+            .registered_objects
+            .iter()
+            .filter(|(_, obj)| obj.type_name == type_name && !obj.set_only)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        request_ids.sort_unstable();
+
+        for request_id in &request_ids {
+            self.registered_objects.remove(request_id);
+            self.objects_by_type.remove(request_id);
+        }
+
+        request_ids
+    }
+
+    /// Unregister an object by its Request ID, clearing its data definition.
+    #[tracing::instrument(
+        name = "SimConnect::unregister_request_id",
+        level = "trace",
+        skip(self)
+    )]
+    pub(crate) fn unregister_request_id(&mut self, request_id: u32) -> Result<(), SimConnectError> {
+        if !self.registered_objects.contains_key(&request_id) {
+            return Err(SimConnectError::UnexpectedError(format!(
+                "request {request_id} is not registered"
+            )));
+        }
+
+        self.backend.clear_data_definition(request_id)?;
+        self.registered_objects.remove(&request_id);
+        self.objects_by_type.remove(&request_id);
+
+        Ok(())
+    }
+
+    /// Unregister the objects whose [`crate::Subscription`] has been dropped.
+    pub(crate) fn unregister_dropped_subscriptions(&mut self) -> Result<(), SimConnectError> {
+        while let Ok(request_id) = self.dropped_subscriptions.1.try_recv() {
+            // The object might have been unregistered through `unregister_object` already.
+            if self.registered_objects.contains_key(&request_id) {
+                self.unregister_request_id(request_id)?;
+            }
+        }
+
+        Ok(())
     }
 
     /// Get the Type Name of a Request ID.
@@ -453,18 +820,16 @@ impl SimConnect {
     )]
     pub(crate) fn get_type_name_by_request_id(&self, request_id: u32) -> Option<String> {
         self.registered_objects
-            .iter()
-            .find(|(_, v)| v.id == request_id)
-            .map(|(k, _)| k.clone())
+            .get(&request_id)
+            .map(|obj| obj.type_name.clone())
     }
 
     /// Get the Type Name of a Request ID.
     #[tracing::instrument(name = "SimConnect::is_transient_request", level = "trace", skip(self))]
     pub(crate) fn is_transient_request(&self, request_id: u32) -> Option<bool> {
         self.registered_objects
-            .iter()
-            .find(|(_, v)| v.id == request_id)
-            .map(|(_, v)| v.transient)
+            .get(&request_id)
+            .map(|obj| obj.transient)
     }
 
     /// Checks if the request is
@@ -494,16 +859,78 @@ impl SimConnect {
                     tracing::Span::current().record("type_name", type_name);
 
                     trace!("Clearing");
-                    self.unregister_request_id_by_type_name(type_name);
+                    self.registered_objects.remove(&request_id);
                 }
             }
         }
     }
 }
 
+/// Copy the data of a `SIMCONNECT_RECV_SIMOBJECT_DATA` message, which starts at `dwData`.
+///
+/// # Safety
+/// `data_buf` must point to a message of `data_len` bytes.
+unsafe fn sim_object_data(data_buf: *const bindings::SIMCONNECT_RECV, data_len: usize) -> Vec<u8> {
+    let offset = std::mem::size_of::<bindings::SIMCONNECT_RECV_SIMOBJECT_DATA>()
+        - std::mem::size_of::<bindings::DWORD>();
+
+    if data_len <= offset {
+        return Vec::new();
+    }
+
+    std::slice::from_raw_parts((data_buf as *const u8).add(offset), data_len - offset).to_vec()
+}
+
+/// Reinterpret a message as a `SIMCONNECT_RECV_*` struct, after checking that the message is long enough to hold it.
+///
+/// # Safety
+/// `data_buf` must point to a message of `data_len` bytes that outlives the returned reference. `T` must be a packed `SIMCONNECT_RECV_*` struct.
+unsafe fn recv_as<'a, T>(
+    data_buf: *const bindings::SIMCONNECT_RECV,
+    data_len: usize,
+) -> Result<&'a T, SimConnectError> {
+    if data_len < std::mem::size_of::<T>() {
+        return Err(SimConnectError::UnexpectedError(format!(
+            "received a message of {data_len} bytes, which is too short to be a {}",
+            std::any::type_name::<T>()
+                .rsplit("::")
+                .next()
+                .unwrap_or_default()
+        )));
+    }
+
+    Ok(&*(data_buf as *const T))
+}
+
+/// The records of a `SIMCONNECT_RECV_*_LIST` message, which start at `offset`, after checking that the message is long enough to hold `array_size` of them.
+///
+/// # Safety
+/// `data_buf` must point to a message of `data_len` bytes that outlives the returned slice. `T` must be a packed `SIMCONNECT_DATA_*` struct.
+unsafe fn list_records<'a, T>(
+    data_buf: *const bindings::SIMCONNECT_RECV,
+    data_len: usize,
+    offset: usize,
+    array_size: u32,
+) -> Result<&'a [T], SimConnectError> {
+    let size = (array_size as usize)
+        .checked_mul(std::mem::size_of::<T>())
+        .and_then(|size| size.checked_add(offset));
+
+    match size {
+        Some(size) if size <= data_len => Ok(std::slice::from_raw_parts(
+            (data_buf as *const u8).add(offset) as *const T,
+            array_size as usize,
+        )),
+        _ => Err(SimConnectError::UnexpectedError(format!(
+            "received a message of {data_len} bytes, which is too short to hold {array_size} records of {}",
+            std::any::type_name::<T>().rsplit("::").next().unwrap_or_default()
+        ))),
+    }
+}
+
 impl Drop for SimConnect {
     #[tracing::instrument(name = "SimConnect::drop", level = "debug", skip(self))]
     fn drop(&mut self) {
-        let _ = unsafe { bindings::SimConnect_Close(self.handle.as_ptr()) };
+        let _ = self.backend.close();
     }
 }
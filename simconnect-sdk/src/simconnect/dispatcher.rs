@@ -0,0 +1,434 @@
+use std::time::Duration;
+
+use crate::{
+    Airport, ClientEvent, ClientEventRequest, Notification, Object, ObjectId, ReservedKey,
+    SimConnect, SimConnectError, SimConnectObjectExt, SystemEvent, SystemEventRequest, SystemState,
+    Waypoint, NDB, VOR,
+};
+
+// How often `run_until` checks its predicate while no notifications are received.
+const PREDICATE_INTERVAL: Duration = Duration::from_millis(100);
+
+type ClientHandler = Box<dyn FnMut(&mut SimConnect) -> Result<(), SimConnectError>>;
+type Handler<T> = Box<dyn FnMut(&mut SimConnect, T) -> Result<(), SimConnectError>>;
+type ListHandler<T> = Box<dyn FnMut(&mut SimConnect, &[T]) -> Result<(), SimConnectError>>;
+type ObjectHandler = Box<dyn FnMut(&mut SimConnect, &Object) -> Result<(), SimConnectError>>;
+type ObjectsByTypeHandler =
+    Box<dyn FnMut(&mut SimConnect, &[(ObjectId, Object)]) -> Result<(), SimConnectError>>;
+
+/// Callback based SimConnect SDK Client.
+///
+/// It wraps a [`SimConnect`] client and calls the handlers registered for each kind of notification, so that the receive loop doesn't have to be written by hand.
+/// Handlers receive the client, so that they can register objects, subscribe to events and so on. An error returned by a handler stops [`Dispatcher::run`].
+/// Any number of handlers can be registered for the same kind of notification. They are called in the order they have been registered in.
+///
+/// # Remarks
+/// The handlers only receive the notifications, they don't subscribe to them.
+/// Objects still have to be registered through [`SimConnect::register_object`], events subscribed to through [`SimConnect::subscribe_to_system_event`] and so on, typically from within [`Dispatcher::on_open`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use simconnect_sdk::{Dispatcher, SimConnect, SimConnectObject, SystemEventRequest};
+///
+/// #[derive(Debug, Clone, SimConnectObject)]
+/// #[simconnect(period = "second")]
+/// #[allow(dead_code)]
+/// struct AirplaneData {
+///     #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+///     alt: f64,
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = SimConnect::new("Dispatcher example")?;
+///
+///     Dispatcher::new(client)
+///         .on_open(|client| {
+///             client.register_object::<AirplaneData>()?.detach();
+///             client.subscribe_to_system_event(SystemEventRequest::Pause)
+///         })
+///         .on_object(|_, airplane_data: AirplaneData| {
+///             println!("{airplane_data:?}");
+///             Ok(())
+///         })
+///         .on_system_event(SystemEventRequest::Pause, |_, event| {
+///             println!("{event:?}");
+///             Ok(())
+///         })
+///         .run()?;
+///
+///     Ok(())
+/// }
+/// ```
+pub struct Dispatcher {
+    client: SimConnect,
+    open: Vec<ClientHandler>,
+    quit: Vec<ClientHandler>,
+    objects: Vec<ObjectHandler>,
+    objects_by_type: Vec<(String, ObjectsByTypeHandler)>,
+    system_events: Vec<(SystemEventRequest, Handler<SystemEvent>)>,
+    client_events: Vec<(ClientEventRequest, Handler<ClientEvent>)>,
+    custom_client_events: Vec<(String, Handler<i32>)>,
+    client_events_ex1: Vec<(String, Handler<[i32; 5]>)>,
+    reserved_keys: Vec<Handler<ReservedKey>>,
+    system_states: Vec<Handler<SystemState>>,
+    airports: Vec<ListHandler<Airport>>,
+    waypoints: Vec<ListHandler<Waypoint>>,
+    ndbs: Vec<ListHandler<NDB>>,
+    vors: Vec<ListHandler<VOR>>,
+    exceptions: Vec<Handler<u32>>,
+}
+
+impl Dispatcher {
+    /// Create a new dispatcher around `client`, without any handlers.
+    pub fn new(client: SimConnect) -> Self {
+        Self {
+            client,
+            open: Vec::new(),
+            quit: Vec::new(),
+            objects: Vec::new(),
+            objects_by_type: Vec::new(),
+            system_events: Vec::new(),
+            client_events: Vec::new(),
+            custom_client_events: Vec::new(),
+            client_events_ex1: Vec::new(),
+            reserved_keys: Vec::new(),
+            system_states: Vec::new(),
+            airports: Vec::new(),
+            waypoints: Vec::new(),
+            ndbs: Vec::new(),
+            vors: Vec::new(),
+            exceptions: Vec::new(),
+        }
+    }
+
+    /// The underlying [`SimConnect`] client.
+    pub fn client(&mut self) -> &mut SimConnect {
+        &mut self.client
+    }
+
+    /// Unwrap the underlying [`SimConnect`] client.
+    pub fn into_inner(self) -> SimConnect {
+        self.client
+    }
+
+    /// Call `handler` when the connection is open.
+    pub fn on_open(
+        mut self,
+        handler: impl FnMut(&mut SimConnect) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.open.push(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` when the simulator quits. [`Dispatcher::run`] returns right after.
+    pub fn on_quit(
+        mut self,
+        handler: impl FnMut(&mut SimConnect) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.quit.push(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` with the data of every object that can be decoded as a `T`.
+    ///
+    /// The objects of other structs are ignored. An error decoding the data of a `T` stops [`Dispatcher::run`].
+    pub fn on_object<T>(
+        mut self,
+        mut handler: impl FnMut(&mut SimConnect, T) -> Result<(), SimConnectError> + 'static,
+    ) -> Self
+    where
+        T: SimConnectObjectExt + for<'a> TryFrom<&'a Object, Error = SimConnectError> + 'static,
+    {
+        self.objects.push(Box::new(move |client, object| {
+            match T::try_from(object) {
+                Ok(value) => handler(client, value),
+                // the object belongs to a different struct
+                Err(SimConnectError::ObjectMismatch { .. }) => Ok(()),
+                Err(e) => Err(e),
+            }
+        }));
+        self
+    }
+
+    /// Call `handler` with the data of the simulation objects received for a [`SimConnect::request_data_by_type`] of `T`.
+    ///
+    /// An empty list means that there were no objects in range. An error decoding the data of a `T` stops [`Dispatcher::run`].
+    pub fn on_objects_by_type<T>(
+        mut self,
+        mut handler: impl FnMut(&mut SimConnect, Vec<(ObjectId, T)>) -> Result<(), SimConnectError>
+            + 'static,
+    ) -> Self
+    where
+        T: SimConnectObjectExt + for<'a> TryFrom<&'a Object, Error = SimConnectError> + 'static,
+    {
+        let type_name = std::any::type_name::<T>().to_string();
+
+        self.objects_by_type.push((
+            type_name,
+            Box::new(move |client, objects| {
+                let values = objects
+                    .iter()
+                    .map(|(object_id, object)| T::try_from(object).map(|value| (*object_id, value)))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                handler(client, values)
+            }),
+        ));
+        self
+    }
+
+    /// Call `handler` when the system event `request` is received.
+    pub fn on_system_event(
+        mut self,
+        request: SystemEventRequest,
+        handler: impl FnMut(&mut SimConnect, SystemEvent) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.system_events.push((request, Box::new(handler)));
+        self
+    }
+
+    /// Call `handler` when the client event `request` is received.
+    pub fn on_client_event(
+        mut self,
+        request: ClientEventRequest,
+        handler: impl FnMut(&mut SimConnect, ClientEvent) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.client_events.push((request, Box::new(handler)));
+        self
+    }
+
+    /// Call `handler` with the data of the custom client event `name` when it is received. See [`SimConnect::subscribe_to_custom_client_event`].
+    pub fn on_custom_client_event(
+        mut self,
+        name: &str,
+        handler: impl FnMut(&mut SimConnect, i32) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.custom_client_events
+            .push((name.to_string(), Box::new(handler)));
+        self
+    }
+
+    /// Call `handler` with the data parameters of the client event `name` when it is received along with up to five of them. See [`Notification::ClientEventEx1`].
+    pub fn on_client_event_ex1(
+        mut self,
+        name: &str,
+        handler: impl FnMut(&mut SimConnect, [i32; 5]) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.client_events_ex1
+            .push((name.to_string(), Box::new(handler)));
+        self
+    }
+
+    /// Call `handler` when a key has been reserved. See [`SimConnect::request_reserved_key`].
+    pub fn on_reserved_key(
+        mut self,
+        handler: impl FnMut(&mut SimConnect, ReservedKey) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.reserved_keys.push(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` when a system state is received. See [`SimConnect::request_system_state`].
+    pub fn on_system_state(
+        mut self,
+        handler: impl FnMut(&mut SimConnect, SystemState) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.system_states.push(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` when a list of [`crate::Airport`] is received.
+    pub fn on_airports(
+        mut self,
+        handler: impl FnMut(&mut SimConnect, &[Airport]) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.airports.push(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` when a list of [`crate::Waypoint`] is received.
+    pub fn on_waypoints(
+        mut self,
+        handler: impl FnMut(&mut SimConnect, &[Waypoint]) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.waypoints.push(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` when a list of [`crate::NDB`] is received.
+    pub fn on_ndbs(
+        mut self,
+        handler: impl FnMut(&mut SimConnect, &[NDB]) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.ndbs.push(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` when a list of [`crate::VOR`] is received.
+    pub fn on_vors(
+        mut self,
+        handler: impl FnMut(&mut SimConnect, &[VOR]) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.vors.push(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` with the code of every exception received from SimConnect, instead of stopping [`Dispatcher::run`] with [`crate::SimConnectError::SimConnectException`].
+    pub fn on_exception(
+        mut self,
+        handler: impl FnMut(&mut SimConnect, u32) -> Result<(), SimConnectError> + 'static,
+    ) -> Self {
+        self.exceptions.push(Box::new(handler));
+        self
+    }
+
+    /// Receive the notifications and call their handlers until the simulator quits.
+    ///
+    /// # Errors
+    /// The first error returned by the client or by a handler.
+    pub fn run(&mut self) -> Result<(), SimConnectError> {
+        self.run_until(|| false)
+    }
+
+    /// Receive the notifications and call their handlers until `predicate` returns `true` or the simulator quits.
+    ///
+    /// The predicate is checked before every notification, and at least every 100 milliseconds while none are received.
+    ///
+    /// # Errors
+    /// The first error returned by the client or by a handler.
+    pub fn run_until(
+        &mut self,
+        mut predicate: impl FnMut() -> bool,
+    ) -> Result<(), SimConnectError> {
+        while !predicate() {
+            let notification = match self.client.wait_next_dispatch(PREDICATE_INTERVAL) {
+                Ok(Some(notification)) => notification,
+                Ok(None) => continue,
+                Err(SimConnectError::SimConnectException(exception))
+                    if !self.exceptions.is_empty() =>
+                {
+                    for handler in &mut self.exceptions {
+                        handler(&mut self.client, exception)?;
+                    }
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            if matches!(notification, Notification::Quit) {
+                for handler in &mut self.quit {
+                    handler(&mut self.client)?;
+                }
+                return Ok(());
+            }
+
+            self.dispatch(notification)?;
+        }
+
+        Ok(())
+    }
+
+    /// Call the handlers of `notification`.
+    fn dispatch(&mut self, notification: Notification) -> Result<(), SimConnectError> {
+        let client = &mut self.client;
+
+        match notification {
+            Notification::Open => {
+                for handler in &mut self.open {
+                    handler(client)?;
+                }
+            }
+            Notification::Object(object) => {
+                for handler in &mut self.objects {
+                    handler(client, &object)?;
+                }
+            }
+            Notification::ObjectsByType {
+                type_name, objects, ..
+            } => {
+                for (_, handler) in self
+                    .objects_by_type
+                    .iter_mut()
+                    .filter(|(t, _)| *t == type_name)
+                {
+                    handler(client, &objects)?;
+                }
+            }
+            Notification::SystemEvent(event) => {
+                let request = SystemEventRequest::from(&event);
+
+                for (_, handler) in self.system_events.iter_mut().filter(|(r, _)| *r == request) {
+                    handler(client, event.clone())?;
+                }
+            }
+            Notification::ClientEvent(event) => {
+                let (request, _) = event.into();
+
+                for (_, handler) in self.client_events.iter_mut().filter(|(r, _)| *r == request) {
+                    handler(client, event)?;
+                }
+            }
+            Notification::CustomClientEvent { name, data } => {
+                for (_, handler) in self
+                    .custom_client_events
+                    .iter_mut()
+                    .filter(|(n, _)| *n == name)
+                {
+                    handler(client, data)?;
+                }
+            }
+            Notification::ClientEventEx1 { name, data } => {
+                for (_, handler) in self
+                    .client_events_ex1
+                    .iter_mut()
+                    .filter(|(n, _)| *n == name)
+                {
+                    handler(client, data)?;
+                }
+            }
+            Notification::ReservedKey(reserved_key) => {
+                for handler in &mut self.reserved_keys {
+                    handler(client, reserved_key.clone())?;
+                }
+            }
+            Notification::SystemState(state) => {
+                for handler in &mut self.system_states {
+                    handler(client, state.clone())?;
+                }
+            }
+            Notification::AirportList(data) => {
+                call_list_handlers(client, &mut self.airports, &data)?
+            }
+            Notification::WaypointList(data) => {
+                call_list_handlers(client, &mut self.waypoints, &data)?
+            }
+            Notification::NdbList(data) => call_list_handlers(client, &mut self.ndbs, &data)?,
+            Notification::VorList(data) => call_list_handlers(client, &mut self.vors, &data)?,
+            Notification::Quit => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Dispatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dispatcher")
+            .field("client", &self.client)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Call every handler of a facilities list.
+fn call_list_handlers<T>(
+    client: &mut SimConnect,
+    handlers: &mut [ListHandler<T>],
+    data: &[T],
+) -> Result<(), SimConnectError> {
+    for handler in handlers {
+        handler(client, data)?;
+    }
+
+    Ok(())
+}
@@ -6,6 +6,8 @@ where
     T: std::fmt::Debug + std::cmp::PartialEq,
 {
     items: Vec<T>,
+    // The registered items whose notifications have been turned off.
+    disabled: Vec<T>,
 }
 
 impl<T> EventRegister<T>
@@ -13,7 +15,10 @@ where
     T: std::fmt::Debug + std::cmp::PartialEq,
 {
     pub fn new() -> Self {
-        Self { items: Vec::new() }
+        Self {
+            items: Vec::new(),
+            disabled: Vec::new(),
+        }
     }
 
     pub fn is_registered(&self, item: T) -> bool {
@@ -38,11 +43,30 @@ where
         }
 
         self.items.retain(|i| *i != item);
+        self.disabled.retain(|i| *i != item);
+
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, item: T) -> bool {
+        self.items.contains(&item) && !self.disabled.contains(&item)
+    }
+
+    pub fn set_enabled(&mut self, item: T, enabled: bool) -> Result<(), SimConnectError> {
+        if !self.items.contains(&item) {
+            return Err(SimConnectError::EventNotSubscribedTo(format!("{item:?}")));
+        }
+
+        self.disabled.retain(|i| *i != item);
+        if !enabled {
+            self.disabled.push(item);
+        }
 
         Ok(())
     }
 
     pub fn clear(&mut self) {
         self.items.clear();
+        self.disabled.clear();
     }
 }
@@ -1,9 +1,13 @@
 use crate::{
-    bindings, success, ClientEventRequest, SimConnect, SimConnectError, SystemEventRequest,
+    bindings, ClientEvent, ClientEventRequest, InputEvent, InputGroup, KeyChord, NotificationGroup,
+    NotificationGroupEvent, NotificationGroupPriority, ObjectId, SimConnect, SimConnectError,
+    SystemEventRequest, CUSTOM_CLIENT_EVENT_ID_START, MAX_RESERVED_KEY_CHOICES,
 };
 
 // In order to simplify the usage we're using a single notification group for all client events.
 const NOTIFICATION_GROUP_ID: u32 = 0;
+// The maximum number of data parameters `SimConnect_TransmitClientEvent_EX1` accepts.
+const MAX_EVENT_DATA_LEN: usize = 5;
 
 impl SimConnect {
     /// Request that a specific system event is notified.
@@ -18,13 +22,8 @@ impl SimConnect {
     ) -> Result<(), SimConnectError> {
         self.system_event_register.register(event)?;
 
-        success!(unsafe {
-            bindings::SimConnect_SubscribeToSystemEvent(
-                self.handle.as_ptr(),
-                event as u32,
-                event.into_c_char(),
-            )
-        })?;
+        self.backend
+            .subscribe_to_system_event(event as u32, event.into_event_name())?;
 
         Ok(())
     }
@@ -41,16 +40,50 @@ impl SimConnect {
         event: SystemEventRequest,
     ) -> Result<(), SimConnectError> {
         if self.system_event_register.is_registered(event) {
-            success!(unsafe {
-                bindings::SimConnect_UnsubscribeFromSystemEvent(self.handle.as_ptr(), event as u32)
-            })?;
+            self.backend.unsubscribe_from_system_event(event as u32)?;
 
-            self.system_event_register.clear();
+            self.system_event_register.unregister(event)?;
         }
 
         Ok(())
     }
 
+    /// Turn the notifications of a system event subscribed to through [`SimConnect::subscribe_to_system_event`] on or off, without unsubscribing from it.
+    ///
+    /// # Errors
+    /// - [`crate::SimConnectError::EventNotSubscribedTo`] -- The system event has not been subscribed to.
+    #[tracing::instrument(
+        name = "SimConnect::set_system_event_state",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn set_system_event_state(
+        &mut self,
+        event: SystemEventRequest,
+        on: bool,
+    ) -> Result<(), SimConnectError> {
+        if !self.system_event_register.is_registered(event) {
+            return Err(SimConnectError::EventNotSubscribedTo(format!("{event:?}")));
+        }
+
+        let state = if on {
+            bindings::SIMCONNECT_STATE_SIMCONNECT_STATE_ON
+        } else {
+            bindings::SIMCONNECT_STATE_SIMCONNECT_STATE_OFF
+        };
+
+        // The state is only tracked once the backend has changed it.
+        self.backend
+            .set_system_event_state(event as u32, state as u32)?;
+
+        self.system_event_register.set_enabled(event, on)
+    }
+
+    /// Whether the notifications of a system event are on, i.e. whether it has been subscribed to and not turned off through [`SimConnect::set_system_event_state`].
+    pub fn is_system_event_on(&self, event: SystemEventRequest) -> bool {
+        self.system_event_register.is_enabled(event)
+    }
+
     /// Request that a specific client event is notified.
     #[tracing::instrument(
         name = "SimConnect::subscribe_to_client_event",
@@ -63,30 +96,18 @@ impl SimConnect {
     ) -> Result<(), SimConnectError> {
         self.client_event_register.register(event)?;
 
-        success!(unsafe {
-            bindings::SimConnect_MapClientEventToSimEvent(
-                self.handle.as_ptr(),
-                event as u32,
-                event.into_c_char(),
-            )
-        })?;
-
-        success!(unsafe {
-            bindings::SimConnect_AddClientEventToNotificationGroup(
-                self.handle.as_ptr(),
-                NOTIFICATION_GROUP_ID,
-                event as u32,
-                0,
-            )
-        })?;
+        self.map_client_event(event)?;
 
-        success!(unsafe {
-            bindings::SimConnect_SetNotificationGroupPriority(
-                self.handle.as_ptr(),
-                NOTIFICATION_GROUP_ID,
-                bindings::SIMCONNECT_GROUP_PRIORITY_HIGHEST,
-            )
-        })?;
+        self.backend.add_client_event_to_notification_group(
+            NOTIFICATION_GROUP_ID,
+            event as u32,
+            false,
+        )?;
+
+        self.backend.set_notification_group_priority(
+            NOTIFICATION_GROUP_ID,
+            bindings::SIMCONNECT_GROUP_PRIORITY_HIGHEST,
+        )?;
 
         Ok(())
     }
@@ -103,13 +124,8 @@ impl SimConnect {
         event: ClientEventRequest,
     ) -> Result<(), SimConnectError> {
         if self.client_event_register.is_registered(event) {
-            success!(unsafe {
-                bindings::SimConnect_RemoveClientEvent(
-                    self.handle.as_ptr(),
-                    NOTIFICATION_GROUP_ID,
-                    event as u32,
-                )
-            })?;
+            self.backend
+                .remove_client_event(NOTIFICATION_GROUP_ID, event as u32)?;
 
             self.client_event_register.unregister(event)?;
         }
@@ -124,11 +140,425 @@ impl SimConnect {
         skip(self)
     )]
     pub fn unsubscribe_from_all_client_events(&mut self) -> Result<(), SimConnectError> {
-        success!(unsafe {
-            bindings::SimConnect_ClearNotificationGroup(self.handle.as_ptr(), NOTIFICATION_GROUP_ID)
-        })?;
+        self.backend
+            .clear_notification_group(NOTIFICATION_GROUP_ID)?;
 
         self.client_event_register.clear();
+        self.custom_client_event_register.clear();
+
+        Ok(())
+    }
+
+    /// Transmit a client event to the simulator, as if it was triggered by the user.
+    ///
+    /// # Arguments
+    /// * `object_id` - [`crate::ObjectId`]. The simulation object the event is transmitted to, usually [`crate::ObjectId::USER`].
+    /// * `event` - [`crate::ClientEvent`]. The value of the event, if any, is transmitted as its data.
+    ///
+    /// # Remarks
+    /// The event doesn't have to be subscribed to. It is mapped to its sim event the first time it is transmitted.
+    #[tracing::instrument(
+        name = "SimConnect::transmit_client_event",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn transmit_client_event(
+        &mut self,
+        object_id: ObjectId,
+        event: ClientEvent,
+    ) -> Result<(), SimConnectError> {
+        let (request, data): (ClientEventRequest, i32) = event.into();
+
+        self.map_client_event(request)?;
+
+        self.backend.transmit_client_event(
+            object_id.into(),
+            request as u32,
+            data as u32,
+            bindings::SIMCONNECT_GROUP_PRIORITY_HIGHEST,
+            bindings::SIMCONNECT_EVENT_FLAG_GROUPID_IS_PRIORITY,
+        )
+    }
+
+    /// Transmit a client event to the simulator along with up to five data parameters, e.g. a value and an engine index.
+    ///
+    /// # Arguments
+    /// * `object_id` - [`crate::ObjectId`]. The simulation object the event is transmitted to, usually [`crate::ObjectId::USER`].
+    /// * `event` - [`crate::ClientEventRequest`]
+    /// * `data` - Up to five data parameters. The missing ones are transmitted as `0`.
+    ///
+    /// # Errors
+    /// - [`crate::SimConnectError::UnexpectedError`] -- More than five data parameters have been given.
+    #[tracing::instrument(
+        name = "SimConnect::transmit_client_event_ex1",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn transmit_client_event_ex1(
+        &mut self,
+        object_id: ObjectId,
+        event: ClientEventRequest,
+        data: &[i32],
+    ) -> Result<(), SimConnectError> {
+        if data.len() > MAX_EVENT_DATA_LEN {
+            return Err(SimConnectError::UnexpectedError(format!(
+                "at most {MAX_EVENT_DATA_LEN} data parameters can be transmitted, got {}",
+                data.len()
+            )));
+        }
+
+        let mut parameters = [0; MAX_EVENT_DATA_LEN];
+        for (parameter, value) in parameters.iter_mut().zip(data) {
+            *parameter = *value as u32;
+        }
+
+        self.map_client_event(event)?;
+
+        self.backend.transmit_client_event_ex1(
+            object_id.into(),
+            event as u32,
+            bindings::SIMCONNECT_GROUP_PRIORITY_HIGHEST,
+            bindings::SIMCONNECT_EVENT_FLAG_GROUPID_IS_PRIORITY,
+            parameters,
+        )
+    }
+
+    /// Map a client event to a sim event by name, e.g. `AP_MASTER` or a custom `Namespace.EventName` event of an add-on aircraft.
+    ///
+    /// Returns the ID the client has allocated for the event. Mapping the same name again returns the same ID.
+    ///
+    /// # Remarks
+    /// Events don't have to be mapped before subscribing to them or transmitting them, it is done automatically.
+    #[tracing::instrument(
+        name = "SimConnect::map_custom_client_event",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn map_custom_client_event(&mut self, name: &str) -> Result<u32, SimConnectError> {
+        if let Some(event_id) = self.custom_client_events.get(name) {
+            return Ok(*event_id);
+        }
+
+        let event_id = CUSTOM_CLIENT_EVENT_ID_START + self.custom_client_events.len() as u32;
+
+        self.backend.map_client_event_to_sim_event(event_id, name)?;
+
+        self.custom_client_events.insert(name.to_string(), event_id);
+
+        Ok(event_id)
+    }
+
+    /// Request that a client event is notified, by name. It is received as [`crate::Notification::CustomClientEvent`].
+    ///
+    /// This allows subscribing to the events that are not part of [`crate::ClientEventRequest`].
+    #[tracing::instrument(
+        name = "SimConnect::subscribe_to_custom_client_event",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn subscribe_to_custom_client_event(&mut self, name: &str) -> Result<(), SimConnectError> {
+        self.custom_client_event_register
+            .register(name.to_string())?;
+
+        let event_id = match self.map_custom_client_event(name) {
+            Ok(event_id) => event_id,
+            Err(e) => {
+                self.custom_client_event_register
+                    .unregister(name.to_string())?;
+                return Err(e);
+            }
+        };
+
+        self.backend.add_client_event_to_notification_group(
+            NOTIFICATION_GROUP_ID,
+            event_id,
+            false,
+        )?;
+
+        self.backend.set_notification_group_priority(
+            NOTIFICATION_GROUP_ID,
+            bindings::SIMCONNECT_GROUP_PRIORITY_HIGHEST,
+        )?;
+
+        Ok(())
+    }
+
+    /// Request that notifications are no longer received for a client event subscribed to by name.
+    /// If the client event is not subscribed to, this function does nothing.
+    #[tracing::instrument(
+        name = "SimConnect::unsubscribe_from_custom_client_event",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn unsubscribe_from_custom_client_event(
+        &mut self,
+        name: &str,
+    ) -> Result<(), SimConnectError> {
+        if self
+            .custom_client_event_register
+            .is_registered(name.to_string())
+        {
+            if let Some(event_id) = self.custom_client_events.get(name) {
+                self.backend
+                    .remove_client_event(NOTIFICATION_GROUP_ID, *event_id)?;
+            }
+
+            self.custom_client_event_register
+                .unregister(name.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    /// Transmit a client event to the simulator by name, as if it was triggered by the user.
+    ///
+    /// # Arguments
+    /// * `object_id` - [`crate::ObjectId`]. The simulation object the event is transmitted to, usually [`crate::ObjectId::USER`].
+    /// * `name` - The name of the event, e.g. `AP_MASTER` or a custom `Namespace.EventName` event of an add-on aircraft.
+    /// * `data` - The data of the event, if any.
+    #[tracing::instrument(
+        name = "SimConnect::transmit_custom_client_event",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn transmit_custom_client_event(
+        &mut self,
+        object_id: ObjectId,
+        name: &str,
+        data: i32,
+    ) -> Result<(), SimConnectError> {
+        let event_id = self.map_custom_client_event(name)?;
+
+        self.backend.transmit_client_event(
+            object_id.into(),
+            event_id,
+            data as u32,
+            bindings::SIMCONNECT_GROUP_PRIORITY_HIGHEST,
+            bindings::SIMCONNECT_EVENT_FLAG_GROUPID_IS_PRIORITY,
+        )
+    }
+
+    /// Add a group of client events that are notified with the same priority, e.g. to intercept maskable events before the simulator processes them.
+    ///
+    /// Returns the ID of the group.
+    ///
+    /// # Remarks
+    /// The events of the group are mapped to their sim events automatically. They don't have to be subscribed to.
+    #[tracing::instrument(
+        name = "SimConnect::add_notification_group",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn add_notification_group(
+        &mut self,
+        group: NotificationGroup,
+    ) -> Result<u32, SimConnectError> {
+        let group_id = self.next_notification_group_id;
+        self.next_notification_group_id += 1;
+
+        for (event, maskable) in group.events {
+            let event_id = self.map_notification_group_event(event)?;
+
+            self.backend
+                .add_client_event_to_notification_group(group_id, event_id, maskable)?;
+        }
+
+        self.backend
+            .set_notification_group_priority(group_id, group.priority.into())?;
+
+        Ok(group_id)
+    }
+
+    /// Change the priority of a notification group added through [`SimConnect::add_notification_group`].
+    #[tracing::instrument(
+        name = "SimConnect::set_notification_group_priority",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn set_notification_group_priority(
+        &mut self,
+        group_id: u32,
+        priority: NotificationGroupPriority,
+    ) -> Result<(), SimConnectError> {
+        self.backend
+            .set_notification_group_priority(group_id, priority.into())
+    }
+
+    /// Request that the events of a notification group added through [`SimConnect::add_notification_group`] are transmitted to the client
+    /// while the simulator is in dialog mode, e.g. while a menu is open.
+    #[tracing::instrument(
+        name = "SimConnect::request_notification_group",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn request_notification_group(&mut self, group_id: u32) -> Result<(), SimConnectError> {
+        self.backend.request_notification_group(group_id)
+    }
+
+    /// Remove all the events of a notification group added through [`SimConnect::add_notification_group`].
+    /// Its events are no longer notified.
+    #[tracing::instrument(
+        name = "SimConnect::remove_notification_group",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn remove_notification_group(&mut self, group_id: u32) -> Result<(), SimConnectError> {
+        self.backend.clear_notification_group(group_id)
+    }
+
+    /// Add a group of input events, i.e. key chords and joystick inputs, mapped to client events. The group is enabled right away.
+    ///
+    /// Returns the ID of the group.
+    ///
+    /// # Remarks
+    /// The client events are mapped to their sim events automatically. They don't have to be subscribed to.
+    #[tracing::instrument(name = "SimConnect::add_input_group", level = "debug", skip(self))]
+    pub fn add_input_group(&mut self, group: InputGroup) -> Result<u32, SimConnectError> {
+        let group_id = self.next_input_group_id;
+        self.next_input_group_id += 1;
+
+        for (input, event, maskable) in group.events {
+            let event_id = self.map_notification_group_event(event)?;
+
+            self.backend.map_input_event_to_client_event(
+                group_id,
+                &input.to_string(),
+                event_id,
+                0,
+                bindings::SIMCONNECT_UNUSED,
+                0,
+                maskable,
+            )?;
+        }
+
+        self.backend
+            .set_input_group_priority(group_id, group.priority.into())?;
+        self.backend.set_input_group_state(
+            group_id,
+            bindings::SIMCONNECT_STATE_SIMCONNECT_STATE_ON as u32,
+        )?;
+
+        Ok(group_id)
+    }
+
+    /// Change the priority of an input group added through [`SimConnect::add_input_group`].
+    #[tracing::instrument(
+        name = "SimConnect::set_input_group_priority",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn set_input_group_priority(
+        &mut self,
+        group_id: u32,
+        priority: NotificationGroupPriority,
+    ) -> Result<(), SimConnectError> {
+        self.backend
+            .set_input_group_priority(group_id, priority.into())
+    }
+
+    /// Enable or disable an input group added through [`SimConnect::add_input_group`].
+    /// The input events of a disabled group are not transmitted, and not masked.
+    #[tracing::instrument(
+        name = "SimConnect::set_input_group_state",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn set_input_group_state(
+        &mut self,
+        group_id: u32,
+        enabled: bool,
+    ) -> Result<(), SimConnectError> {
+        let state = if enabled {
+            bindings::SIMCONNECT_STATE_SIMCONNECT_STATE_ON
+        } else {
+            bindings::SIMCONNECT_STATE_SIMCONNECT_STATE_OFF
+        };
+
+        self.backend.set_input_group_state(group_id, state as u32)
+    }
+
+    /// Remove an input event from an input group added through [`SimConnect::add_input_group`].
+    #[tracing::instrument(name = "SimConnect::remove_input_event", level = "debug", skip(self))]
+    pub fn remove_input_event(
+        &mut self,
+        group_id: u32,
+        input: &InputEvent,
+    ) -> Result<(), SimConnectError> {
+        self.backend
+            .remove_input_event(group_id, &input.to_string())
+    }
+
+    /// Remove all the input events of an input group added through [`SimConnect::add_input_group`].
+    #[tracing::instrument(name = "SimConnect::remove_input_group", level = "debug", skip(self))]
+    pub fn remove_input_group(&mut self, group_id: u32) -> Result<(), SimConnectError> {
+        self.backend.clear_input_group(group_id)
+    }
+
+    /// Request that the simulator reserves one of the given keys for the client, so that it doesn't clash with the key bindings of the simulator or of other add-ons.
+    ///
+    /// The key that has been reserved is received as [`crate::Notification::ReservedKey`]. From then on, the key triggers the client event `name`,
+    /// which is received as [`crate::Notification::CustomClientEvent`]. The event doesn't have to be subscribed to.
+    ///
+    /// Returns the ID of the client event.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the client event the key is mapped to, e.g. `MyAddon.Toggle`.
+    /// * `choices` - One to three key chords, in order of preference.
+    ///
+    /// # Errors
+    /// - [`crate::SimConnectError::UnexpectedError`] -- No key choices, or more than three, have been given.
+    #[tracing::instrument(name = "SimConnect::request_reserved_key", level = "debug", skip(self))]
+    pub fn request_reserved_key(
+        &mut self,
+        name: &str,
+        choices: &[KeyChord],
+    ) -> Result<u32, SimConnectError> {
+        if choices.is_empty() || choices.len() > MAX_RESERVED_KEY_CHOICES {
+            return Err(SimConnectError::UnexpectedError(format!(
+                "between 1 and {MAX_RESERVED_KEY_CHOICES} key choices can be requested, got {}",
+                choices.len()
+            )));
+        }
+
+        let mut key_choices: [String; MAX_RESERVED_KEY_CHOICES] = Default::default();
+        for (key_choice, choice) in key_choices.iter_mut().zip(choices) {
+            *key_choice = choice.to_string();
+        }
+
+        let event_id = self.map_custom_client_event(name)?;
+
+        self.backend.request_reserved_key(
+            event_id,
+            &key_choices[0],
+            &key_choices[1],
+            &key_choices[2],
+        )?;
+
+        Ok(event_id)
+    }
+
+    /// Map the client event of a notification or input group to its sim event, and return its ID.
+    fn map_notification_group_event(
+        &mut self,
+        event: NotificationGroupEvent,
+    ) -> Result<u32, SimConnectError> {
+        match event {
+            NotificationGroupEvent::Client(event) => {
+                self.map_client_event(event)?;
+                Ok(event as u32)
+            }
+            NotificationGroupEvent::Custom(name) => self.map_custom_client_event(&name),
+        }
+    }
+
+    /// Map a client event to its sim event, unless it has been mapped already.
+    fn map_client_event(&mut self, event: ClientEventRequest) -> Result<(), SimConnectError> {
+        if !self.mapped_client_events.contains(&event) {
+            self.backend
+                .map_client_event_to_sim_event(event as u32, event.into_event_name())?;
+
+            self.mapped_client_events.push(event);
+        }
 
         Ok(())
     }
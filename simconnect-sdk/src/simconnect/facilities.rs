@@ -1,4 +1,4 @@
-use crate::{bindings, success, FacilityType, SimConnect, SimConnectError};
+use crate::{FacilityType, SimConnect, SimConnectError};
 
 impl SimConnect {
     /// Request a list of all the facilities of a given type currently held in the facilities cache.
@@ -22,13 +22,8 @@ impl SimConnect {
         let type_name = facility_type.to_type_name();
         let request_id = self.new_request_id(type_name, true)?;
 
-        success!(unsafe {
-            bindings::SimConnect_RequestFacilitiesList(
-                self.handle.as_ptr(),
-                facility_type.into(),
-                request_id,
-            )
-        })
+        self.backend
+            .request_facilities_list(facility_type.into(), request_id)
     }
 
     /// Request notifications when a facility of a certain type is added to the facilities cache.
@@ -56,13 +51,8 @@ impl SimConnect {
         let type_name = facility_type.to_type_name();
         let request_id = self.new_request_id(type_name, false)?;
 
-        success!(unsafe {
-            bindings::SimConnect_SubscribeToFacilities(
-                self.handle.as_ptr(),
-                facility_type.into(),
-                request_id,
-            )
-        })
+        self.backend
+            .subscribe_to_facilities(facility_type.into(), request_id)
     }
 
     /// Request that notifications of additions to the facilities cache are not longer sent.
@@ -80,11 +70,10 @@ impl SimConnect {
     ) -> Result<(), SimConnectError> {
         let type_name = facility_type.to_type_name();
 
-        success!(unsafe {
-            bindings::SimConnect_UnsubscribeToFacilities(self.handle.as_ptr(), facility_type.into())
-        })?;
+        self.backend
+            .unsubscribe_to_facilities(facility_type.into())?;
 
-        self.unregister_request_id_by_type_name(&type_name);
+        self.unregister_request_ids_by_type_name(&type_name);
 
         Ok(())
     }
@@ -1,9 +1,19 @@
+#[cfg(feature = "tokio")]
+mod async_simconnect;
 mod base;
+mod dispatcher;
 mod event_register;
 mod events;
 mod facilities;
 mod objects;
+mod subscription;
+mod system_state;
 
 pub(crate) use event_register::*;
+pub(crate) use subscription::Route;
 
+#[cfg(feature = "tokio")]
+pub use async_simconnect::AsyncSimConnect;
 pub use base::*;
+pub use dispatcher::Dispatcher;
+pub use subscription::Subscription;
@@ -1,43 +1,211 @@
 use crate::{
-    as_c_string, bindings, success, Condition, DataType, Period, SimConnect, SimConnectError,
-    SimConnectObjectExt,
+    bindings, Condition, DataType, ObjectId, Period, SimConnect, SimConnectError,
+    SimConnectObjectExt, SimConnectSettableObjectExt, SimObjectType, Subscription,
 };
 
 impl SimConnect {
-    // Register an object with SimConnect by assigning it an unique interval `request_id` and then calling the [`crate::SimConnectObjectExt::register`] method on the struct.
+    /// Register an object with SimConnect by assigning it an unique interval `request_id`, adding the struct to its data definition through [`crate::SimConnectObjectExt::define`] and then requesting its data.
+    ///
+    /// The returned [`crate::Subscription`] receives the data of the object, decoded as `T`. Dropping it unregisters the object.
+    /// The same struct can be registered any number of times, each registration getting its own `request_id`. See [`crate::Object::request_id`].
     #[tracing::instrument(name = "SimConnect::register_object", level = "debug", skip(self))]
-    pub fn register_object<T: SimConnectObjectExt>(&mut self) -> Result<u32, SimConnectError> {
+    pub fn register_object<T: SimConnectObjectExt + Send + 'static>(
+        &mut self,
+    ) -> Result<Subscription<T>, SimConnectError> {
+        self.register_object_with_request::<T>(None, None)
+    }
+
+    /// Register an object with SimConnect, like [`crate::SimConnect::register_object`] does, but request its data for the simulation object `object_id` instead of the one the struct has been defined with.
+    ///
+    /// The IDs of other aircraft can be obtained through [`crate::Object::object_id`].
+    #[tracing::instrument(name = "SimConnect::register_object_for", level = "debug", skip(self))]
+    pub fn register_object_for<T: SimConnectObjectExt + Send + 'static>(
+        &mut self,
+        object_id: ObjectId,
+    ) -> Result<Subscription<T>, SimConnectError> {
+        self.register_object_with_request::<T>(Some(object_id), None)
+    }
+
+    /// Register an object with SimConnect, like [`crate::SimConnect::register_object`] does, but request its data with a different period, condition and interval than the ones the struct has been defined with.
+    ///
+    /// # Arguments
+    /// * `period` - [`crate::Period`]
+    /// * `condition` - [`crate::Condition`]
+    /// * `interval` - The number of period events that should elapse between transmissions of the data. `0` means the data is transmitted every Period, `1` means that the data is transmitted every other Period, etc.
+    #[tracing::instrument(
+        name = "SimConnect::register_object_with_period",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn register_object_with_period<T: SimConnectObjectExt + Send + 'static>(
+        &mut self,
+        period: Period,
+        condition: Condition,
+        interval: u32,
+    ) -> Result<Subscription<T>, SimConnectError> {
+        self.register_object_with_request::<T>(None, Some((period, condition, interval)))
+    }
+
+    fn register_object_with_request<T: SimConnectObjectExt + Send + 'static>(
+        &mut self,
+        object_id: Option<ObjectId>,
+        request: Option<(Period, Condition, u32)>,
+    ) -> Result<Subscription<T>, SimConnectError> {
+        self.unregister_dropped_subscriptions()?;
+
+        let type_name: String = std::any::type_name::<T>().into();
+
+        let id = self.new_shared_request_id(type_name, false);
+
+        let (subscription, route) = Subscription::new(id, self.dropped_subscriptions.0.clone());
+        if let Some(object) = self.registered_objects.get_mut(&id) {
+            object.route = Some(route);
+        }
+
+        let (period, condition, interval) =
+            request.unwrap_or((T::PERIOD, T::CONDITION, T::INTERVAL));
+
+        let registered = T::define(self, id).and_then(|_| {
+            self.request_data_on_sim_object_for(
+                id,
+                object_id.unwrap_or(T::OBJECT_ID),
+                period,
+                condition,
+                interval,
+            )
+        });
+
+        if let Err(e) = registered {
+            subscription.detach();
+            self.discard_registration(id);
+            return Err(e);
+        }
+
+        Ok(subscription)
+    }
+
+    /// Request the data of all the simulation objects of a type within `radius_meters` of the user aircraft, once.
+    ///
+    /// The data is received as a single [`crate::Notification::ObjectsByType`], after which the request is unregistered.
+    /// The period, condition and interval the struct has been defined with are ignored.
+    ///
+    /// Returns the request ID, which is also the one of the received objects. See [`crate::Object::request_id`].
+    ///
+    /// # Arguments
+    /// * `radius_meters` - The radius around the user aircraft. The maximum is 200,000 meters. `0` only returns the user aircraft.
+    /// * `object_type` - [`crate::SimObjectType`]
+    #[tracing::instrument(name = "SimConnect::request_data_by_type", level = "debug", skip(self))]
+    pub fn request_data_by_type<T: SimConnectObjectExt>(
+        &mut self,
+        radius_meters: u32,
+        object_type: SimObjectType,
+    ) -> Result<u32, SimConnectError> {
         let type_name: String = std::any::type_name::<T>().into();
 
-        let id = self.new_request_id(type_name, false)?;
+        let id = self.new_shared_request_id(type_name, true);
+
+        let registered = T::define(self, id).and_then(|_| {
+            self.backend
+                .request_data_on_sim_object_type(id, id, radius_meters, object_type.into())
+        });
 
-        T::register(self, id)?;
+        if let Err(e) = registered {
+            self.discard_registration(id);
+            return Err(e);
+        }
 
         Ok(id)
     }
 
-    // Unregister an object with SimConnect.
-    #[tracing::instrument(name = "SimConnect::unregister_object", level = "debug", skip(self))]
-    pub fn unregister_object<T: SimConnectObjectExt>(&mut self) -> Result<u32, SimConnectError> {
+    /// Set the data of the simulation object `object_id` to the values of `value`.
+    ///
+    /// The data definition of the struct is registered the first time the struct is set, without requesting its data.
+    /// Only structs that have the `settable` attribute can be set. Read-only simulation variables are ignored by SimConnect.
+    #[tracing::instrument(
+        name = "SimConnect::set_data_on_sim_object",
+        level = "debug",
+        skip(self, value)
+    )]
+    pub fn set_data_on_sim_object<T: SimConnectSettableObjectExt>(
+        &mut self,
+        object_id: ObjectId,
+        value: &T,
+    ) -> Result<(), SimConnectError> {
         let type_name: String = std::any::type_name::<T>().into();
 
-        let request_id = self
+        let define_id = self
             .registered_objects
-            .get(&type_name)
-            .ok_or_else(|| SimConnectError::ObjectNotRegistered(type_name.clone()))?;
+            .iter()
+            .find(|(_, object)| object.set_only && object.type_name == type_name)
+            .map(|(id, _)| *id);
+
+        let define_id = match define_id {
+            Some(define_id) => define_id,
+            None => {
+                let id = self.new_shared_request_id(type_name, false);
 
-        success!(unsafe {
-            bindings::SimConnect_ClearDataDefinition(self.handle.as_ptr(), request_id.id)
-        })?;
+                if let Some(object) = self.registered_objects.get_mut(&id) {
+                    object.set_only = true;
+                }
 
-        self.unregister_request_id_by_type_name(&type_name)
-            .ok_or(SimConnectError::ObjectNotRegistered(type_name))
+                if let Err(e) = T::define(self, id) {
+                    self.discard_registration(id);
+                    return Err(e);
+                }
+
+                id
+            }
+        };
+
+        let data = value.to_data();
+
+        self.backend.set_data_on_sim_object(
+            define_id,
+            object_id.into(),
+            0,
+            1,
+            data.len() as u32,
+            &data,
+        )
+    }
+
+    /// Remove a registration that failed partway, along with the fields that have already been added to its data definition.
+    fn discard_registration(&mut self, id: u32) {
+        self.registered_objects.remove(&id);
+
+        // the error of the registration is the one worth reporting
+        let _ = self.backend.clear_data_definition(id);
+    }
+
+    /// Unregister all the registrations of an object with SimConnect.
+    ///
+    /// Returns the request IDs of the registrations.
+    ///
+    /// # Errors
+    /// - [`crate::SimConnectError::ObjectNotRegistered`] -- The object is not registered.
+    #[tracing::instrument(name = "SimConnect::unregister_object", level = "debug", skip(self))]
+    pub fn unregister_object<T: SimConnectObjectExt>(
+        &mut self,
+    ) -> Result<Vec<u32>, SimConnectError> {
+        let type_name: String = std::any::type_name::<T>().into();
+
+        let request_ids = self.unregister_request_ids_by_type_name(&type_name);
+
+        if request_ids.is_empty() {
+            return Err(SimConnectError::ObjectNotRegistered(type_name));
+        }
+
+        for request_id in &request_ids {
+            self.backend.clear_data_definition(*request_id)?;
+        }
+
+        Ok(request_ids)
     }
 
     /// Add a Microsoft Flight Simulator simulation variable name to a client defined object definition.
     ///
     /// # Remarks
-    /// The [`crate::SimConnectObject`] macro will automatically call this method for the struct.
+    /// The data is sent again with [`crate::Condition::Changed`] on any change of the value. See [`SimConnect::add_to_data_definition_with_epsilon`].
     #[tracing::instrument(
         name = "SimConnect::add_to_data_definition",
         level = "debug",
@@ -49,31 +217,55 @@ impl SimConnect {
         name: &str,
         unit: &str,
         data_type: DataType,
+    ) -> Result<(), SimConnectError> {
+        self.add_to_data_definition_with_epsilon(request_id, name, unit, data_type, 0.0)
+    }
+
+    /// Add a Microsoft Flight Simulator simulation variable name to a client defined object definition, along with the amount its value has to change by before the data is sent again with [`crate::Condition::Changed`].
+    ///
+    /// # Remarks
+    /// The [`crate::SimConnectObject`] macro will automatically call this method for the struct, with the `epsilon` attribute of the field.
+    #[tracing::instrument(
+        name = "SimConnect::add_to_data_definition_with_epsilon",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn add_to_data_definition_with_epsilon(
+        &self,
+        request_id: u32,
+        name: &str,
+        unit: &str,
+        data_type: DataType,
+        epsilon: f32,
     ) -> Result<(), SimConnectError> {
         let c_type = match data_type {
             DataType::Float64 => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64,
-            DataType::Bool => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT32,
+            DataType::Float32 => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT32,
+            DataType::Int64 => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT64,
+            DataType::Int32 | DataType::Bool => {
+                bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT32
+            }
             DataType::String => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING256,
+            DataType::String8 => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING8,
+            DataType::String32 => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING32,
+            DataType::String64 => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING64,
+            DataType::String128 => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING128,
+            DataType::String260 => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING260,
+            DataType::StringV => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRINGV,
+            DataType::LatLonAlt => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_LATLONALT,
+            DataType::XYZ => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_XYZ,
+            DataType::InitPosition => {
+                bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INITPOSITION
+            }
+            DataType::MarkerState => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_MARKERSTATE,
+            DataType::Waypoint => bindings::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_WAYPOINT,
         };
 
-        success!(unsafe {
-            bindings::SimConnect_AddToDataDefinition(
-                self.handle.as_ptr(),
-                request_id,
-                as_c_string!(name),
-                as_c_string!(unit),
-                c_type,
-                0.0,
-                u32::MAX,
-            )
-        })
+        self.backend
+            .add_to_data_definition(request_id, name, unit, c_type, epsilon, u32::MAX)
     }
 
-    /// Request when the SimConnect client is to receive data values for a specific object.
-    ///
-    /// # Current limitation
-    /// All objects are requested from the local user's aircraft POV.
-    /// This comes with the side-effect that currently there is no way to request data for other aircraft in multiplayer.
+    /// Request when the SimConnect client is to receive data values for the user aircraft.
     ///
     /// # Arguments
     /// * `request_id` - The request ID of the object.
@@ -82,7 +274,7 @@ impl SimConnect {
     /// * `interval` - The number of period events that should elapse between transmissions of the data. `0` means the data is transmitted every Period, `1` means that the data is transmitted every other Period, etc.
     ///
     /// # Remarks
-    /// The [`crate::SimConnectObject`] macro will automatically call this method for the struct.
+    /// See [`crate::SimConnect::request_data_on_sim_object_for`].
     #[tracing::instrument(
         name = "SimConnect::request_data_on_sim_object",
         level = "debug",
@@ -95,18 +287,42 @@ impl SimConnect {
         condition: Condition,
         interval: u32,
     ) -> Result<(), SimConnectError> {
-        success!(unsafe {
-            bindings::SimConnect_RequestDataOnSimObject(
-                self.handle.as_ptr(),
-                request_id,
-                request_id,
-                bindings::SIMCONNECT_OBJECT_ID_USER,
-                period.into(),
-                condition.into(),
-                0,
-                interval,
-                0,
-            )
-        })
+        self.request_data_on_sim_object_for(request_id, ObjectId::USER, period, condition, interval)
+    }
+
+    /// Request when the SimConnect client is to receive data values for a specific simulation object.
+    ///
+    /// # Arguments
+    /// * `request_id` - The request ID of the object.
+    /// * `object_id` - [`crate::ObjectId`]
+    /// * `period` - [`crate::Period`]
+    /// * `condition` - [`crate::Condition`]
+    /// * `interval` - The number of period events that should elapse between transmissions of the data. `0` means the data is transmitted every Period, `1` means that the data is transmitted every other Period, etc.
+    ///
+    /// # Remarks
+    /// [`crate::SimConnectObjectExt::request`] calls this method with the `object_id`, `period`, `condition` and `interval` of the struct.
+    #[tracing::instrument(
+        name = "SimConnect::request_data_on_sim_object_for",
+        level = "debug",
+        skip(self)
+    )]
+    pub fn request_data_on_sim_object_for(
+        &self,
+        request_id: u32,
+        object_id: ObjectId,
+        period: Period,
+        condition: Condition,
+        interval: u32,
+    ) -> Result<(), SimConnectError> {
+        self.backend.request_data_on_sim_object(
+            request_id,
+            request_id,
+            object_id.into(),
+            period.into(),
+            condition.into(),
+            0,
+            interval,
+            0,
+        )
     }
 }
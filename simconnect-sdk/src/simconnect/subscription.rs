@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{Object, SimConnect, SimConnectError, SimConnectObjectExt};
+
+// The number of values a subscription buffers before the oldest ones are discarded.
+const SUBSCRIPTION_CAPACITY: usize = 128;
+
+/// Routes the data received for a registered object to its [`Subscription`].
+pub(crate) type Route = Box<dyn Fn(&Object) + Send>;
+
+type Callback<T> = Box<dyn FnMut(T) + Send>;
+
+/// A typed handle to an object registered through [`SimConnect::register_object`].
+///
+/// Every time SimConnect sends data for the object, the data is decoded into a `T` and handed to the subscription,
+/// which buffers it until it is received through [`Subscription::try_recv`], [`Subscription::latest`] or [`Subscription::try_iter`],
+/// or passes it to the callback set through [`Subscription::on_value`].
+/// Up to 128 values are buffered, after which the oldest ones are discarded.
+/// The data is still received as [`crate::Notification::Object`] too, so the values are only routed while [`SimConnect::get_next_dispatch`] is being called.
+///
+/// Dropping the subscription unregisters the object, the next time the client receives or registers anything.
+/// Use [`Subscription::detach`] to keep the object registered until [`SimConnect::unregister_object`] is called instead.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use simconnect_sdk::{SimConnect, SimConnectObject};
+///
+/// #[derive(Debug, Clone, SimConnectObject)]
+/// #[simconnect(period = "second")]
+/// #[allow(dead_code)]
+/// struct AirplaneData {
+///     #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+///     alt: f64,
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut client = SimConnect::new("Subscription example")?;
+///     let subscription = client.register_object::<AirplaneData>()?;
+///
+///     loop {
+///         client.get_next_dispatch()?;
+///
+///         for airplane_data in subscription.try_iter() {
+///             println!("{airplane_data:?}");
+///         }
+///
+///         std::thread::sleep(std::time::Duration::from_millis(16));
+///     }
+/// }
+/// ```
+#[must_use = "dropping a subscription unregisters the object"]
+pub struct Subscription<T> {
+    request_id: u32,
+    shared: Arc<Mutex<Shared<T>>>,
+    unregister: Option<Sender<u32>>,
+}
+
+struct Shared<T> {
+    values: VecDeque<T>,
+    callback: Option<Callback<T>>,
+}
+
+impl<T: SimConnectObjectExt + Send + 'static> Subscription<T> {
+    /// Create a subscription and the route that feeds it.
+    pub(crate) fn new(request_id: u32, unregister: Sender<u32>) -> (Self, Route) {
+        let shared = Arc::new(Mutex::new(Shared {
+            values: VecDeque::new(),
+            callback: None,
+        }));
+
+        let route_shared = Arc::clone(&shared);
+        let route: Route = Box::new(move |object| match T::try_from(object) {
+            Ok(value) => push(&route_shared, value),
+            Err(_) => tracing::warn!(
+                "Failed to decode the data of request {request_id} as `{}`",
+                std::any::type_name::<T>()
+            ),
+        });
+
+        let subscription = Self {
+            request_id,
+            shared,
+            unregister: Some(unregister),
+        };
+
+        (subscription, route)
+    }
+}
+
+impl<T> Subscription<T> {
+    /// The request ID the object has been registered with.
+    pub fn request_id(&self) -> u32 {
+        self.request_id
+    }
+
+    /// Receive the oldest buffered value, if any.
+    pub fn try_recv(&self) -> Option<T> {
+        lock(&self.shared).values.pop_front()
+    }
+
+    /// Receive the newest buffered value, if any. The older ones are discarded.
+    pub fn latest(&self) -> Option<T> {
+        let mut shared = lock(&self.shared);
+        let latest = shared.values.pop_back();
+        shared.values.clear();
+
+        latest
+    }
+
+    /// Receive all the buffered values, oldest first.
+    pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(move || self.try_recv())
+    }
+
+    /// Pass every value to `callback` as soon as it is received, instead of buffering it. The values that are already buffered are passed right away.
+    ///
+    /// # Remarks
+    /// The callback is called from within [`SimConnect::get_next_dispatch`].
+    pub fn on_value(&self, callback: impl FnMut(T) + Send + 'static) {
+        let mut callback: Callback<T> = Box::new(callback);
+
+        let buffered = std::mem::take(&mut lock(&self.shared).values);
+        for value in buffered {
+            callback(value);
+        }
+
+        lock(&self.shared).callback = Some(callback);
+    }
+
+    /// Unregister the object right away, without waiting for the client to receive or register anything.
+    pub fn unregister(mut self, client: &mut SimConnect) -> Result<(), SimConnectError> {
+        self.unregister = None;
+
+        client.unregister_request_id(self.request_id)
+    }
+
+    /// Drop the handle but keep the object registered. It can still be unregistered through [`SimConnect::unregister_object`].
+    ///
+    /// Returns the request ID the object has been registered with.
+    pub fn detach(mut self) -> u32 {
+        self.unregister = None;
+
+        self.request_id
+    }
+}
+
+impl<T> std::fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("type_name", &std::any::type_name::<T>())
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        if let Some(unregister) = self.unregister.take() {
+            // The client might be gone already, in which case there is nothing left to unregister.
+            let _ = unregister.send(self.request_id);
+        }
+    }
+}
+
+fn push<T>(shared: &Mutex<Shared<T>>, value: T) {
+    let mut guard = lock(shared);
+
+    match guard.callback.take() {
+        Some(mut callback) => {
+            // The lock is released while the callback runs, so that it can use the subscription.
+            drop(guard);
+            callback(value);
+
+            let mut guard = lock(shared);
+            if guard.callback.is_none() {
+                guard.callback = Some(callback);
+            }
+        }
+        None => {
+            if guard.values.len() == SUBSCRIPTION_CAPACITY {
+                guard.values.pop_front();
+            }
+            guard.values.push_back(value);
+        }
+    }
+}
+
+fn lock<T>(shared: &Mutex<Shared<T>>) -> MutexGuard<'_, Shared<T>> {
+    // A panic in a callback doesn't leave the buffer in an inconsistent state.
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
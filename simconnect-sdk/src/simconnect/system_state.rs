@@ -0,0 +1,43 @@
+use crate::{SimConnect, SimConnectError, SystemState, SystemStateRequest};
+
+impl SimConnect {
+    /// Request information from a number of SimConnect system states.
+    ///
+    /// The state is received as [`crate::Notification::SystemState`], typed according to `request`.
+    ///
+    /// # Returns
+    /// The Request ID of the system state request.
+    #[tracing::instrument(name = "SimConnect::request_system_state", level = "debug", skip(self))]
+    pub fn request_system_state(
+        &mut self,
+        request: SystemStateRequest,
+    ) -> Result<u32, SimConnectError> {
+        let type_name: String = std::any::type_name::<SystemState>().into();
+        let request_id = self.new_shared_request_id(type_name, true);
+
+        if let Err(e) = self
+            .backend
+            .request_system_state(request_id, request.into_state_name())
+        {
+            self.registered_objects.remove(&request_id);
+            return Err(e);
+        }
+
+        self.system_state_requests.insert(request_id, request);
+
+        Ok(request_id)
+    }
+
+    /// Set one of a number of SimConnect system states.
+    ///
+    /// # Remarks
+    /// The simulator might not honor all of the system states, e.g. loading a different aircraft by setting [`SystemState::AircraftLoaded`].
+    #[tracing::instrument(name = "SimConnect::set_system_state", level = "debug", skip(self))]
+    pub fn set_system_state(&mut self, state: &SystemState) -> Result<(), SimConnectError> {
+        let request = SystemStateRequest::from(state);
+        let (integer, float, string) = state.values();
+
+        self.backend
+            .set_system_state(request.into_state_name(), integer, float, string)
+    }
+}
@@ -1,6 +1,39 @@
-use crate::{Object, SimConnect, SimConnectError};
+use crate::{Condition, Object, ObjectId, Period, SimConnect, SimConnectError};
 
 /// Trait to be implemented by objects that can be registered with SimConnect.
 pub trait SimConnectObjectExt: Clone + for<'a> TryFrom<&'a Object> {
-    fn register(client: &mut SimConnect, id: u32) -> Result<(), SimConnectError>;
+    /// The simulation object whose data is requested.
+    const OBJECT_ID: ObjectId = ObjectId::USER;
+    /// The period the data is requested with.
+    const PERIOD: Period = Period::Second;
+    /// The condition the data is requested with.
+    const CONDITION: Condition = Condition::None;
+    /// The number of period events that should elapse between transmissions of the data.
+    const INTERVAL: u32 = 0;
+
+    /// Add the fields of the struct to the data definition `id`, without requesting its data.
+    fn define(client: &mut SimConnect, id: u32) -> Result<(), SimConnectError>;
+
+    /// Request the data of the data definition `id` with the simulation object, period, condition and interval of the struct.
+    fn request(client: &mut SimConnect, id: u32) -> Result<(), SimConnectError> {
+        client.request_data_on_sim_object_for(
+            id,
+            Self::OBJECT_ID,
+            Self::PERIOD,
+            Self::CONDITION,
+            Self::INTERVAL,
+        )
+    }
+
+    /// Add the fields of the struct to the data definition `id` and request its data.
+    fn register(client: &mut SimConnect, id: u32) -> Result<(), SimConnectError> {
+        Self::define(client, id)?;
+        Self::request(client, id)
+    }
+}
+
+/// Trait to be implemented by objects whose data can be set on simulation objects. See [`crate::SimConnect::set_data_on_sim_object`].
+pub trait SimConnectSettableObjectExt: SimConnectObjectExt {
+    /// The packed data of the object, in the order its fields have been added to the data definition.
+    fn to_data(&self) -> Vec<u8>;
 }
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use simconnect_sdk::{
+    AsyncSimConnect, MockSimulator, Notification, SimConnectError, SimConnectObject, SystemEvent,
+    SystemEventRequest,
+};
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+struct AirplaneData {
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+    alt: f64,
+}
+
+#[tokio::test]
+async fn notifications_are_streamed() {
+    let simulator = MockSimulator::new();
+    let mut client = AsyncSimConnect::with_backend(simulator.clone());
+
+    simulator.push_open();
+    assert!(matches!(client.next().await, Some(Ok(Notification::Open))));
+
+    let request_id = client.register_object::<AirplaneData>().unwrap().detach();
+
+    // the notifications pushed while the stream is waiting wake it up
+    let pusher = simulator.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        pusher.push_sim_object_data(request_id, &1500.0f64.to_le_bytes());
+        pusher.push_system_event(SystemEventRequest::Pause, 1);
+    });
+
+    let notification = tokio::time::timeout(Duration::from_secs(5), client.next())
+        .await
+        .unwrap();
+    match notification {
+        Some(Ok(Notification::Object(data))) => {
+            let data = AirplaneData::try_from(&data).unwrap();
+            assert_eq!(data.alt, 1500.0);
+        }
+        notification => panic!("unexpected notification {notification:?}"),
+    }
+
+    let notification = tokio::time::timeout(Duration::from_secs(5), client.next())
+        .await
+        .unwrap();
+    assert!(matches!(
+        notification,
+        Some(Ok(Notification::SystemEvent(SystemEvent::Pause {
+            state: true
+        })))
+    ));
+}
+
+#[tokio::test]
+async fn data_of_unregistered_objects_is_skipped() {
+    let simulator = MockSimulator::new();
+    let mut client = AsyncSimConnect::with_backend(simulator.clone());
+
+    simulator.push_sim_object_data(42, &1500.0f64.to_le_bytes());
+    simulator.push_open();
+
+    assert!(matches!(client.next().await, Some(Ok(Notification::Open))));
+}
+
+#[tokio::test]
+async fn stream_ends_after_quit() {
+    let simulator = MockSimulator::new();
+    let mut client = AsyncSimConnect::with_backend(simulator.clone());
+
+    simulator.push_open();
+    simulator.push_quit();
+    simulator.push_open();
+
+    assert!(matches!(client.next().await, Some(Ok(Notification::Open))));
+    assert!(matches!(client.next().await, Some(Ok(Notification::Quit))));
+    assert!(client.next().await.is_none());
+}
+
+#[tokio::test]
+async fn dropping_a_waiting_stream_closes_the_connection() {
+    let simulator = MockSimulator::new();
+    let mut client = AsyncSimConnect::with_backend(simulator.clone());
+
+    let pending = tokio::time::timeout(Duration::from_millis(50), client.next()).await;
+    assert!(pending.is_err());
+
+    drop(client);
+
+    assert!(simulator.is_closed());
+}
+
+#[tokio::test]
+async fn stream_ends_after_disconnection() {
+    let simulator = MockSimulator::new();
+    let mut client = AsyncSimConnect::with_backend(simulator.clone());
+
+    simulator.push_open();
+    assert!(matches!(client.next().await, Some(Ok(Notification::Open))));
+
+    // the connection is lost while the stream is waiting, without a quit
+    let disconnector = simulator.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        disconnector.disconnect();
+    });
+
+    let notification = tokio::time::timeout(Duration::from_secs(5), client.next())
+        .await
+        .unwrap();
+    assert!(matches!(
+        notification,
+        Some(Err(SimConnectError::Disconnected))
+    ));
+    assert!(client.next().await.is_none());
+}
@@ -0,0 +1,1899 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use simconnect_sdk::{
+    AiWaypoint, Airport, ClientEvent, ClientEventData, ClientEventRequest, Condition, DataType,
+    Dispatcher, FacilityType, InitPosition, InitPositionAirspeed, InputEvent, InputGroup,
+    JoystickAxis, Key, KeyChord, LatLonAlt, MarkerState, MockCall, MockSimulator, Notification,
+    NotificationGroup, NotificationGroupPriority, Object, ObjectId, Period, ReservedKey,
+    SimConnect, SimConnectError, SimConnectObject, SimConnectObjectExt,
+    SimConnectSettableObjectExt, SimObjectType, SystemEvent, SystemEventRequest, SystemState,
+    SystemStateRequest, WeatherMode, XYZ,
+};
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", condition = "changed")]
+struct AirplaneData {
+    #[simconnect(name = "TITLE")]
+    title: String,
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+    alt: f64,
+}
+
+fn airplane_data_bytes(title: &str, alt: f64) -> Vec<u8> {
+    let mut data = vec![0; 256];
+    data[..title.len()].copy_from_slice(title.as_bytes());
+    data.extend_from_slice(&alt.to_le_bytes());
+    data
+}
+
+fn new_client() -> (MockSimulator, SimConnect) {
+    let simulator = MockSimulator::new();
+    let client = SimConnect::with_backend(simulator.clone());
+
+    (simulator, client)
+}
+
+#[test]
+fn open_and_quit() {
+    let (simulator, mut client) = new_client();
+
+    simulator.push_open();
+    simulator.push_quit();
+
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::Open))
+    ));
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::Quit))
+    ));
+    assert!(matches!(client.get_next_dispatch(), Ok(None)));
+}
+
+#[test]
+fn register_object() {
+    let (simulator, mut client) = new_client();
+
+    let request_id = client.register_object::<AirplaneData>().unwrap().detach();
+
+    let definition = simulator.data_definition(request_id).unwrap();
+    assert_eq!(definition.len(), 2);
+    assert_eq!(definition[0].name, "TITLE");
+    assert_eq!(definition[1].name, "PLANE ALTITUDE");
+    assert_eq!(definition[1].unit, "feet");
+
+    let request = simulator
+        .calls()
+        .into_iter()
+        .find(|call| matches!(call, MockCall::RequestDataOnSimObject { .. }));
+    assert!(matches!(
+        request,
+        Some(MockCall::RequestDataOnSimObject { request_id: id, define_id, interval: 0, .. })
+            if id == request_id && define_id == request_id
+    ));
+
+    client.unregister_object::<AirplaneData>().unwrap();
+
+    assert_eq!(simulator.data_definition(request_id), None);
+    assert_eq!(
+        simulator.calls().last(),
+        Some(&MockCall::ClearDataDefinition {
+            define_id: request_id
+        })
+    );
+}
+
+#[test]
+fn object_data() {
+    let (simulator, mut client) = new_client();
+
+    let subscription = client.register_object::<AirplaneData>().unwrap();
+    simulator.push_sim_object_data(
+        subscription.request_id(),
+        &airplane_data_bytes("Cessna 172", 1500.5),
+    );
+
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => {
+            let data = AirplaneData::try_from(&object).unwrap();
+
+            assert_eq!(data.title, "Cessna 172");
+            assert_eq!(data.alt, 1500.5);
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct EngineData {
+    #[simconnect(name = "ENG COMBUSTION:1", unit = "bool")]
+    combustion: bool,
+    #[simconnect(name = "NUMBER OF ENGINES", unit = "number")]
+    engines: i32,
+    #[simconnect(name = "TRANSPONDER CODE:1", unit = "Bco16")]
+    transponder: u32,
+    #[simconnect(name = "ENG RPM ANIMATION PERCENT:1", unit = "percent", epsilon = 0.5)]
+    rpm: f32,
+    #[simconnect(name = "ZULU TIME", unit = "seconds", epsilon = 60)]
+    zulu_time: i64,
+}
+
+#[test]
+fn object_data_types() {
+    let (simulator, mut client) = new_client();
+
+    let request_id = client.register_object::<EngineData>().unwrap().detach();
+
+    let definition = simulator.data_definition(request_id).unwrap();
+    let datum_types = definition
+        .iter()
+        .map(|datum| datum.datum_type)
+        .collect::<Vec<_>>();
+    // INT32, INT32, INT32, FLOAT32 and INT64
+    assert_eq!(datum_types, [1, 1, 1, 3, 2]);
+    let epsilons = definition
+        .iter()
+        .map(|datum| datum.epsilon)
+        .collect::<Vec<_>>();
+    assert_eq!(epsilons, [0.0, 0.0, 0.0, 0.5, 60.0]);
+
+    // booleans are sent as INT32
+    let mut data = Vec::new();
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend_from_slice(&0x7000u32.to_le_bytes());
+    data.extend_from_slice(&87.5f32.to_le_bytes());
+    data.extend_from_slice(&43_200i64.to_le_bytes());
+    simulator.push_sim_object_data(request_id, &data);
+
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => {
+            let engine_data = EngineData::try_from(&object).unwrap();
+
+            assert!(engine_data.combustion);
+            assert_eq!(engine_data.engines, 2);
+            assert_eq!(engine_data.transponder, 0x7000);
+            assert_eq!(engine_data.rpm, 87.5);
+            assert_eq!(engine_data.zulu_time, 43_200);
+
+            client
+                .set_data_on_sim_object(ObjectId::USER, &engine_data)
+                .unwrap();
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+
+    match simulator.calls().last() {
+        Some(MockCall::SetDataOnSimObject { data: set_data, .. }) => assert_eq!(*set_data, data),
+        other => panic!("unexpected call: {other:?}"),
+    }
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct FlightData {
+    #[simconnect(name = "TITLE", variable)]
+    title: String,
+    #[simconnect(name = "ATC ID", string_len = 32)]
+    atc_id: String,
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+    alt: f64,
+}
+
+#[test]
+fn object_data_strings() {
+    let (simulator, mut client) = new_client();
+
+    let request_id = client.register_object::<FlightData>().unwrap().detach();
+
+    let definition = simulator.data_definition(request_id).unwrap();
+    let datum_types = definition
+        .iter()
+        .map(|datum| datum.datum_type)
+        .collect::<Vec<_>>();
+    // STRINGV, STRING32 and FLOAT64
+    assert_eq!(datum_types, [11, 6, 4]);
+
+    // the variable-length string takes as many bytes as it needs
+    let mut data = b"Cessna Skyhawk\0".to_vec();
+    let mut atc_id = [0; 32];
+    atc_id[..6].copy_from_slice(b"N172SP");
+    data.extend_from_slice(&atc_id);
+    data.extend_from_slice(&1500.5f64.to_le_bytes());
+    simulator.push_sim_object_data(request_id, &data);
+
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => {
+            let flight_data = FlightData::try_from(&object).unwrap();
+
+            assert_eq!(flight_data.title, "Cessna Skyhawk");
+            assert_eq!(flight_data.atc_id, "N172SP");
+            assert_eq!(flight_data.alt, 1500.5);
+
+            client
+                .set_data_on_sim_object(ObjectId::USER, &flight_data)
+                .unwrap();
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+
+    match simulator.calls().last() {
+        Some(MockCall::SetDataOnSimObject { data: set_data, .. }) => assert_eq!(*set_data, data),
+        other => panic!("unexpected call: {other:?}"),
+    }
+
+    // the data is cut short in the middle of the fixed size string
+    simulator.push_sim_object_data(request_id, &data[..20]);
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => assert!(matches!(
+            FlightData::try_from(&object),
+            Err(SimConnectError::UnexpectedError(_))
+        )),
+        other => panic!("unexpected notification: {other:?}"),
+    }
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct EnginesData {
+    #[simconnect(name = "GENERAL ENG RPM:{}", unit = "rpm", count = 4)]
+    rpm: [f64; 4],
+    #[simconnect(name = "ENG COMBUSTION:{}", unit = "bool", count = 2)]
+    combustion: [bool; 2],
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct TanksData {
+    #[simconnect(name = "TITLE", variable)]
+    title: String,
+    #[simconnect(name = "FUELSYSTEM TANK QUANTITY:{}", unit = "gallons", count = 2)]
+    quantity: [f64; 2],
+}
+
+#[test]
+fn object_data_arrays() {
+    let (simulator, mut client) = new_client();
+
+    let request_id = client.register_object::<EnginesData>().unwrap().detach();
+
+    let definition = simulator.data_definition(request_id).unwrap();
+    let names = definition
+        .iter()
+        .map(|datum| datum.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "GENERAL ENG RPM:1",
+            "GENERAL ENG RPM:2",
+            "GENERAL ENG RPM:3",
+            "GENERAL ENG RPM:4",
+            "ENG COMBUSTION:1",
+            "ENG COMBUSTION:2",
+        ]
+    );
+
+    let mut data = Vec::new();
+    for rpm in [2400.0f64, 2350.5, 0.0, 0.0] {
+        data.extend_from_slice(&rpm.to_le_bytes());
+    }
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&0i32.to_le_bytes());
+    simulator.push_sim_object_data(request_id, &data);
+
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => {
+            let engines_data = EnginesData::try_from(&object).unwrap();
+
+            assert_eq!(engines_data.rpm, [2400.0, 2350.5, 0.0, 0.0]);
+            assert_eq!(engines_data.combustion, [true, false]);
+
+            client
+                .set_data_on_sim_object(ObjectId::USER, &engines_data)
+                .unwrap();
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+
+    match simulator.calls().last() {
+        Some(MockCall::SetDataOnSimObject { data: set_data, .. }) => assert_eq!(*set_data, data),
+        other => panic!("unexpected call: {other:?}"),
+    }
+
+    // arrays are also supported by the structs that are read one field at a time
+    let request_id = client.register_object::<TanksData>().unwrap().detach();
+
+    let mut data = b"Cessna Skyhawk\0".to_vec();
+    data.extend_from_slice(&26.5f64.to_le_bytes());
+    data.extend_from_slice(&24.0f64.to_le_bytes());
+    simulator.push_sim_object_data(request_id, &data);
+
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => {
+            let tanks_data = TanksData::try_from(&object).unwrap();
+
+            assert_eq!(tanks_data.title, "Cessna Skyhawk");
+            assert_eq!(tanks_data.quantity, [26.5, 24.0]);
+
+            client
+                .set_data_on_sim_object(ObjectId::USER, &tanks_data)
+                .unwrap();
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+
+    match simulator.calls().last() {
+        Some(MockCall::SetDataOnSimObject { data: set_data, .. }) => assert_eq!(*set_data, data),
+        other => panic!("unexpected call: {other:?}"),
+    }
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second", settable)]
+struct PositionData {
+    #[simconnect(name = "STRUCT LATLONALT")]
+    position: LatLonAlt,
+    #[simconnect(name = "STRUCT WORLDVELOCITY")]
+    velocity: XYZ,
+    #[simconnect(name = "Tailhook")]
+    tailhook: MarkerState,
+    #[simconnect(name = "SIM ON GROUND", unit = "bool")]
+    sim_on_ground: bool,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "once", settable)]
+struct RepositionData {
+    #[simconnect(name = "Initial Position")]
+    position: InitPosition,
+}
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "once", settable)]
+struct AiWaypointData {
+    #[simconnect(name = "AI WAYPOINT LIST")]
+    waypoint: AiWaypoint,
+}
+
+#[test]
+fn object_data_structs() {
+    let (simulator, mut client) = new_client();
+
+    let request_id = client.register_object::<PositionData>().unwrap().detach();
+
+    let definition = simulator.data_definition(request_id).unwrap();
+    let datum_types = definition
+        .iter()
+        .map(|datum| datum.datum_type)
+        .collect::<Vec<_>>();
+    // LATLONALT, XYZ, MARKERSTATE and INT32
+    assert_eq!(datum_types, [15, 16, 13, 1]);
+
+    let mut data = Vec::new();
+    for value in [46.785f64, 23.686, 1200.0, 1.5, -0.5, 120.0] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    let mut marker_name = [0; 64];
+    marker_name[..8].copy_from_slice(b"Tailhook");
+    data.extend_from_slice(&marker_name);
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    simulator.push_sim_object_data(request_id, &data);
+
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => {
+            let position_data = PositionData::try_from(&object).unwrap();
+
+            assert_eq!(
+                position_data.position,
+                LatLonAlt {
+                    lat: 46.785,
+                    lon: 23.686,
+                    alt: 1200.0,
+                }
+            );
+            assert_eq!(
+                position_data.velocity,
+                XYZ {
+                    x: 1.5,
+                    y: -0.5,
+                    z: 120.0,
+                }
+            );
+            assert_eq!(
+                position_data.tailhook,
+                MarkerState {
+                    name: "Tailhook".to_string(),
+                    on: true,
+                }
+            );
+            assert!(!position_data.sim_on_ground);
+
+            client
+                .set_data_on_sim_object(ObjectId::USER, &position_data)
+                .unwrap();
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+
+    match simulator.calls().last() {
+        Some(MockCall::SetDataOnSimObject { data: set_data, .. }) => assert_eq!(*set_data, data),
+        other => panic!("unexpected call: {other:?}"),
+    }
+
+    // reposition the user aircraft
+    client
+        .set_data_on_sim_object(
+            ObjectId::USER,
+            &RepositionData {
+                position: InitPosition {
+                    lat: 44.571,
+                    lon: 26.085,
+                    heading: 80.0,
+                    on_ground: true,
+                    airspeed: InitPositionAirspeed::Knots(0),
+                    ..Default::default()
+                },
+            },
+        )
+        .unwrap();
+
+    match simulator.calls().last() {
+        Some(MockCall::SetDataOnSimObject {
+            define_id, data, ..
+        }) => {
+            let definition = simulator.data_definition(*define_id).unwrap();
+            assert_eq!(definition[0].name, "Initial Position");
+            assert_eq!(definition[0].datum_type, 12);
+
+            assert_eq!(data.len(), 6 * 8 + 2 * 4);
+            assert_eq!(data[0..8], 44.571f64.to_le_bytes());
+            assert_eq!(data[40..48], 80.0f64.to_le_bytes());
+            assert_eq!(data[48..], [1, 0, 0, 0, 0, 0, 0, 0]);
+        }
+        other => panic!("unexpected call: {other:?}"),
+    }
+
+    // the waypoint flags are derived from its options
+    client
+        .set_data_on_sim_object(
+            ObjectId(5),
+            &AiWaypointData {
+                waypoint: AiWaypoint {
+                    lat: 44.571,
+                    lon: 26.085,
+                    alt: 3000.0,
+                    speed: Some(120.0),
+                    wrap_to_first: true,
+                    ..Default::default()
+                },
+            },
+        )
+        .unwrap();
+
+    match simulator.calls().last() {
+        Some(MockCall::SetDataOnSimObject {
+            define_id, data, ..
+        }) => {
+            let definition = simulator.data_definition(*define_id).unwrap();
+            assert_eq!(definition[0].datum_type, 14);
+
+            assert_eq!(data.len(), 5 * 8 + 4);
+            assert_eq!(data[24..28], 0x0040_0004u32.to_le_bytes());
+            assert_eq!(data[28..36], 120.0f64.to_le_bytes());
+        }
+        other => panic!("unexpected call: {other:?}"),
+    }
+}
+
+#[test]
+fn subscription() {
+    let (simulator, mut client) = new_client();
+
+    let subscription = client.register_object::<AirplaneData>().unwrap();
+    let request_id = subscription.request_id();
+
+    for alt in [1000.0, 2000.0, 3000.0] {
+        simulator.push_sim_object_data(request_id, &airplane_data_bytes("Cessna 172", alt));
+    }
+    while client.get_next_dispatch().unwrap().is_some() {}
+
+    assert_eq!(subscription.try_recv().unwrap().alt, 1000.0);
+    assert_eq!(subscription.latest().unwrap().alt, 3000.0);
+    assert!(subscription.try_recv().is_none());
+
+    let received = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    subscription.on_value({
+        let received = received.clone();
+        move |data: AirplaneData| received.lock().unwrap().push(data.alt)
+    });
+    simulator.push_sim_object_data(request_id, &airplane_data_bytes("Cessna 172", 4000.0));
+    client.get_next_dispatch().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![4000.0]);
+    assert!(subscription.try_recv().is_none());
+
+    // dropping the subscription unregisters the object the next time the client dispatches
+    drop(subscription);
+    assert!(simulator.data_definition(request_id).is_some());
+
+    client.get_next_dispatch().unwrap();
+
+    assert_eq!(simulator.data_definition(request_id), None);
+    assert!(simulator.calls().contains(&MockCall::ClearDataDefinition {
+        define_id: request_id
+    }));
+
+    // the struct can be registered again
+    let subscription = client.register_object::<AirplaneData>().unwrap();
+    subscription.unregister(&mut client).unwrap();
+    assert!(client.unregister_object::<AirplaneData>().is_err());
+}
+
+#[test]
+fn events() {
+    let (simulator, mut client) = new_client();
+
+    client
+        .subscribe_to_system_event(SystemEventRequest::Pause)
+        .unwrap();
+    client
+        .subscribe_to_client_event(ClientEventRequest::Throttle1Set)
+        .unwrap();
+
+    simulator.push_system_event(SystemEventRequest::Pause, 1);
+    simulator.push_system_event_filename(SystemEventRequest::FlightLoaded, "flight.flt");
+    simulator.push_client_event(ClientEventRequest::Throttle1Set, 8000);
+
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::SystemEvent(SystemEvent::Pause {
+            state: true
+        })))
+    ));
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::SystemEvent(SystemEvent::FlightLoaded { file_name })))
+            if file_name == "flight.flt"
+    ));
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::ClientEvent(ClientEvent::Throttle1Set {
+            value: 8000
+        })))
+    ));
+}
+
+#[test]
+fn system_event_state() {
+    let (simulator, mut client) = new_client();
+
+    assert!(matches!(
+        client.set_system_event_state(SystemEventRequest::Frame, false),
+        Err(SimConnectError::EventNotSubscribedTo(_))
+    ));
+
+    client
+        .subscribe_to_system_event(SystemEventRequest::Frame)
+        .unwrap();
+    assert!(client.is_system_event_on(SystemEventRequest::Frame));
+
+    client
+        .set_system_event_state(SystemEventRequest::Frame, false)
+        .unwrap();
+    assert!(!client.is_system_event_on(SystemEventRequest::Frame));
+
+    client
+        .set_system_event_state(SystemEventRequest::Frame, true)
+        .unwrap();
+    assert!(client.is_system_event_on(SystemEventRequest::Frame));
+
+    let event_id = SystemEventRequest::Frame as u32;
+    assert!(simulator.calls().ends_with(&[
+        MockCall::SetSystemEventState { event_id, state: 0 },
+        MockCall::SetSystemEventState { event_id, state: 1 },
+    ]));
+}
+
+#[test]
+fn unsubscribe_from_one_system_event() {
+    let (_simulator, mut client) = new_client();
+
+    client
+        .subscribe_to_system_event(SystemEventRequest::Frame)
+        .unwrap();
+    client
+        .subscribe_to_system_event(SystemEventRequest::SixTimesPerSecond)
+        .unwrap();
+
+    client
+        .unsubscribe_from_system_event(SystemEventRequest::Frame)
+        .unwrap();
+    assert!(!client.is_system_event_on(SystemEventRequest::Frame));
+    assert!(client.is_system_event_on(SystemEventRequest::SixTimesPerSecond));
+
+    // the other subscription is kept
+    client
+        .set_system_event_state(SystemEventRequest::SixTimesPerSecond, false)
+        .unwrap();
+    assert!(!client.is_system_event_on(SystemEventRequest::SixTimesPerSecond));
+    assert!(matches!(
+        client.set_system_event_state(SystemEventRequest::Frame, true),
+        Err(SimConnectError::EventNotSubscribedTo(_))
+    ));
+}
+
+#[test]
+fn system_events_with_payloads() {
+    let (simulator, mut client) = new_client();
+
+    client
+        .subscribe_to_system_event(SystemEventRequest::ObjectAdded)
+        .unwrap();
+
+    simulator.push_system_event_object(
+        SystemEventRequest::ObjectAdded,
+        ObjectId(42),
+        SimObjectType::Aircraft,
+    );
+    simulator.push_system_event(SystemEventRequest::WeatherModeChanged, 1);
+    simulator.push_system_event(SystemEventRequest::MultiplayerSessionEnded, 0);
+    simulator.push_client_event_ex1(ClientEventRequest::AxisThrottleSet, [1, 2, 3, 4, 5]);
+
+    // `SIMCONNECT_RECV_CUSTOM_ACTION`, followed by its variable-length payload
+    let payload = b"open door\0";
+    let mut message = Vec::new();
+    for value in [0u32, 0, 14, u32::MAX] {
+        message.extend_from_slice(&value.to_le_bytes());
+    }
+    message
+        .extend_from_slice(&(SystemEventRequest::CustomMissionActionExecuted as u32).to_le_bytes());
+    message.extend_from_slice(&0u32.to_le_bytes());
+    message.extend_from_slice(&0x7E7B_B9C2u32.to_le_bytes());
+    message.extend_from_slice(&0xBB01u16.to_le_bytes());
+    message.extend_from_slice(&0x4D6Bu16.to_le_bytes());
+    message.extend_from_slice(&[0x8B, 0x47, 0xF5, 0xD0, 0xD6, 0xDD, 0x3C, 0x5B]);
+    message.extend_from_slice(&1u32.to_le_bytes());
+    message.extend_from_slice(payload);
+    let size = message.len() as u32;
+    message[0..4].copy_from_slice(&size.to_le_bytes());
+    simulator.push_raw(message);
+
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::SystemEvent(SystemEvent::ObjectAdded {
+            object_id: ObjectId(42),
+            object_type: SimObjectType::Aircraft,
+        })))
+    ));
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::SystemEvent(
+            SystemEvent::WeatherModeChanged {
+                mode: WeatherMode::RealWorld
+            }
+        )))
+    ));
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::SystemEvent(
+            SystemEvent::MultiplayerSessionEnded
+        )))
+    ));
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::ClientEventEx1 { name, data }))
+            if name == "AXIS_THROTTLE_SET" && data == [1, 2, 3, 4, 5]
+    ));
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::SystemEvent(SystemEvent::CustomMissionActionExecuted {
+            instance_id,
+            wait_for_completion: true,
+            payload,
+        }))) if instance_id == "{7E7BB9C2-BB01-4D6B-8B47-F5D0D6DD3C5B}" && payload == "open door"
+    ));
+}
+
+#[test]
+fn client_event_catalog() {
+    let (simulator, mut client) = new_client();
+
+    assert_eq!(
+        ClientEventRequest::Throttle1Set.data(),
+        ClientEventData::Range { min: 0, max: 16383 }
+    );
+    assert_eq!(
+        ClientEventRequest::ComRadioSetHz.data(),
+        ClientEventData::Value
+    );
+    assert!(!ClientEventRequest::GearToggle.data().is_used());
+
+    client
+        .subscribe_to_client_event(ClientEventRequest::ComRadioSetHz)
+        .unwrap();
+    client
+        .subscribe_to_client_event(ClientEventRequest::GearToggle)
+        .unwrap();
+
+    simulator.push_client_event(ClientEventRequest::ComRadioSetHz, 122_800_000);
+    simulator.push_client_event(ClientEventRequest::GearToggle, 0);
+
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::ClientEvent(
+            ClientEvent::ComRadioSetHz { value: 122_800_000 }
+        )))
+    ));
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::ClientEvent(ClientEvent::GearToggle)))
+    ));
+
+    let mapped = simulator
+        .calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            MockCall::MapClientEventToSimEvent { event_name, .. } => Some(event_name),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(mapped, vec!["COM_RADIO_SET_HZ", "GEAR_TOGGLE"]);
+}
+
+#[test]
+fn transmit_client_event() {
+    let (simulator, mut client) = new_client();
+
+    client
+        .subscribe_to_client_event(ClientEventRequest::Throttle1Set)
+        .unwrap();
+    client
+        .transmit_client_event(ObjectId::USER, ClientEvent::Throttle1Set { value: -8000 })
+        .unwrap();
+    client
+        .transmit_client_event_ex1(ObjectId(4), ClientEventRequest::ParkingBrakes, &[1, 2])
+        .unwrap();
+    assert!(client
+        .transmit_client_event_ex1(ObjectId::USER, ClientEventRequest::Brakes, &[0; 6])
+        .is_err());
+
+    let calls = simulator.calls();
+
+    // the subscribed event is not mapped again
+    let mapped = calls
+        .iter()
+        .filter_map(|call| match call {
+            MockCall::MapClientEventToSimEvent { event_name, .. } => Some(event_name.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(mapped, vec!["THROTTLE1_SET", "PARKING_BRAKES"]);
+
+    assert!(calls.contains(&MockCall::TransmitClientEvent {
+        object_id: 0,
+        event_id: ClientEventRequest::Throttle1Set as u32,
+        data: -8000i32 as u32,
+        group_id: 1,
+        flags: 16,
+    }));
+    assert!(calls.contains(&MockCall::TransmitClientEventEx1 {
+        object_id: 4,
+        event_id: ClientEventRequest::ParkingBrakes as u32,
+        group_id: 1,
+        flags: 16,
+        data: [1, 2, 0, 0, 0],
+    }));
+}
+
+#[test]
+fn custom_client_events() {
+    let (simulator, mut client) = new_client();
+
+    let event_id = client.map_custom_client_event("AP_MASTER").unwrap();
+    assert_eq!(
+        client.map_custom_client_event("AP_MASTER").unwrap(),
+        event_id
+    );
+
+    client
+        .subscribe_to_custom_client_event("AP_MASTER")
+        .unwrap();
+    client
+        .subscribe_to_custom_client_event("Fenix.A320.Autobrake")
+        .unwrap();
+    assert!(client
+        .subscribe_to_custom_client_event("AP_MASTER")
+        .is_err());
+
+    simulator.push_custom_client_event("Fenix.A320.Autobrake", 3);
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::CustomClientEvent { name, data })) => {
+            assert_eq!(name, "Fenix.A320.Autobrake");
+            assert_eq!(data, 3);
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+
+    client
+        .transmit_custom_client_event(ObjectId::USER, "AP_MASTER", 0)
+        .unwrap();
+    client
+        .unsubscribe_from_custom_client_event("AP_MASTER")
+        .unwrap();
+
+    let calls = simulator.calls();
+
+    let mapped = calls
+        .iter()
+        .filter(|call| matches!(call, MockCall::MapClientEventToSimEvent { .. }))
+        .count();
+    assert_eq!(mapped, 2);
+    assert!(calls.contains(&MockCall::TransmitClientEvent {
+        object_id: 0,
+        event_id,
+        data: 0,
+        group_id: 1,
+        flags: 16,
+    }));
+    assert!(matches!(
+        calls.last(),
+        Some(MockCall::RemoveClientEvent { event_id: id, .. }) if *id == event_id
+    ));
+}
+
+#[test]
+fn notification_groups() {
+    let (simulator, mut client) = new_client();
+
+    let group = NotificationGroup::new(NotificationGroupPriority::HighestMaskable)
+        .with_maskable_event(ClientEventRequest::ParkingBrakes)
+        .with_event(ClientEventRequest::GearToggle)
+        .with_maskable_custom_event("Fenix.A320.Autobrake");
+    let group_id = client.add_notification_group(group).unwrap();
+    let other_group_id = client
+        .add_notification_group(NotificationGroup::new(NotificationGroupPriority::Lowest))
+        .unwrap();
+    assert_ne!(group_id, 0);
+    assert_ne!(group_id, other_group_id);
+
+    simulator.push_client_event(ClientEventRequest::ParkingBrakes, 0);
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::ClientEvent(ClientEvent::ParkingBrakes)))
+    ));
+
+    client
+        .set_notification_group_priority(group_id, NotificationGroupPriority::Custom(5000))
+        .unwrap();
+    client.request_notification_group(group_id).unwrap();
+    client.remove_notification_group(group_id).unwrap();
+
+    let calls = simulator.calls();
+
+    let autobrake_id = client
+        .map_custom_client_event("Fenix.A320.Autobrake")
+        .unwrap();
+    let grouped = calls
+        .iter()
+        .filter_map(|call| match call {
+            MockCall::AddClientEventToNotificationGroup {
+                group_id: id,
+                event_id,
+                maskable,
+            } if *id == group_id => Some((*event_id, *maskable)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        grouped,
+        vec![
+            (ClientEventRequest::ParkingBrakes as u32, true),
+            (ClientEventRequest::GearToggle as u32, false),
+            (autobrake_id, true),
+        ]
+    );
+
+    assert!(calls.contains(&MockCall::SetNotificationGroupPriority {
+        group_id,
+        priority: 10_000_000,
+    }));
+    assert!(calls.contains(&MockCall::SetNotificationGroupPriority {
+        group_id: other_group_id,
+        priority: 4_000_000_000,
+    }));
+    assert!(calls.ends_with(&[
+        MockCall::SetNotificationGroupPriority {
+            group_id,
+            priority: 5000,
+        },
+        MockCall::RequestNotificationGroup { group_id },
+        MockCall::ClearNotificationGroup { group_id },
+    ]));
+}
+
+#[test]
+fn input_event_parsing() {
+    let chord = "ctrl+shift+f12".parse::<KeyChord>().unwrap();
+    assert!(chord.shift && chord.ctrl && !chord.alt);
+    assert_eq!(chord.key, Key::Function(12));
+    assert_eq!(chord.to_string(), "Shift+Ctrl+F12");
+
+    assert_eq!(
+        "Space".parse::<KeyChord>().unwrap(),
+        KeyChord::new(Key::Named("Space".to_string()))
+    );
+    assert_eq!(
+        "joystick:1:button:3".parse::<InputEvent>().unwrap(),
+        InputEvent::JoystickButton {
+            joystick: 1,
+            button: 3
+        }
+    );
+    assert_eq!(
+        "Joystick:0:rzaxis".parse::<InputEvent>().unwrap(),
+        InputEvent::JoystickAxis {
+            joystick: 0,
+            axis: JoystickAxis::Rz
+        }
+    );
+    assert_eq!(
+        "joystick:2:POV".parse::<InputEvent>().unwrap().to_string(),
+        "joystick:2:POV"
+    );
+
+    for invalid in [
+        "",
+        "shift+",
+        "ctrl+ctrl+U",
+        "shift+ctrl",
+        "F25",
+        "U+I",
+        "joystick:x:POV",
+    ] {
+        assert!(
+            matches!(
+                invalid.parse::<InputEvent>(),
+                Err(SimConnectError::InvalidInputEvent(_))
+            ),
+            "{invalid}"
+        );
+    }
+}
+
+#[test]
+fn input_groups() {
+    let (simulator, mut client) = new_client();
+
+    let group = InputGroup::new(NotificationGroupPriority::Highest)
+        .with_maskable_custom_event(
+            "shift+ctrl+U".parse::<KeyChord>().unwrap(),
+            "MyAddon.Toggle",
+        )
+        .with_event(
+            InputEvent::JoystickAxis {
+                joystick: 0,
+                axis: JoystickAxis::Z,
+            },
+            ClientEventRequest::AxisThrottleSet,
+        );
+    let group_id = client.add_input_group(group).unwrap();
+
+    simulator.push_custom_client_event("MyAddon.Toggle", 0);
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::CustomClientEvent { name, .. })) if name == "MyAddon.Toggle"
+    ));
+
+    let toggle_id = client.map_custom_client_event("MyAddon.Toggle").unwrap();
+    let calls = simulator.calls();
+
+    assert!(calls.contains(&MockCall::MapInputEventToClientEvent {
+        group_id,
+        input_definition: "Shift+Ctrl+U".to_string(),
+        down_event_id: toggle_id,
+        down_value: 0,
+        up_event_id: u32::MAX,
+        up_value: 0,
+        maskable: true,
+    }));
+    assert!(calls.contains(&MockCall::MapInputEventToClientEvent {
+        group_id,
+        input_definition: "joystick:0:ZAxis".to_string(),
+        down_event_id: ClientEventRequest::AxisThrottleSet as u32,
+        down_value: 0,
+        up_event_id: u32::MAX,
+        up_value: 0,
+        maskable: false,
+    }));
+    assert!(calls.ends_with(&[
+        MockCall::SetInputGroupPriority {
+            group_id,
+            priority: 1,
+        },
+        MockCall::SetInputGroupState { group_id, state: 1 },
+    ]));
+
+    client
+        .set_input_group_priority(group_id, NotificationGroupPriority::Standard)
+        .unwrap();
+    client.set_input_group_state(group_id, false).unwrap();
+    client
+        .remove_input_event(group_id, &"shift+ctrl+U".parse().unwrap())
+        .unwrap();
+    client.remove_input_group(group_id).unwrap();
+
+    assert!(simulator.calls().ends_with(&[
+        MockCall::SetInputGroupPriority {
+            group_id,
+            priority: 1_900_000_000,
+        },
+        MockCall::SetInputGroupState { group_id, state: 0 },
+        MockCall::RemoveInputEvent {
+            group_id,
+            input_definition: "Shift+Ctrl+U".to_string(),
+        },
+        MockCall::ClearInputGroup { group_id },
+    ]));
+}
+
+#[test]
+fn reserved_key() {
+    let (simulator, mut client) = new_client();
+
+    assert!(matches!(
+        client.request_reserved_key("MyAddon.Menu", &[]),
+        Err(SimConnectError::UnexpectedError(_))
+    ));
+
+    let choices = ["ctrl+shift+M", "alt+M"]
+        .iter()
+        .map(|choice| choice.parse::<KeyChord>().unwrap())
+        .collect::<Vec<_>>();
+    let event_id = client
+        .request_reserved_key("MyAddon.Menu", &choices)
+        .unwrap();
+
+    assert!(simulator.calls().ends_with(&[
+        MockCall::MapClientEventToSimEvent {
+            event_id,
+            event_name: "MyAddon.Menu".to_string(),
+        },
+        MockCall::RequestReservedKey {
+            event_id,
+            key_choices: [
+                "Shift+Ctrl+M".to_string(),
+                "Alt+M".to_string(),
+                String::new(),
+            ],
+        },
+    ]));
+
+    simulator.push_reserved_key("Alt+M", "Tab+M");
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::ReservedKey(reserved_key))) => assert_eq!(
+            reserved_key,
+            ReservedKey {
+                choice: "Alt+M".to_string(),
+                key: "Tab+M".to_string(),
+            }
+        ),
+        other => panic!("unexpected notification {other:?}"),
+    }
+}
+
+#[test]
+fn system_state() {
+    let (simulator, mut client) = new_client();
+
+    let aircraft_request_id = client
+        .request_system_state(SystemStateRequest::AircraftLoaded)
+        .unwrap();
+    let sim_request_id = client
+        .request_system_state(SystemStateRequest::Sim)
+        .unwrap();
+
+    assert_ne!(aircraft_request_id, sim_request_id);
+    assert!(simulator.calls().ends_with(&[
+        MockCall::RequestSystemState {
+            request_id: aircraft_request_id,
+            state: "AircraftLoaded".to_string(),
+        },
+        MockCall::RequestSystemState {
+            request_id: sim_request_id,
+            state: "Sim".to_string(),
+        },
+    ]));
+
+    // the answers are typed by their request, regardless of the order they are received in
+    simulator.push_system_state(SystemStateRequest::Sim, 1, 0.0, "");
+    simulator.push_system_state(
+        SystemStateRequest::AircraftLoaded,
+        0,
+        0.0,
+        "SimObjects\\Airplanes\\Asobo_C172SP_AS1000\\aircraft.cfg",
+    );
+
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::SystemState(SystemState::Sim {
+            state: true
+        })))
+    ));
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::SystemState(SystemState::AircraftLoaded { file_name }))) => {
+            assert_eq!(
+                file_name,
+                "SimObjects\\Airplanes\\Asobo_C172SP_AS1000\\aircraft.cfg"
+            )
+        }
+        other => panic!("unexpected notification {other:?}"),
+    }
+
+    // each request is answered only once
+    simulator.push_system_state(SystemStateRequest::Sim, 0, 0.0, "");
+    assert!(matches!(client.get_next_dispatch(), Ok(None)));
+
+    // a stray answer is skipped without giving up on the messages queued after it
+    simulator.push_system_state(SystemStateRequest::Sim, 0, 0.0, "");
+    simulator.push_quit();
+    assert!(matches!(
+        client.wait_next_dispatch(Duration::ZERO),
+        Ok(Some(Notification::Quit))
+    ));
+
+    client
+        .set_system_state(&SystemState::DialogMode { state: true })
+        .unwrap();
+    client
+        .set_system_state(&SystemState::FlightLoaded {
+            file_name: "flights\\other\\MyFlight.FLT".to_string(),
+        })
+        .unwrap();
+
+    assert!(simulator.calls().ends_with(&[
+        MockCall::SetSystemState {
+            state: "DialogMode".to_string(),
+            integer: 1,
+            float: 0.0,
+            string: String::new(),
+        },
+        MockCall::SetSystemState {
+            state: "FlightLoaded".to_string(),
+            integer: 0,
+            float: 0.0,
+            string: "flights\\other\\MyFlight.FLT".to_string(),
+        },
+    ]));
+}
+
+#[test]
+fn facilities_list() {
+    let (simulator, mut client) = new_client();
+
+    client
+        .request_facilities_list(FacilityType::Airport)
+        .unwrap();
+
+    let request_id = match simulator.calls().last() {
+        Some(MockCall::RequestFacilitiesList { request_id, .. }) => *request_id,
+        other => panic!("unexpected call: {other:?}"),
+    };
+
+    let airport = Airport {
+        icao: "EGLL".to_string(),
+        lat: 51.4775,
+        lon: -0.461389,
+        alt: 25.0,
+    };
+    simulator.push_airport_list(request_id, 0, 2, &[airport.clone(), airport.clone()]);
+    simulator.push_airport_list(request_id, 1, 2, &[airport]);
+
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::AirportList(airports))) => {
+            assert_eq!(airports.len(), 2);
+            assert_eq!(airports[0].icao, "EGLL");
+            assert_eq!(airports[1].lon, -0.461389);
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::AirportList(airports))) if airports.len() == 1
+    ));
+
+    // the request is transient, so a new one can be made after the last entry has been received
+    client
+        .request_facilities_list(FacilityType::Airport)
+        .unwrap();
+}
+
+#[test]
+fn exception() {
+    let (simulator, mut client) = new_client();
+
+    simulator.push_exception(7, 3, 0);
+
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Err(SimConnectError::SimConnectException(7))
+    ));
+}
+
+/// A raw `SIMCONNECT_RECV` message with the given ID and body.
+fn raw_message(recv_id: u32, body: &[u8]) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(&(12 + body.len() as u32).to_le_bytes());
+    message.extend_from_slice(&0u32.to_le_bytes());
+    message.extend_from_slice(&recv_id.to_le_bytes());
+    message.extend_from_slice(body);
+    message
+}
+
+#[test]
+fn truncated_messages() {
+    let (simulator, mut client) = new_client();
+
+    // SIMCONNECT_RECV_EVENT without its fields
+    simulator.push_raw(raw_message(4, &[]));
+    // SIMCONNECT_RECV_SYSTEM_STATE without its string
+    simulator.push_raw(raw_message(15, &[0; 12]));
+    // SIMCONNECT_RECV_SIMOBJECT_DATA cut short in the header
+    simulator.push_raw(raw_message(8, &[0; 8]));
+    // SIMCONNECT_RECV_VOR_LIST cut short in the header
+    simulator.push_raw(raw_message(19, &[0; 4]));
+    // SIMCONNECT_RECV_AIRPORT_LIST announcing 3 airports, but holding only 1
+    client
+        .request_facilities_list(FacilityType::Airport)
+        .unwrap();
+    let request_id = match simulator.calls().last() {
+        Some(MockCall::RequestFacilitiesList { request_id, .. }) => *request_id,
+        other => panic!("unexpected call: {other:?}"),
+    };
+    let mut list = Vec::new();
+    for value in [request_id, 3, 0, 1] {
+        list.extend_from_slice(&value.to_le_bytes());
+    }
+    list.extend_from_slice(&[0; 33]);
+    simulator.push_raw(raw_message(18, &list));
+
+    // shorter than the SIMCONNECT_RECV header
+    simulator.push_raw(vec![0; 8]);
+
+    for _ in 0..6 {
+        assert!(matches!(
+            client.get_next_dispatch(),
+            Err(SimConnectError::UnexpectedError(_))
+        ));
+    }
+
+    // the malformed last entry of the list doesn't complete the request
+    assert!(matches!(
+        client.request_facilities_list(FacilityType::Airport),
+        Err(SimConnectError::ObjectAlreadyRegistered(_))
+    ));
+
+    // the next messages are still received
+    simulator.push_quit();
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Ok(Some(Notification::Quit))
+    ));
+}
+
+#[test]
+fn wait_next_dispatch() {
+    let (simulator, mut client) = new_client();
+
+    let start = Instant::now();
+    assert!(matches!(
+        client.wait_next_dispatch(Duration::from_millis(50)),
+        Ok(None)
+    ));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    let pusher = simulator.clone();
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        pusher.push_open();
+    });
+
+    assert!(matches!(
+        client.wait_next_dispatch(Duration::from_secs(5)),
+        Ok(Some(Notification::Open))
+    ));
+    handle.join().unwrap();
+
+    // data of objects that are not registered is skipped without giving up on the wait
+    simulator.push_sim_object_data(42, &airplane_data_bytes("Cessna", 0.0));
+    simulator.push_quit();
+
+    assert!(matches!(
+        client.wait_next_dispatch(Duration::from_secs(5)),
+        Ok(Some(Notification::Quit))
+    ));
+}
+
+#[test]
+fn close_on_drop() {
+    let (simulator, client) = new_client();
+
+    assert!(!simulator.is_closed());
+    drop(client);
+    assert!(simulator.is_closed());
+}
+
+#[test]
+fn dispatcher() {
+    let (simulator, client) = new_client();
+    let received = Rc::new(RefCell::new(Vec::new()));
+
+    simulator.push_open();
+    // the first registered object gets request ID 0
+    simulator.push_sim_object_data(0, &airplane_data_bytes("Cessna 172", 1500.5));
+    simulator.push_system_event(SystemEventRequest::Pause, 1);
+    simulator.push_system_event(SystemEventRequest::Sim, 1);
+    simulator.push_client_event(ClientEventRequest::Throttle1Set, 8000);
+    simulator.push_exception(1, 0, 0);
+    simulator.push_quit();
+
+    let log = |received: &Rc<RefCell<Vec<String>>>| {
+        let received = received.clone();
+        move |entry: String| received.borrow_mut().push(entry)
+    };
+
+    let mut dispatcher = Dispatcher::new(client)
+        .on_open({
+            let log = log(&received);
+            move |client| {
+                log("open".to_string());
+                client.register_object::<AirplaneData>()?.detach();
+                Ok(())
+            }
+        })
+        .on_object({
+            let log = log(&received);
+            move |_, data: AirplaneData| {
+                log(format!("object {}", data.alt));
+                Ok(())
+            }
+        })
+        .on_system_event(SystemEventRequest::Pause, {
+            let log = log(&received);
+            move |_, event| {
+                log(format!("{event:?}"));
+                Ok(())
+            }
+        })
+        .on_client_event(ClientEventRequest::Throttle1Set, {
+            let log = log(&received);
+            move |_, event| {
+                log(format!("{event:?}"));
+                Ok(())
+            }
+        })
+        .on_exception({
+            let log = log(&received);
+            move |_, exception| {
+                log(format!("exception {exception}"));
+                Ok(())
+            }
+        })
+        .on_quit({
+            let log = log(&received);
+            move |_| {
+                log("quit".to_string());
+                Ok(())
+            }
+        });
+
+    dispatcher.run().unwrap();
+
+    assert_eq!(
+        *received.borrow(),
+        vec![
+            "open",
+            "object 1500.5",
+            "Pause { state: true }",
+            "Throttle1Set { value: 8000 }",
+            "exception 1",
+            "quit",
+        ]
+    );
+}
+
+#[test]
+fn dispatcher_object_errors() {
+    let (simulator, client) = new_client();
+    let received = Rc::new(RefCell::new(Vec::new()));
+
+    simulator.push_open();
+    // `FlightData` gets request ID 0 and `AirplaneData` request ID 1
+    simulator.push_sim_object_data(1, &airplane_data_bytes("Cessna 172", 1500.5));
+    // the variable-length string is not NUL terminated
+    simulator.push_sim_object_data(0, b"Cessna Skyhawk");
+
+    let mut dispatcher = Dispatcher::new(client)
+        .on_open(|client| {
+            client.register_object::<FlightData>()?.detach();
+            client.register_object::<AirplaneData>()?.detach();
+            Ok(())
+        })
+        .on_object({
+            let received = received.clone();
+            move |_, data: AirplaneData| {
+                received.borrow_mut().push(format!("object {}", data.alt));
+                Ok(())
+            }
+        })
+        .on_object(|_, _: FlightData| panic!("the data cannot be decoded"));
+
+    // the objects of other structs are ignored, but the errors decoding the data are not
+    assert!(matches!(
+        dispatcher.run(),
+        Err(SimConnectError::UnexpectedError(_))
+    ));
+    assert_eq!(*received.borrow(), vec!["object 1500.5"]);
+}
+
+#[test]
+fn dispatcher_objects_by_type() {
+    let (simulator, client) = new_client();
+    let received = Rc::new(RefCell::new(Vec::new()));
+
+    simulator.push_open();
+    // `AirplaneData` gets request ID 0 and `FlightData` request ID 1
+    simulator.push_sim_object_data_by_type(
+        0,
+        ObjectId(1),
+        1,
+        1,
+        &airplane_data_bytes("Cessna 172", 1500.5),
+    );
+    simulator.push_sim_object_data_by_type(1, ObjectId(0), 0, 0, &[]);
+    simulator.push_quit();
+
+    let log = |received: &Rc<RefCell<Vec<String>>>| {
+        let received = received.clone();
+        move |entry: String| received.borrow_mut().push(entry)
+    };
+
+    let mut dispatcher = Dispatcher::new(client)
+        .on_open(|client| {
+            client.request_data_by_type::<AirplaneData>(10_000, SimObjectType::Aircraft)?;
+            client.request_data_by_type::<FlightData>(10_000, SimObjectType::Aircraft)?;
+            Ok(())
+        })
+        .on_objects_by_type({
+            let log = log(&received);
+            move |_, objects: Vec<(ObjectId, AirplaneData)>| {
+                log(format!("airplanes {}", objects.len()));
+                Ok(())
+            }
+        })
+        .on_objects_by_type({
+            let log = log(&received);
+            move |_, objects: Vec<(ObjectId, FlightData)>| {
+                log(format!("flights {}", objects.len()));
+                Ok(())
+            }
+        });
+
+    dispatcher.run().unwrap();
+
+    // the empty answer only reaches the handler of its own struct
+    assert_eq!(*received.borrow(), vec!["airplanes 1", "flights 0"]);
+}
+
+#[test]
+fn dispatcher_run_until() {
+    let (simulator, client) = new_client();
+    let count = Rc::new(Cell::new(0));
+
+    for _ in 0..3 {
+        simulator.push_system_event(SystemEventRequest::OneSecond, 0);
+    }
+    simulator.push_exception(1, 0, 0);
+
+    let mut dispatcher = Dispatcher::new(client).on_system_event(SystemEventRequest::OneSecond, {
+        let count = count.clone();
+        move |_, _| {
+            count.set(count.get() + 1);
+            Ok(())
+        }
+    });
+
+    dispatcher.run_until(|| count.get() == 2).unwrap();
+    assert_eq!(count.get(), 2);
+
+    // without an exception handler, exceptions stop the dispatcher
+    assert!(matches!(
+        dispatcher.run(),
+        Err(SimConnectError::SimConnectException(1))
+    ));
+    assert_eq!(count.get(), 3);
+}
+
+#[test]
+fn multiple_registrations() {
+    let (simulator, mut client) = new_client();
+
+    let every_second = client.register_object::<AirplaneData>().unwrap();
+    let every_frame = client
+        .register_object_with_period::<AirplaneData>(Period::SimFrame, Condition::None, 0)
+        .unwrap();
+    assert_ne!(every_second.request_id(), every_frame.request_id());
+
+    let periods = simulator
+        .calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            MockCall::RequestDataOnSimObject {
+                request_id, period, ..
+            } => Some((request_id, period)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        periods,
+        vec![
+            (every_second.request_id(), i32::from(Period::Second)),
+            (every_frame.request_id(), i32::from(Period::SimFrame)),
+        ]
+    );
+
+    simulator.push_sim_object_data(
+        every_frame.request_id(),
+        &airplane_data_bytes("Cessna 172", 1500.5),
+    );
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => {
+            assert_eq!(object.request_id(), every_frame.request_id());
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+    assert!(every_second.try_recv().is_none());
+    assert_eq!(every_frame.try_recv().unwrap().alt, 1500.5);
+
+    let request_ids = vec![every_second.detach(), every_frame.detach()];
+    assert_eq!(
+        client.unregister_object::<AirplaneData>().unwrap(),
+        request_ids
+    );
+}
+
+#[test]
+fn register_object_for() {
+    #[derive(Debug, Clone, SimConnectObject)]
+    #[simconnect(period = "second", object_id = 3)]
+    #[allow(dead_code)]
+    struct OtherAircraft {
+        #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+        alt: f64,
+    }
+
+    let (simulator, mut client) = new_client();
+
+    let user = client.register_object::<AirplaneData>().unwrap();
+    let traffic = client
+        .register_object_for::<AirplaneData>(ObjectId(7))
+        .unwrap();
+    let other = client.register_object::<OtherAircraft>().unwrap();
+
+    let object_ids = simulator
+        .calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            MockCall::RequestDataOnSimObject {
+                request_id,
+                object_id,
+                ..
+            } => Some((request_id, object_id)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        object_ids,
+        vec![
+            (user.request_id(), 0),
+            (traffic.request_id(), 7),
+            (other.request_id(), 3),
+        ]
+    );
+
+    // requesting the data again uses the given arguments, not the ones of the registration
+    client
+        .request_data_on_sim_object_for(
+            traffic.request_id(),
+            ObjectId(9),
+            Period::Once,
+            Condition::None,
+            0,
+        )
+        .unwrap();
+    assert!(matches!(
+        simulator.calls().last(),
+        Some(MockCall::RequestDataOnSimObject {
+            object_id: 9,
+            period: 1,
+            ..
+        })
+    ));
+
+    simulator.push_sim_object_data_for(
+        traffic.request_id(),
+        ObjectId(7),
+        &airplane_data_bytes("Cessna 172", 1500.5),
+    );
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::Object(object))) => {
+            assert_eq!(object.request_id(), traffic.request_id());
+            assert_eq!(object.object_id(), ObjectId(7));
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+}
+
+#[test]
+fn request_data_by_type() {
+    let (simulator, mut client) = new_client();
+
+    let request_id = client
+        .request_data_by_type::<AirplaneData>(10_000, SimObjectType::Aircraft)
+        .unwrap();
+
+    assert!(simulator
+        .calls()
+        .contains(&MockCall::RequestDataOnSimObjectType {
+            request_id,
+            define_id: request_id,
+            radius_meters: 10_000,
+            object_type: 2,
+        }));
+    assert!(!simulator
+        .calls()
+        .iter()
+        .any(|call| matches!(call, MockCall::RequestDataOnSimObject { .. })));
+
+    simulator.push_sim_object_data_by_type(
+        request_id,
+        ObjectId(1),
+        1,
+        2,
+        &airplane_data_bytes("Cessna 172", 1500.5),
+    );
+    simulator.push_sim_object_data_by_type(
+        request_id,
+        ObjectId(9),
+        2,
+        2,
+        &airplane_data_bytes("Airbus A320", 35000.0),
+    );
+
+    // the first entry is held back until all of them are received
+    assert!(matches!(client.get_next_dispatch(), Ok(None)));
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::ObjectsByType { objects, .. })) => {
+            let objects = objects
+                .iter()
+                .map(|(object_id, object)| {
+                    let data = AirplaneData::try_from(object).unwrap();
+                    (*object_id, data.title)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                objects,
+                vec![
+                    (ObjectId(1), "Cessna 172".to_string()),
+                    (ObjectId(9), "Airbus A320".to_string()),
+                ]
+            );
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+
+    // the request is one-off
+    assert!(simulator.calls().contains(&MockCall::ClearDataDefinition {
+        define_id: request_id
+    }));
+
+    // no objects are in range
+    let request_id = client
+        .request_data_by_type::<AirplaneData>(10_000, SimObjectType::Aircraft)
+        .unwrap();
+    simulator.push_sim_object_data_by_type(request_id, ObjectId(0), 0, 0, &[]);
+    match client.get_next_dispatch() {
+        Ok(Some(Notification::ObjectsByType {
+            request_id: answered_request_id,
+            type_name,
+            objects,
+        })) => {
+            assert_eq!(answered_request_id, request_id);
+            assert_eq!(type_name, std::any::type_name::<AirplaneData>());
+            assert!(objects.is_empty());
+        }
+        other => panic!("unexpected notification: {other:?}"),
+    }
+}
+
+#[test]
+fn set_data_on_sim_object() {
+    #[derive(Debug, Clone, SimConnectObject)]
+    #[simconnect(period = "second", settable)]
+    struct Fuel {
+        #[simconnect(name = "ATC ID")]
+        atc_id: String,
+        #[simconnect(name = "FUEL TANK CENTER QUANTITY", unit = "gallons")]
+        center: f64,
+    }
+
+    let (simulator, mut client) = new_client();
+
+    for center in [10.0, 20.5] {
+        client
+            .set_data_on_sim_object(
+                ObjectId(3),
+                &Fuel {
+                    atc_id: "N172SP".to_string(),
+                    center,
+                },
+            )
+            .unwrap();
+    }
+
+    let calls = simulator.calls();
+
+    // the data definition is registered once, without requesting the data
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|call| matches!(call, MockCall::AddToDataDefinition { .. }))
+            .count(),
+        2
+    );
+    assert!(!calls
+        .iter()
+        .any(|call| matches!(call, MockCall::RequestDataOnSimObject { .. })));
+
+    let data = calls
+        .iter()
+        .filter_map(|call| match call {
+            MockCall::SetDataOnSimObject {
+                object_id,
+                unit_size,
+                data,
+                ..
+            } => {
+                assert_eq!(*object_id, 3);
+                assert_eq!(*unit_size as usize, data.len());
+                Some(data.clone())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[1].len(), 256 + 8);
+    assert_eq!(&data[1][..7], b"N172SP\0");
+    assert_eq!(data[1][256..], 20.5f64.to_le_bytes());
+
+    // the data definition of the set data is not one of the registrations of the object
+    assert!(matches!(
+        client.unregister_object::<Fuel>(),
+        Err(SimConnectError::ObjectNotRegistered(_))
+    ));
+
+    let request_id = client.register_object::<Fuel>().unwrap().detach();
+    assert_eq!(client.unregister_object::<Fuel>().unwrap(), [request_id]);
+
+    // the data definition of the set data can still be requested explicitly
+    let define_id = calls
+        .iter()
+        .find_map(|call| match call {
+            MockCall::SetDataOnSimObject { define_id, .. } => Some(*define_id),
+            _ => None,
+        })
+        .unwrap();
+    client
+        .request_data_on_sim_object(define_id, Period::Once, Condition::None, 0)
+        .unwrap();
+    assert!(matches!(
+        simulator.calls().last(),
+        Some(MockCall::RequestDataOnSimObject { request_id, .. }) if *request_id == define_id
+    ));
+
+    client
+        .set_data_on_sim_object(
+            ObjectId(3),
+            &Fuel {
+                atc_id: "N172SP".to_string(),
+                center: 30.0,
+            },
+        )
+        .unwrap();
+
+    let calls = simulator.calls();
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|call| matches!(call, MockCall::ClearDataDefinition { .. }))
+            .count(),
+        1
+    );
+    // the data definition of the set data is kept, so only the registration has added entries since
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|call| matches!(call, MockCall::AddToDataDefinition { .. }))
+            .count(),
+        4
+    );
+}
+
+#[test]
+fn failed_registrations_clear_their_data_definition() {
+    #[derive(Debug, Clone)]
+    struct Broken;
+
+    impl SimConnectObjectExt for Broken {
+        fn define(client: &mut SimConnect, id: u32) -> Result<(), SimConnectError> {
+            client.add_to_data_definition(id, "PLANE ALTITUDE", "feet", DataType::Float64)?;
+
+            Err(SimConnectError::UnexpectedError("broken".to_string()))
+        }
+    }
+
+    impl SimConnectSettableObjectExt for Broken {
+        fn to_data(&self) -> Vec<u8> {
+            Vec::new()
+        }
+    }
+
+    impl TryFrom<&Object> for Broken {
+        type Error = SimConnectError;
+
+        fn try_from(_: &Object) -> Result<Self, Self::Error> {
+            Ok(Broken)
+        }
+    }
+
+    let (simulator, mut client) = new_client();
+
+    assert!(client.register_object::<Broken>().is_err());
+    assert!(client
+        .request_data_by_type::<Broken>(10_000, SimObjectType::Aircraft)
+        .is_err());
+    assert!(client
+        .set_data_on_sim_object(ObjectId::USER, &Broken)
+        .is_err());
+
+    let calls = simulator.calls();
+
+    // every partial data definition is cleared, and nothing is requested or set
+    let defined = calls
+        .iter()
+        .filter_map(|call| match call {
+            MockCall::AddToDataDefinition { define_id, .. } => Some(*define_id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let cleared = calls
+        .iter()
+        .filter_map(|call| match call {
+            MockCall::ClearDataDefinition { define_id } => Some(*define_id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(defined.len(), 3);
+    assert_eq!(defined, cleared);
+    assert_eq!(calls.len(), 6);
+
+    assert!(matches!(
+        client.unregister_object::<Broken>(),
+        Err(SimConnectError::ObjectNotRegistered(_))
+    ));
+}
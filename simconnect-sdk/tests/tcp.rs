@@ -0,0 +1,181 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use simconnect_sdk::{Notification, SimConnect, SimConnectError, SimConnectObject, TcpBackend};
+
+#[derive(Debug, Clone, SimConnectObject)]
+#[simconnect(period = "second")]
+#[allow(dead_code)]
+struct AirplaneData {
+    #[simconnect(name = "PLANE ALTITUDE", unit = "feet")]
+    alt: f64,
+}
+
+/// Read a client packet and return its ID and arguments.
+fn read_packet(stream: &mut TcpStream) -> (u32, Vec<u8>) {
+    let mut header = [0; 16];
+    stream.read_exact(&mut header).unwrap();
+
+    let size = u32::from_le_bytes(header[0..4].try_into().unwrap()) as usize;
+    let packet_type = u32::from_le_bytes(header[8..12].try_into().unwrap());
+    assert_eq!(packet_type & 0xF000_0000, 0xF000_0000);
+
+    let mut arguments = vec![0; size - header.len()];
+    stream.read_exact(&mut arguments).unwrap();
+
+    (packet_type & 0x0FFF_FFFF, arguments)
+}
+
+fn read_string(arguments: &[u8]) -> String {
+    let end = arguments.iter().position(|c| *c == 0).unwrap();
+    String::from_utf8(arguments[..end].to_vec()).unwrap()
+}
+
+fn recv_open() -> Vec<u8> {
+    // SIMCONNECT_RECV_OPEN: the header, the application name and ten DWORDs
+    let size = 12 + 256 + 10 * 4;
+
+    let mut message = Vec::with_capacity(size);
+    message.extend_from_slice(&(size as u32).to_le_bytes());
+    message.extend_from_slice(&4u32.to_le_bytes());
+    message.extend_from_slice(&2u32.to_le_bytes());
+    message.resize(size, 0);
+    message
+}
+
+/// A `SIMCONNECT_RECV` message with the given ID and body.
+fn recv_message(recv_id: u32, body: &[u8]) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(&(12 + body.len() as u32).to_le_bytes());
+    message.extend_from_slice(&4u32.to_le_bytes());
+    message.extend_from_slice(&recv_id.to_le_bytes());
+    message.extend_from_slice(body);
+    message
+}
+
+fn next_dispatch(client: &mut SimConnect) -> Result<Notification, SimConnectError> {
+    let start = Instant::now();
+
+    loop {
+        if let Some(notification) = client.get_next_dispatch()? {
+            return Ok(notification);
+        }
+
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn next_notification(client: &mut SimConnect) -> Notification {
+    next_dispatch(client).unwrap()
+}
+
+#[test]
+fn open_and_register_object() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+
+        let (id, arguments) = read_packet(&mut stream);
+        assert_eq!(id, 0x01);
+        assert_eq!(read_string(&arguments), "TCP test");
+
+        stream.write_all(&recv_open()).unwrap();
+
+        let (id, arguments) = read_packet(&mut stream);
+        assert_eq!(id, 0x0C);
+        assert_eq!(read_string(&arguments[4..]), "PLANE ALTITUDE");
+        assert_eq!(read_string(&arguments[260..]), "feet");
+
+        let (id, _) = read_packet(&mut stream);
+        assert_eq!(id, 0x0E);
+    });
+
+    let backend = TcpBackend::connect("TCP test", addr).unwrap();
+    let mut client = SimConnect::with_backend(backend);
+
+    assert!(matches!(next_notification(&mut client), Notification::Open));
+
+    let _subscription = client.register_object::<AirplaneData>().unwrap();
+
+    server.join().unwrap();
+}
+
+#[test]
+fn truncated_messages() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+
+        read_packet(&mut stream);
+        stream.write_all(&recv_open()).unwrap();
+
+        // SIMCONNECT_RECV_SIMOBJECT_DATA cut short in the header
+        stream.write_all(&recv_message(8, &[0; 8])).unwrap();
+
+        // SIMCONNECT_RECV_AIRPORT_LIST announcing 2 airports, but holding none
+        let mut list = Vec::new();
+        for value in [1u32, 2, 0, 1] {
+            list.extend_from_slice(&value.to_le_bytes());
+        }
+        stream.write_all(&recv_message(18, &list)).unwrap();
+
+        // SIMCONNECT_RECV_QUIT
+        stream.write_all(&recv_message(3, &[])).unwrap();
+    });
+
+    let backend = TcpBackend::connect("TCP test", addr).unwrap();
+    let mut client = SimConnect::with_backend(backend);
+
+    assert!(matches!(next_notification(&mut client), Notification::Open));
+    assert!(matches!(
+        next_dispatch(&mut client),
+        Err(SimConnectError::UnexpectedError(_))
+    ));
+    assert!(matches!(
+        next_dispatch(&mut client),
+        Err(SimConnectError::UnexpectedError(_))
+    ));
+    assert!(matches!(next_notification(&mut client), Notification::Quit));
+
+    server.join().unwrap();
+}
+
+#[test]
+fn lost_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+
+        read_packet(&mut stream);
+        stream.write_all(&recv_open()).unwrap();
+        // the connection is dropped without a SIMCONNECT_RECV_QUIT
+    });
+
+    let backend = TcpBackend::connect("TCP test", addr).unwrap();
+    let mut client = SimConnect::with_backend(backend);
+
+    assert!(matches!(next_notification(&mut client), Notification::Open));
+    server.join().unwrap();
+
+    // the wait ends as soon as the connection is lost
+    let start = Instant::now();
+    assert!(matches!(
+        client.wait_next_dispatch(Duration::from_secs(5)),
+        Err(SimConnectError::Disconnected)
+    ));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // and the connection stays lost
+    assert!(matches!(
+        client.get_next_dispatch(),
+        Err(SimConnectError::Disconnected)
+    ));
+}